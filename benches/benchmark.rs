@@ -1,8 +1,37 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 use nprint_rs::Nprint;
 use nprint_rs::ProtocolType;
 
+/// System allocator counting its allocations, to report the allocations made per packet.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns the number of allocations made by a closure.
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
 fn benchmark(c: &mut Criterion) {
     let raw_packet = vec![
         0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x81, 0x00, 0x20, 0x45, 0x08,
@@ -14,6 +43,29 @@ fn benchmark(c: &mut Criterion) {
         0x00, 0x00, 0x02, 0x04, 0x05, 0xb4, 0x01, 0x03, 0x03, 0x02, 0x01, 0x01, 0x04, 0x02,
     ];
 
+    // Ethernet, outer IPv4 carrying GRE with a key, then an inner IPv4 and TCP packet
+    let mut tunneled_packet = vec![0x0; 12];
+    tunneled_packet.extend([0x08, 0x00]);
+    tunneled_packet.extend([
+        0x45, 0x00, 0x00, 0x58, 0x00, 0x01, 0x00, 0x00, 0x40, 0x2f, 0x00, 0x00, 0x0a, 0x00, 0x00,
+        0x01, 0x0a, 0x00, 0x00, 0x02, 0x20, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x2a, 0x45, 0x00,
+        0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25, 0xc6,
+        0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00, 0x00, 0x00,
+        0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4, 0x04, 0x02, 0x08,
+        0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x03, 0x07,
+    ]);
+
+    // The layers borrow from the packet, popping them allocates nothing
+    for (name, packet) in [("VLAN", &raw_packet), ("GRE", &tunneled_packet)] {
+        let count = allocations(|| {
+            black_box(Nprint::new(
+                black_box(packet),
+                vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp],
+            ));
+        });
+        println!("{name} packet: {count} allocations per Nprint::new");
+    }
+
     c.bench_function("new Nprint", |b| {
         b.iter(|| {
             Nprint::new(
@@ -26,6 +78,18 @@ fn benchmark(c: &mut Criterion) {
             );
         })
     });
+    c.bench_function("new Nprint tunneled", |b| {
+        b.iter(|| {
+            Nprint::new(
+                black_box(&tunneled_packet),
+                black_box(vec![
+                    ProtocolType::Gre,
+                    ProtocolType::Ipv4,
+                    ProtocolType::Tcp,
+                ]),
+            );
+        })
+    });
     c.bench_function("Add 2 packet Nprint", |b| {
         b.iter(|| {
             let mut nprint = Nprint::new(
@@ -71,6 +135,14 @@ fn benchmark(c: &mut Criterion) {
             }
         })
     });
+
+    c.bench_function("Repeated get_headers", |b| {
+        let nprint = Nprint::new(
+            &raw_packet,
+            vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp],
+        );
+        b.iter(|| black_box(nprint.get_headers().len()))
+    });
 }
 
 criterion_group!(benches, benchmark);
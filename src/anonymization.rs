@@ -0,0 +1,176 @@
+//! Sensitive fields removed by the anonymization, each one mapped to its bits in a header.
+use crate::protocols::ipv4::{IPV4_DESTINATION, IPV4_ID, IPV4_SOURCE};
+use crate::ProtocolType;
+use std::ops::RangeInclusive;
+
+/// A sensitive field that can be removed from the parsed headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnonField {
+    /// Destination MAC address of the Ethernet header.
+    EthernetDst,
+    /// Source MAC address of the Ethernet header.
+    EthernetSrc,
+    /// Sender hardware address of an ARP message.
+    ArpSenderMac,
+    /// Sender protocol address of an ARP message.
+    ArpSenderIp,
+    /// Target hardware address of an ARP message.
+    ArpTargetMac,
+    /// Target protocol address of an ARP message.
+    ArpTargetIp,
+    /// Identification of the IPv4 header, kept by default.
+    Ipv4Id,
+    /// Source address of the IPv4 header.
+    Ipv4Src,
+    /// Destination address of the IPv4 header.
+    Ipv4Dst,
+    /// Source port of the TCP header.
+    TcpSrcPort,
+    /// Destination port of the TCP header.
+    TcpDstPort,
+    /// Source port of the UDP header.
+    UdpSrcPort,
+    /// Destination port of the UDP header.
+    UdpDstPort,
+    /// Source port of the SCTP common header.
+    SctpSrcPort,
+    /// Destination port of the SCTP common header.
+    SctpDstPort,
+    /// Client, your, server and gateway IP addresses of a DHCP message.
+    DhcpAddresses,
+    /// Client hardware address of a DHCP message.
+    DhcpClientMac,
+    /// Destination connection ID of a QUIC long header.
+    QuicDcid,
+    /// Source connection ID of a QUIC long header.
+    QuicScid,
+    /// Tunnel endpoint identifier of a GTP-U header.
+    GtpTeid,
+}
+
+impl AnonField {
+    /// Fields removed by `Nprint::anonymize()`, every field but `Ipv4Id`.
+    pub const DEFAULT: [AnonField; 19] = [
+        AnonField::EthernetDst,
+        AnonField::EthernetSrc,
+        AnonField::ArpSenderMac,
+        AnonField::ArpSenderIp,
+        AnonField::ArpTargetMac,
+        AnonField::ArpTargetIp,
+        AnonField::Ipv4Src,
+        AnonField::Ipv4Dst,
+        AnonField::TcpSrcPort,
+        AnonField::TcpDstPort,
+        AnonField::UdpSrcPort,
+        AnonField::UdpDstPort,
+        AnonField::SctpSrcPort,
+        AnonField::SctpDstPort,
+        AnonField::DhcpAddresses,
+        AnonField::DhcpClientMac,
+        AnonField::QuicDcid,
+        AnonField::QuicScid,
+        AnonField::GtpTeid,
+    ];
+
+    /// Returns the protocol whose header holds the field.
+    pub fn protocol(&self) -> ProtocolType {
+        match self {
+            AnonField::EthernetDst | AnonField::EthernetSrc => ProtocolType::Ethernet,
+            AnonField::ArpSenderMac
+            | AnonField::ArpSenderIp
+            | AnonField::ArpTargetMac
+            | AnonField::ArpTargetIp => ProtocolType::Arp,
+            AnonField::Ipv4Id | AnonField::Ipv4Src | AnonField::Ipv4Dst => ProtocolType::Ipv4,
+            AnonField::TcpSrcPort | AnonField::TcpDstPort => ProtocolType::Tcp,
+            AnonField::UdpSrcPort | AnonField::UdpDstPort => ProtocolType::Udp,
+            AnonField::SctpSrcPort | AnonField::SctpDstPort => ProtocolType::Sctp,
+            AnonField::DhcpAddresses | AnonField::DhcpClientMac => ProtocolType::Dhcp,
+            AnonField::QuicDcid | AnonField::QuicScid => ProtocolType::Quic,
+            AnonField::GtpTeid => ProtocolType::Gtp,
+        }
+    }
+
+    /// Returns the bits of the field in the data of its protocol, bounds included.
+    pub(crate) fn bits(&self) -> RangeInclusive<usize> {
+        match self {
+            AnonField::EthernetDst => 0..=47,
+            AnonField::EthernetSrc => 48..=95,
+            AnonField::ArpSenderMac => 64..=111,
+            AnonField::ArpSenderIp => 112..=143,
+            AnonField::ArpTargetMac => 144..=191,
+            AnonField::ArpTargetIp => 192..=223,
+            AnonField::Ipv4Id => IPV4_ID.start..=IPV4_ID.end - 1,
+            AnonField::Ipv4Src => IPV4_SOURCE.start..=IPV4_SOURCE.end - 1,
+            AnonField::Ipv4Dst => IPV4_DESTINATION.start..=IPV4_DESTINATION.end - 1,
+            AnonField::TcpSrcPort | AnonField::UdpSrcPort | AnonField::SctpSrcPort => 0..=15,
+            AnonField::TcpDstPort | AnonField::UdpDstPort | AnonField::SctpDstPort => 16..=31,
+            AnonField::DhcpAddresses => 96..=223,
+            AnonField::DhcpClientMac => 224..=351,
+            AnonField::QuicDcid => 45..=204,
+            AnonField::QuicScid => 213..=372,
+            AnonField::GtpTeid => 32..=63,
+        }
+    }
+}
+
+#[cfg(test)]
+mod anonymization_tests {
+    use super::*;
+    use crate::protocols::arp::ArpHeader;
+    use crate::protocols::dhcp::DhcpHeader;
+    use crate::protocols::ethernet::EthernetHeader;
+    use crate::protocols::gtp::GtpHeader;
+    use crate::protocols::ipv4::Ipv4Header;
+    use crate::protocols::packet::PacketHeader;
+    use crate::protocols::quic::QuicHeader;
+    use crate::protocols::sctp::SctpHeader;
+    use crate::protocols::tcp::TcpHeader;
+    use crate::protocols::udp::UdpHeader;
+    use crate::protocols::util::remove;
+
+    /// Asserts that the default fields of a protocol remove the same bits as its header.
+    fn assert_default_matches<H: PacketHeader>(protocol: ProtocolType) {
+        let width = protocol.width();
+        let mut expected = vec![1.; width];
+        H::anonymize_bits(&mut expected);
+        let mut data = vec![1.; width];
+        for field in AnonField::DEFAULT
+            .iter()
+            .filter(|field| field.protocol() == protocol)
+        {
+            let bits = field.bits();
+            remove(&mut data, *bits.start(), *bits.end());
+        }
+        assert_eq!(
+            data, expected,
+            "Unexpected default fields of {:?}",
+            protocol
+        );
+    }
+
+    #[test]
+    fn test_default_fields() {
+        assert_default_matches::<EthernetHeader>(ProtocolType::Ethernet);
+        assert_default_matches::<ArpHeader>(ProtocolType::Arp);
+        assert_default_matches::<Ipv4Header>(ProtocolType::Ipv4);
+        assert_default_matches::<TcpHeader>(ProtocolType::Tcp);
+        assert_default_matches::<UdpHeader>(ProtocolType::Udp);
+        assert_default_matches::<SctpHeader>(ProtocolType::Sctp);
+        assert_default_matches::<DhcpHeader>(ProtocolType::Dhcp);
+        assert_default_matches::<QuicHeader>(ProtocolType::Quic);
+        assert_default_matches::<GtpHeader>(ProtocolType::Gtp);
+        assert!(!AnonField::DEFAULT.contains(&AnonField::Ipv4Id));
+    }
+
+    #[test]
+    fn test_field_bits_in_header() {
+        for field in AnonField::DEFAULT.iter().chain([&AnonField::Ipv4Id]) {
+            assert!(
+                *field.bits().end() < field.protocol().width(),
+                "{:?} beyond its header",
+                field
+            );
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Conversion to `ndarray`, available with the `ndarray` feature.
+use crate::Nprint;
+use ndarray::Array2;
+
+impl Nprint {
+    /// Returns the nprint values as an `Array2` of shape `[max_packets, bits_per_packet]`.
+    ///
+    /// Rows are built by `print_matrix()`: extra packets are dropped and missing
+    /// packets are filled with the configured fill value.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_packets` - Number of rows of the array.
+    pub fn to_array2(&self, max_packets: usize) -> Array2<f32> {
+        let width = self.bits_per_packet();
+        let values = self.print_matrix(max_packets).concat();
+        Array2::from_shape_vec((max_packets, width), values)
+            .expect("print_matrix() always returns max_packets rows of the header width")
+    }
+}
+
+#[cfg(test)]
+mod array_tests {
+    use crate::{Nprint, ProtocolType};
+
+    #[test]
+    fn test_to_array2() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::Ipv4, ProtocolType::Udp]);
+        nprint.add(&raw_packet);
+        let array = nprint.to_array2(3);
+        assert_eq!(array.shape(), [3, 544], "Wrong array shape");
+        assert_eq!(array[[0, 1]], 1., "Expected IPv4 version bit");
+        assert_eq!(array[[1, 480]], 1., "Expected UDP source port bit");
+        assert_eq!(array[[1, 160]], -1., "Expected options padding");
+        assert!(
+            array.row(2).iter().all(|bit| *bit == -1.),
+            "Expected padding row"
+        );
+        assert_eq!(nprint.to_array2(1).shape(), [1, 544], "Expected truncation");
+    }
+}
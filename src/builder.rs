@@ -0,0 +1,311 @@
+use crate::{
+    BitOrder, FillValue, Layout, LinkLayer, Nprint, NprintConfig, PayloadSource, ProtocolType,
+    TcpFlagsEncoding, TruncationPolicy,
+};
+
+/// Builder gathering the configuration of an `Nprint` before parsing its first packet.
+///
+/// # Example
+///
+/// ```
+/// use nprint_rs::{FillValue, NprintBuilder, ProtocolType};
+///
+/// let nprint = NprintBuilder::new()
+///     .protocols(vec![ProtocolType::Ipv4, ProtocolType::Tcp])
+///     .fill_value(FillValue::Zero)
+///     .max_packets(10)
+///     .anonymize(true)
+///     .build_from(&[0x0]);
+/// assert_eq!(nprint.count(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct NprintBuilder {
+    /// Ordered list of Protocol to parse.
+    protocols: Vec<ProtocolType>,
+    /// Configuration of the built Nprint.
+    config: NprintConfig,
+}
+
+impl NprintBuilder {
+    /// Creates a builder with no protocol and the default configuration.
+    pub fn new() -> NprintBuilder {
+        NprintBuilder::default()
+    }
+
+    /// Sets the protocol stack to parse.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
+    pub fn protocols(mut self, protocols: Vec<ProtocolType>) -> NprintBuilder {
+        self.protocols = protocols;
+        self
+    }
+
+    /// Sets the value encoding absent bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `fill_value` - A `FillValue`, `FillValue::MinusOne` by default.
+    pub fn fill_value(mut self, fill_value: FillValue) -> NprintBuilder {
+        self.config.fill_value = fill_value;
+        self
+    }
+
+    /// Sets the maximum number of packets stored, see `truncation` for further packets.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_packets` - Maximum number of packets.
+    pub fn max_packets(mut self, max_packets: usize) -> NprintBuilder {
+        self.config.max_packets = Some(max_packets);
+        self
+    }
+
+    /// Sets which packets are kept once `max_packets` is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `truncation` - A `TruncationPolicy`, `TruncationPolicy::DropNewest` by default.
+    pub fn truncation(mut self, truncation: TruncationPolicy) -> NprintBuilder {
+        self.config.truncation = truncation;
+        self
+    }
+
+    /// Sets whether `print()` is padded with filled packets up to `max_packets`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pad_packets` - `true` to always print `max_packets` packets.
+    pub fn pad_packets(mut self, pad_packets: bool) -> NprintBuilder {
+        self.config.pad_packets = pad_packets;
+        self
+    }
+
+    /// Sets whether every packet is anonymized as soon as it's parsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `anonymize` - `true` to anonymize each added packet.
+    pub fn anonymize(mut self, anonymize: bool) -> NprintBuilder {
+        self.config.anonymize = anonymize;
+        self
+    }
+
+    /// Sets whether the effective TCP window is extracted as `tcp_wsize_scaled`.
+    ///
+    /// The window size is shifted by the scale announced in the SYN of its direction, once
+    /// both directions have announced one. SYN windows and windows of flows without window
+    /// scaling are kept as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale_window` - `true` to append the 30 bits of the scaled window to the TCP header.
+    pub fn scale_window(mut self, scale_window: bool) -> NprintBuilder {
+        self.config.scale_window = scale_window;
+        self
+    }
+
+    /// Sets whether the checksums are updated to match the anonymized fields.
+    ///
+    /// Applies to the IPv4, TCP and UDP checksums, which are left untouched by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `recompute_checksums` - `true` to recompute the checksums after anonymization.
+    pub fn recompute_checksums(mut self, recompute_checksums: bool) -> NprintBuilder {
+        self.config.recompute_checksums = recompute_checksums;
+        self
+    }
+
+    /// Sets whether TCP sequence and acknowledgment numbers are rewritten as offsets.
+    ///
+    /// Offsets are relative to the first sequence number seen in each direction of the flow.
+    ///
+    /// # Arguments
+    ///
+    /// * `relative_seq` - `true` to use relative sequence numbers.
+    pub fn relative_seq(mut self, relative_seq: bool) -> NprintBuilder {
+        self.config.relative_seq = relative_seq;
+        self
+    }
+
+    /// Restricts the output to the given fields, in the order of the protocol stack.
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - Field names without their bit index, as prefixes of `get_headers()` (e.g. `tcp_wsize`).
+    pub fn select_fields(mut self, fields: &[&str]) -> NprintBuilder {
+        self.config.fields = Some(fields.iter().map(|field| field.to_string()).collect());
+        self
+    }
+
+    /// Sets the number of IPv4 option bits extracted, missing bits are filled.
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - Number of bits, 320 (the longest options) if unset.
+    pub fn ipv4_options_bits(mut self, bits: usize) -> NprintBuilder {
+        self.config.ipv4_options_bits = Some(bits);
+        self
+    }
+
+    /// Sets the number of TCP option bits extracted, missing bits are filled.
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - Number of bits, 320 (the longest options) if unset.
+    pub fn tcp_options_bits(mut self, bits: usize) -> NprintBuilder {
+        self.config.tcp_options_bits = Some(bits);
+        self
+    }
+
+    /// Sets whether the IPv4 and TCP options are extracted, see `NprintConfig::include_options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_options` - `false` to keep only the 160 fixed bits of both headers.
+    pub fn include_options(mut self, include_options: bool) -> NprintBuilder {
+        self.config.include_options = include_options;
+        self
+    }
+
+    /// Sets the number of payload bytes extracted, missing bytes are filled.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_payload_bytes` - Number of bytes, the MTU (1514 by default) if unset.
+    pub fn max_payload_bytes(mut self, max_payload_bytes: usize) -> NprintBuilder {
+        self.config.max_payload_bytes = Some(max_payload_bytes);
+        self
+    }
+
+    /// Sets the largest payload expected, longer payloads being filled as absent.
+    ///
+    /// The payload is extracted up to the MTU unless `max_payload_bytes()` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `mtu` - Number of bytes, e.g. 9000 for jumbo frames.
+    pub fn mtu(mut self, mtu: usize) -> NprintBuilder {
+        self.config.mtu = Some(mtu);
+        self
+    }
+
+    /// Sets the number of leading payload bytes skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload_offset` - Number of bytes, 0 by default.
+    pub fn payload_offset(mut self, payload_offset: usize) -> NprintBuilder {
+        self.config.payload_offset = payload_offset;
+        self
+    }
+
+    /// Sets the order of the bits extracted from every byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit_order` - Order of the bits, `BitOrder::Msb0` by default.
+    pub fn bit_order(mut self, bit_order: BitOrder) -> NprintBuilder {
+        self.config.bit_order = bit_order;
+        self
+    }
+
+    /// Sets the link layer of the parsed packets.
+    ///
+    /// # Arguments
+    ///
+    /// * `link_layer` - Link layer, `LinkLayer::Ethernet` by default.
+    pub fn link_layer(mut self, link_layer: LinkLayer) -> NprintBuilder {
+        self.config.link_layer = link_layer;
+        self
+    }
+
+    /// Sets the order of the values in `print()` and `get_headers()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - Layout of the output, `Layout::PacketMajor` by default.
+    pub fn layout(mut self, layout: Layout) -> NprintBuilder {
+        self.config.layout = layout;
+        self
+    }
+
+    /// Replaces the TCP and UDP ports with their 2-bit range (`*_range0` and `*_range1`):
+    /// well-known, registered or ephemeral. Meant as an alternative to anonymizing them.
+    ///
+    /// # Arguments
+    ///
+    /// * `port_categories` - Keep the range of the ports instead of their value, `false` by default.
+    pub fn port_categories(mut self, port_categories: bool) -> NprintBuilder {
+        self.config.port_categories = port_categories;
+        self
+    }
+
+    /// Replaces the IPv4, TCP and UDP checksums with a single validity bit (`*_cksum_valid`).
+    ///
+    /// # Arguments
+    ///
+    /// * `checksum_validity` - Verify the checksums instead of extracting them, `false` by default.
+    pub fn checksum_validity(mut self, checksum_validity: bool) -> NprintBuilder {
+        self.config.checksum_validity = checksum_validity;
+        self
+    }
+
+    /// Extracts an indicator bit before the IPv4 and TCP options (`ipv4_has_opt_0` and
+    /// `tcp_has_opt_0`), 1 if the header carries options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options_indicator` - `true` to extract the indicators, `false` by default.
+    pub fn options_indicator(mut self, options_indicator: bool) -> NprintBuilder {
+        self.config.options_indicator = options_indicator;
+        self
+    }
+
+    /// Sets the bytes extracted as the payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload_source` - The transport payload, or the whole IP payload,
+    ///   `PayloadSource::TransportPayload` by default.
+    pub fn payload_source(mut self, payload_source: PayloadSource) -> NprintBuilder {
+        self.config.payload_source = payload_source;
+        self
+    }
+
+    /// Sets the representation of the TCP flags.
+    ///
+    /// # Arguments
+    ///
+    /// * `tcp_flags` - Individual bits, a single `tcp_flags_value`, or both.
+    pub fn tcp_flags(mut self, tcp_flags: TcpFlagsEncoding) -> NprintBuilder {
+        self.config.tcp_flags = tcp_flags;
+        self
+    }
+
+    /// Sets whether `tcp_flags_value` is normalized to [0, 1].
+    ///
+    /// # Arguments
+    ///
+    /// * `normalize_tcp_flags` - `true` to divide the flags byte by 255, `false` by default.
+    pub fn normalize_tcp_flags(mut self, normalize_tcp_flags: bool) -> NprintBuilder {
+        self.config.normalize_tcp_flags = normalize_tcp_flags;
+        self
+    }
+
+    /// Builds the `Nprint` without any packet, see `Nprint::empty()`.
+    pub fn build(self) -> Nprint {
+        Nprint::empty_with_config(self.protocols, self.config)
+    }
+
+    /// Builds the `Nprint` from its first packet.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet data.
+    pub fn build_from(self, packet: &[u8]) -> Nprint {
+        Nprint::new_with_config(packet, self.protocols, self.config)
+    }
+}
@@ -0,0 +1,183 @@
+//! Reading of `.pcap` files, available with the `pcap` feature.
+use crate::protocols::link;
+use crate::{FlowTable, LinkLayer, Nprint, NprintError, ProtocolType};
+use pcap::{Capture, Linktype};
+use std::path::Path;
+use std::time::Duration;
+
+/// Length of an Ethernet II header.
+const ETHERNET_HEADER_LEN: usize = 14;
+
+impl Nprint {
+    /// Reads a pcap file and returns one `Nprint` per flow.
+    ///
+    /// Packets are grouped by their canonical 5-tuple, see `FlowTable`, and IPv4 fragments are reassembled.
+    /// Ethernet, raw IP and Linux cooked (SLL and SLL2) link-layer captures are supported,
+    /// cooked packets shorter than their header being skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the pcap file.
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
+    ///
+    /// # Returns
+    ///
+    /// The `Nprint` of every flow found in the capture, in no particular order.
+    pub fn from_pcap<P: AsRef<Path>>(
+        path: P,
+        protocols: Vec<ProtocolType>,
+    ) -> Result<Vec<Nprint>, NprintError> {
+        let mut capture = Capture::from_file(path)?;
+        let linktype = capture.get_datalink();
+        let mut table = FlowTable::new(protocols);
+        loop {
+            let packet = match capture.next_packet() {
+                Ok(packet) => packet,
+                Err(pcap::Error::NoMorePackets) => break,
+                Err(err) => return Err(err.into()),
+            };
+            let timestamp = Duration::from_secs(packet.header.ts.tv_sec as u64)
+                + Duration::from_micros(packet.header.ts.tv_usec as u64);
+            let wire_len = packet.header.len as usize;
+            match linktype {
+                Linktype::ETHERNET => table.insert_with_wire_len(packet.data, timestamp, wire_len),
+                Linktype::RAW | Linktype::IPV4 | Linktype::IPV6 => table.insert_with_wire_len(
+                    &to_ethernet(packet.data),
+                    timestamp,
+                    wire_len + ETHERNET_HEADER_LEN,
+                ),
+                Linktype::LINUX_SLL | Linktype::LINUX_SLL2 => {
+                    let (link_layer, header_len) = if linktype == Linktype::LINUX_SLL {
+                        (LinkLayer::LinuxSll, link::SLL_HEADER_LEN)
+                    } else {
+                        (LinkLayer::LinuxSll2, link::SLL2_HEADER_LEN)
+                    };
+                    if let Some(frame) = cooked_to_ethernet(packet.data, link_layer) {
+                        let wire_len = wire_len.saturating_sub(header_len) + ETHERNET_HEADER_LEN;
+                        table.insert_with_wire_len(&frame, timestamp, wire_len);
+                    }
+                }
+                Linktype(other) => return Err(NprintError::UnsupportedLinkType(other)),
+            }
+        }
+        Ok(table.into_flows().into_values().collect())
+    }
+}
+
+/// Prepends a blank Ethernet II header to a raw IP packet.
+///
+/// The ethertype is chosen from the IP version nibble.
+///
+/// # Arguments
+/// * `packet` - Raw bytes of an IP packet.
+fn to_ethernet(packet: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0; ETHERNET_HEADER_LEN];
+    match packet.first().map(|byte| byte >> 4) {
+        Some(6) => frame[12..].copy_from_slice(&[0x86, 0xdd]),
+        _ => frame[12..].copy_from_slice(&[0x08, 0x00]),
+    }
+    frame.extend_from_slice(packet);
+    frame
+}
+
+/// Replaces the Linux cooked capture header of a packet with a blank Ethernet II header.
+///
+/// # Arguments
+/// * `packet` - Raw bytes of the packet, starting with its SLL or SLL2 header.
+/// * `link_layer` - `LinkLayer::LinuxSll` or `LinkLayer::LinuxSll2`.
+///
+/// # Returns
+/// The Ethernet frame carrying the protocol type of the cooked header, or `None` if the
+/// packet is shorter than its header.
+fn cooked_to_ethernet(packet: &[u8], link_layer: LinkLayer) -> Option<Vec<u8>> {
+    let (ethertype, payload) = link::strip(packet, link_layer)?;
+    let mut frame = vec![0; ETHERNET_HEADER_LEN];
+    frame[12..].copy_from_slice(&ethertype.0.to_be_bytes());
+    frame.extend_from_slice(payload);
+    Some(frame)
+}
+
+#[cfg(test)]
+mod capture_tests {
+    use super::*;
+
+    const PACKET: [u8; 60] = [
+        0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b,
+        0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+        0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4, 0x04,
+        0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x03, 0x07,
+    ];
+
+    /// Writes a little-endian pcap file containing `count` times `packet`.
+    fn write_pcap(name: &str, linktype: u32, packet: &[u8], count: usize) -> std::path::PathBuf {
+        let mut file = vec![];
+        file.extend(0xa1b2c3d4u32.to_le_bytes());
+        file.extend(2u16.to_le_bytes());
+        file.extend(4u16.to_le_bytes());
+        file.extend(0u32.to_le_bytes());
+        file.extend(0u32.to_le_bytes());
+        file.extend(65535u32.to_le_bytes());
+        file.extend(linktype.to_le_bytes());
+        for i in 0..count {
+            file.extend((i as u32).to_le_bytes());
+            file.extend(0u32.to_le_bytes());
+            file.extend((packet.len() as u32).to_le_bytes());
+            file.extend((packet.len() as u32).to_le_bytes());
+            file.extend(packet);
+        }
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, file).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_to_ethernet() {
+        let frame = to_ethernet(&PACKET);
+        assert_eq!(frame.len(), PACKET.len() + ETHERNET_HEADER_LEN);
+        assert_eq!(frame[12..14], [0x08, 0x00], "Expected IPv4 ethertype");
+        assert_eq!(frame[14..], PACKET);
+    }
+
+    #[test]
+    fn test_from_pcap_raw_ip() {
+        let path = write_pcap("nprint_rs_raw_ip.pcap", 101, &PACKET, 3);
+        let flows = Nprint::from_pcap(&path, vec![ProtocolType::Ipv4, ProtocolType::Tcp]).unwrap();
+        assert_eq!(flows.len(), 1, "Expected a single flow");
+        assert_eq!(flows[0].count(), 3, "Wrong number of packet!");
+    }
+
+    #[test]
+    fn test_from_pcap_linux_cooked() {
+        // Incoming packet from an Ethernet interface: packet type, ARPHRD_ETHER, address
+        let mut sll = vec![0x00, 0x00, 0x00, 0x01, 0x00, 0x06];
+        sll.extend([0x52, 0x54, 0x00, 0x12, 0x34, 0x56, 0x00, 0x00, 0x08, 0x00]);
+        sll.extend(PACKET);
+        // Protocol type, reserved, interface index, ARPHRD_ETHER, packet type, address
+        let mut sll2 = vec![0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x01];
+        sll2.extend([0x00, 0x06, 0x52, 0x54, 0x00, 0x12, 0x34, 0x56, 0x00, 0x00]);
+        sll2.extend(PACKET);
+        let expected = Nprint::new(&to_ethernet(&PACKET), vec![ProtocolType::Tcp]).print();
+
+        for (name, linktype, packet) in [("sll", 113, sll), ("sll2", 276, sll2)] {
+            let path = write_pcap(&format!("nprint_rs_{}.pcap", name), linktype, &packet, 2);
+            let flows = Nprint::from_pcap(&path, vec![ProtocolType::Tcp]).unwrap();
+            assert_eq!(flows.len(), 1, "Expected a single flow for {}", name);
+            assert_eq!(flows[0].count(), 2, "Wrong number of packet for {}", name);
+            assert_eq!(
+                flows[0].print()[..480],
+                expected,
+                "Wrong TCP header for {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_pcap_unsupported_linktype() {
+        let path = write_pcap("nprint_rs_unsupported.pcap", 147, &PACKET, 1);
+        assert!(matches!(
+            Nprint::from_pcap(&path, vec![ProtocolType::Ipv4]),
+            Err(NprintError::UnsupportedLinkType(147))
+        ));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::protocols::payload::DEFAULT_PAYLOAD_BYTES;
+use crate::protocols::util::DEFAULT_OPTIONS_BITS;
+
+/// Value used to encode the bits of absent fields.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FillValue {
+    /// `-1`, the nPrint reference encoding.
+    #[default]
+    MinusOne,
+    /// `0`.
+    Zero,
+    /// `f32::NAN`.
+    NaN,
+    /// Any other value.
+    Custom(f32),
+}
+
+/// Behavior of `Nprint::add` once `max_packets` packets are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TruncationPolicy {
+    /// Ignore the new packets, keeping the first ones.
+    #[default]
+    DropNewest,
+    /// Evict the oldest packet, keeping the last ones.
+    DropOldest,
+}
+
+/// Order in which the bits of every byte are extracted.
+///
+/// Fields narrower than a byte follow the order of their byte, e.g. with `Lsb0` the IPv4
+/// header starts with the IHL bits, least significant first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BitOrder {
+    /// Most significant bit first, the nPrint reference encoding.
+    #[default]
+    Msb0,
+    /// Least significant bit first.
+    Lsb0,
+}
+
+/// Order of the values of every packet in `Nprint::print()` and `Nprint::get_headers()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Layout {
+    /// Packets one after the other, each one with all its protocols.
+    #[default]
+    PacketMajor,
+    /// Protocols one after the other, each one with the bits of all the packets.
+    ProtocolMajor,
+}
+
+/// Representation of the TCP flags in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TcpFlagsEncoding {
+    /// One value per flag bit, from `tcp_ns` to `tcp_fin`.
+    #[default]
+    Bits,
+    /// A single `tcp_flags_value`, the flags byte (`tcp_cwr` to `tcp_fin`) as an integer.
+    Value,
+    /// The flag bits, followed by `tcp_flags_value` after the options.
+    BitsAndValue,
+}
+
+impl TcpFlagsEncoding {
+    /// Returns `true` if the individual flag bits are kept.
+    pub fn has_bits(&self) -> bool {
+        *self != TcpFlagsEncoding::Value
+    }
+
+    /// Returns `true` if `tcp_flags_value` is extracted.
+    pub fn has_value(&self) -> bool {
+        *self != TcpFlagsEncoding::Bits
+    }
+}
+
+/// Bytes extracted by `ProtocolType::Payload`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PayloadSource {
+    /// The bytes following the TCP or UDP header, the payload being absent for other protocols.
+    #[default]
+    TransportPayload,
+    /// The bytes following the IP header, its IPv6 extension headers and any AH header,
+    /// whatever the upper-layer protocol, e.g. ICMP messages or ESP encrypted data.
+    IpPayload,
+}
+
+/// Link layer preceding the network layer in every packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LinkLayer {
+    /// Ethernet II frames, or IEEE 802.3 frames encapsulating the network layer in LLC/SNAP.
+    #[default]
+    Ethernet,
+    /// IPv4 or IPv6 packets without link layer, e.g. captured on a `tun` interface.
+    RawIp,
+    /// Linux cooked capture (SLL) header of 16 bytes, e.g. captured with `tcpdump -i any`.
+    LinuxSll,
+    /// Linux cooked capture v2 (SLL2) header of 20 bytes.
+    LinuxSll2,
+    /// Raw IP if the first nibble is an IP version (4 or 6), Ethernet (II or LLC/SNAP) otherwise.
+    ///
+    /// Cooked captures can't be told apart from Ethernet frames and must be selected explicitly.
+    Auto,
+}
+
+/// Configuration shared by every packet parsed in an `Nprint`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NprintConfig {
+    /// Value used for absent headers and for the padding of the options.
+    pub fill_value: FillValue,
+    /// Maximum number of packets stored, `None` for no limit.
+    pub max_packets: Option<usize>,
+    /// Packets kept once `max_packets` is reached.
+    pub truncation: TruncationPolicy,
+    /// Pad `Nprint::print()` with packets filled with the fill value up to `max_packets`.
+    pub pad_packets: bool,
+    /// Anonymize every packet as soon as it's parsed.
+    pub anonymize: bool,
+    /// Rewrite TCP sequence and acknowledgment numbers relative to the first ones of each direction.
+    pub relative_seq: bool,
+    /// Append `tcp_wsize_scaled`, the TCP window size shifted by the window scale negotiated
+    /// in the handshake of the flow.
+    pub scale_window: bool,
+    /// Names of the fields kept in the output (e.g. `tcp_syn`, `tcp_wsize`), `None` to keep all of them.
+    pub fields: Option<Vec<String>>,
+    /// Maximum number of payload bytes extracted, `None` for the `mtu`.
+    pub max_payload_bytes: Option<usize>,
+    /// Largest payload expected in bytes, e.g. 9000 for jumbo frames, `None` for the maximum
+    /// Ethernet frame length (1514) without rejecting larger payloads.
+    ///
+    /// Payloads longer than the MTU are considered malformed and filled as absent.
+    pub mtu: Option<usize>,
+    /// Number of IPv4 option bits extracted, padded or truncated, `None` for 320.
+    pub ipv4_options_bits: Option<usize>,
+    /// Number of TCP option bits extracted, padded or truncated, `None` for 320.
+    pub tcp_options_bits: Option<usize>,
+    /// Extract the IPv4 and TCP options, `true` by default. Without them, both headers are
+    /// reduced to their 160 fixed bits.
+    pub include_options: bool,
+    /// Bytes extracted as the payload, after the transport or the IP header.
+    pub payload_source: PayloadSource,
+    /// Extract `ipv4_has_opt_0` and `tcp_has_opt_0` right before the options of their header,
+    /// 1 if the header carries options and 0 otherwise, whether the options are extracted or not.
+    pub options_indicator: bool,
+    /// Number of leading payload bytes skipped before extraction.
+    pub payload_offset: usize,
+    /// Order of the bits extracted from every byte.
+    pub bit_order: BitOrder,
+    /// Link layer of the parsed packets, the Ethernet header is absent for other link layers.
+    pub link_layer: LinkLayer,
+    /// Replace the IPv4, TCP and UDP checksums with a single bit, 1 if the checksum is valid.
+    pub checksum_validity: bool,
+    /// Replace the TCP and UDP ports with their 2-bit range (`*_range0` and `*_range1`), `00`
+    /// for well-known ports (below 1024), `01` for registered ports (below 49152) and `10`
+    /// for ephemeral ones. The ranges are kept by the anonymization.
+    pub port_categories: bool,
+    /// Update the IPv4, TCP and UDP checksums to match the anonymized fields, instead of
+    /// leaving the original ones. Ignored with `checksum_validity`.
+    pub recompute_checksums: bool,
+    /// Representation of the TCP flags, as individual bits and/or as a single value.
+    pub tcp_flags: TcpFlagsEncoding,
+    /// Divide `tcp_flags_value` by 255 so that it lies in [0, 1].
+    pub normalize_tcp_flags: bool,
+    /// Order of the values in `Nprint::print()`, packet rows are left untouched.
+    pub layout: Layout,
+}
+
+impl Default for NprintConfig {
+    fn default() -> Self {
+        NprintConfig {
+            fill_value: FillValue::default(),
+            max_packets: None,
+            truncation: TruncationPolicy::default(),
+            pad_packets: false,
+            anonymize: false,
+            relative_seq: false,
+            scale_window: false,
+            fields: None,
+            max_payload_bytes: None,
+            mtu: None,
+            ipv4_options_bits: None,
+            tcp_options_bits: None,
+            include_options: true,
+            options_indicator: false,
+            payload_source: PayloadSource::default(),
+            payload_offset: 0,
+            bit_order: BitOrder::default(),
+            link_layer: LinkLayer::default(),
+            checksum_validity: false,
+            port_categories: false,
+            recompute_checksums: false,
+            tcp_flags: TcpFlagsEncoding::default(),
+            normalize_tcp_flags: false,
+            layout: Layout::default(),
+        }
+    }
+}
+
+impl NprintConfig {
+    /// Returns the number of payload bytes extracted for every packet.
+    pub fn payload_bytes(&self) -> usize {
+        self.max_payload_bytes
+            .or(self.mtu)
+            .unwrap_or(DEFAULT_PAYLOAD_BYTES)
+    }
+
+    /// Returns the number of IPv4 option bits extracted for every packet, 0 without
+    /// `include_options`.
+    pub fn ipv4_options_width(&self) -> usize {
+        if self.include_options {
+            self.ipv4_options_bits.unwrap_or(DEFAULT_OPTIONS_BITS)
+        } else {
+            0
+        }
+    }
+
+    /// Returns the number of TCP option bits extracted for every packet, 0 without
+    /// `include_options`.
+    pub fn tcp_options_width(&self) -> usize {
+        if self.include_options {
+            self.tcp_options_bits.unwrap_or(DEFAULT_OPTIONS_BITS)
+        } else {
+            0
+        }
+    }
+}
+
+impl FillValue {
+    /// Returns the `f32` encoding absent bits.
+    pub fn value(&self) -> f32 {
+        match self {
+            FillValue::MinusOne => -1.,
+            FillValue::Zero => 0.,
+            FillValue::NaN => f32::NAN,
+            FillValue::Custom(value) => *value,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+
+/// Errors returned by the fallible `Nprint` operations.
+#[derive(Debug)]
+pub enum NprintError {
+    /// Error raised by libpcap while opening or reading a capture.
+    #[cfg(feature = "pcap")]
+    Pcap(::pcap::Error),
+    /// The capture uses a link-layer type that can't be parsed.
+    UnsupportedLinkType(i32),
+    /// The packed bytes are truncated or hold an invalid value code.
+    MalformedPackedBytes,
+    /// The columns of a saved dataset don't match the ones of the `Nprint`, see `Nprint::schema_hash()`.
+    SchemaMismatch {
+        /// Schema hash of the `Nprint`.
+        expected: u64,
+        /// Schema hash of the saved dataset.
+        found: u64,
+    },
+    /// A row of a CSV can't be read back, see `Nprint::from_csv()`.
+    MalformedCsv {
+        /// Line of the row, starting at 1 with the header row.
+        line: usize,
+        /// Description of the problem.
+        reason: String,
+    },
+    /// A hex string can't be decoded into a packet, see `Nprint::from_hex()`.
+    MalformedHex {
+        /// Byte offset of the problem in the string.
+        position: usize,
+        /// Description of the problem.
+        reason: String,
+    },
+    /// Error raised while reading a saved dataset.
+    Io(std::io::Error),
+}
+
+impl fmt::Display for NprintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "pcap")]
+            NprintError::Pcap(err) => write!(f, "pcap error: {}", err),
+            NprintError::UnsupportedLinkType(linktype) => {
+                write!(f, "unsupported link-layer type: {}", linktype)
+            }
+            NprintError::MalformedPackedBytes => write!(f, "malformed packed bytes"),
+            NprintError::SchemaMismatch { expected, found } => write!(
+                f,
+                "schema mismatch: expected hash {:016x}, found {:016x}",
+                expected, found
+            ),
+            NprintError::MalformedCsv { line, reason } => {
+                write!(f, "malformed CSV at line {}: {}", line, reason)
+            }
+            NprintError::MalformedHex { position, reason } => {
+                write!(f, "malformed hex at offset {}: {}", position, reason)
+            }
+            NprintError::Io(err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for NprintError {}
+
+impl From<std::io::Error> for NprintError {
+    fn from(err: std::io::Error) -> Self {
+        NprintError::Io(err)
+    }
+}
+
+#[cfg(feature = "pcap")]
+impl From<::pcap::Error> for NprintError {
+    fn from(err: ::pcap::Error) -> Self {
+        NprintError::Pcap(err)
+    }
+}
@@ -0,0 +1,615 @@
+//! Grouping of raw packets into bidirectional flows, one `Nprint` per flow.
+use crate::config::LinkLayer;
+use crate::protocols::link;
+use crate::protocols::vlan::{is_vlan, MAX_VLAN_DEPTH};
+use crate::reassembly::Reassembler;
+use crate::{Nprint, ProtocolType};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use pnet::packet::ethernet::EtherTypes;
+use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
+use pnet::packet::ipv4::Ipv4Packet;
+use pnet::packet::tcp::TcpPacket;
+use pnet::packet::udp::UdpPacket;
+use pnet::packet::vlan::VlanPacket;
+use pnet::packet::Packet;
+
+/// The 5-tuple identifying a flow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlowKey {
+    /// Source IP address.
+    pub src_ip: IpAddr,
+    /// Destination IP address.
+    pub dst_ip: IpAddr,
+    /// Source port, `0` when the transport has no ports.
+    pub src_port: u16,
+    /// Destination port, `0` when the transport has no ports.
+    pub dst_port: u16,
+    /// IP next level protocol number.
+    pub proto: u8,
+}
+
+/// Direction of a packet relative to the first packet of its flow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    /// Same direction as the first packet, e.g. from the client to the server.
+    #[default]
+    Forward,
+    /// Opposite direction, e.g. from the server to the client.
+    Backward,
+}
+
+/// FIN flag of the TCP header.
+const FIN: u8 = 0x01;
+/// RST flag of the TCP header.
+const RST: u8 = 0x04;
+
+/// Structure grouping packets by their canonical 5-tuple, creating one `Nprint` per flow.
+#[derive(Debug)]
+pub struct FlowTable {
+    /// Ordered list of Protocol used for every created Nprint.
+    protocols: Vec<ProtocolType>,
+    /// Each flow, indexed by the canonical key.
+    flows: HashMap<FlowKey, Flow>,
+    /// Buffer of the fragmented IPv4 datagrams.
+    reassembler: Reassembler,
+    /// Creation time of the table, clock expiring the fragments and flows of the packets
+    /// inserted without a timestamp.
+    start: Instant,
+}
+
+/// A flow of a `FlowTable` and the state used to expire it.
+#[derive(Debug)]
+struct Flow {
+    /// Packets of the flow.
+    nprint: Nprint,
+    /// Capture time of the last packet.
+    last_seen: Duration,
+    /// Whether a TCP FIN was sent in the forward and backward direction.
+    fin: [bool; 2],
+    /// Whether the TCP connection is over, both sides having sent a FIN or any a RST.
+    closed: bool,
+}
+
+impl Flow {
+    /// Updates the state of the flow with a packet.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - 5-tuple of the packet.
+    /// * `packet` - A byte slice representing the raw packet data.
+    /// * `timestamp` - Capture time of the packet.
+    fn update(&mut self, key: &FlowKey, packet: &[u8], timestamp: Duration) {
+        self.last_seen = self.last_seen.max(timestamp);
+        let Some(flags) = tcp_flags(packet) else {
+            return;
+        };
+        let direction = self
+            .nprint
+            .flow_key()
+            .map_or(Direction::Forward, |flow| key.direction(flow));
+        if flags & FIN != 0 {
+            self.fin[usize::from(direction == Direction::Backward)] = true;
+        }
+        self.closed |= flags & RST != 0 || self.fin == [true, true];
+    }
+}
+
+impl FlowKey {
+    /// Extracts the 5-tuple of a raw Ethernet packet, as seen on the wire.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet data.
+    ///
+    /// # Returns
+    ///
+    /// The `FlowKey` of the packet, or `None` if the packet doesn't carry IPv4.
+    pub fn from_packet(packet: &[u8]) -> Option<FlowKey> {
+        FlowKey::from_ipv4(&packet[ipv4_offset(packet)?..])
+    }
+
+    /// Extracts the 5-tuple of a raw packet with the given link layer.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet data.
+    /// * `link_layer` - Link layer of the packet.
+    ///
+    /// # Returns
+    ///
+    /// The `FlowKey` of the packet, or `None` if the packet doesn't carry IPv4.
+    pub(crate) fn from_link_layer(packet: &[u8], link_layer: LinkLayer) -> Option<FlowKey> {
+        match link::resolve(packet, link_layer) {
+            LinkLayer::Ethernet => FlowKey::from_packet(packet),
+            link_layer => match link::strip(packet, link_layer)? {
+                (EtherTypes::Ipv4, payload) => FlowKey::from_ipv4(payload),
+                _ => None,
+            },
+        }
+    }
+
+    /// Extracts the 5-tuple of a raw IPv4 packet.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - Raw bytes starting at the IPv4 header.
+    fn from_ipv4(packet: &[u8]) -> Option<FlowKey> {
+        let ipv4 = Ipv4Packet::new(packet)?;
+        let proto = ipv4.get_next_level_protocol();
+        let (src_port, dst_port) = get_ports(proto, ipv4.payload());
+        Some(FlowKey {
+            src_ip: IpAddr::V4(ipv4.get_source()),
+            dst_ip: IpAddr::V4(ipv4.get_destination()),
+            src_port,
+            dst_port,
+            proto: proto.0,
+        })
+    }
+
+    /// Returns the key with its endpoints ordered, so that A→B and B→A share the same key.
+    pub fn canonical(&self) -> FlowKey {
+        if (self.src_ip, self.src_port) <= (self.dst_ip, self.dst_port) {
+            *self
+        } else {
+            self.reversed()
+        }
+    }
+
+    /// Returns the key of the opposite direction, with source and destination swapped.
+    pub fn reversed(&self) -> FlowKey {
+        FlowKey {
+            src_ip: self.dst_ip,
+            dst_ip: self.src_ip,
+            src_port: self.dst_port,
+            dst_port: self.src_port,
+            proto: self.proto,
+        }
+    }
+
+    /// Returns the direction of a packet with this key, relative to the key of a flow.
+    ///
+    /// # Arguments
+    ///
+    /// * `flow` - Key of the first packet of the flow.
+    pub fn direction(&self, flow: &FlowKey) -> Direction {
+        if self != flow && *self == flow.reversed() {
+            Direction::Backward
+        } else {
+            Direction::Forward
+        }
+    }
+}
+
+impl FlowTable {
+    /// Creates an empty `FlowTable`.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocols` - A vector of `ProtocolType` used to parse the packets of every flow.
+    pub fn new(protocols: Vec<ProtocolType>) -> FlowTable {
+        FlowTable {
+            protocols,
+            flows: HashMap::new(),
+            reassembler: Reassembler::default(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Adds a packet to the `Nprint` of its flow, creating the flow if needed.
+    ///
+    /// Packets without an extractable 5-tuple (e.g. non IPv4) are ignored.
+    /// IPv4 fragments are buffered until their datagram is complete, see `insert_at`.
+    ///
+    /// The packet is added without a timestamp, see `Nprint::add()`. Fragments and flows are
+    /// then timed from the creation of the table, e.g. to pass `now` to `expire()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet data.
+    pub fn insert(&mut self, packet: &[u8]) {
+        self.insert_packet(packet, None, None);
+    }
+
+    /// Adds a packet captured at the given time to the `Nprint` of its flow.
+    ///
+    /// IPv4 fragments are buffered and the reassembled datagram is added once every
+    /// fragment is received. Incomplete datagrams are dropped after 30 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet data.
+    /// * `timestamp` - Capture time of the packet, from any fixed origin.
+    pub fn insert_at(&mut self, packet: &[u8], timestamp: Duration) {
+        self.insert_packet(packet, Some(timestamp), None);
+    }
+
+    /// Adds a packet captured at the given time to the `Nprint` of its flow, like
+    /// `insert_at()`, along with its length on the wire.
+    ///
+    /// The wire length is kept in the `ProtocolType::FrameMeta` header, except for
+    /// reassembled datagrams which have no single wire length.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet data.
+    /// * `timestamp` - Capture time of the packet, from any fixed origin.
+    /// * `wire_len` - Original length of the packet on the wire, in bytes.
+    pub fn insert_with_wire_len(&mut self, packet: &[u8], timestamp: Duration, wire_len: usize) {
+        self.insert_packet(packet, Some(timestamp), Some(wire_len));
+    }
+
+    /// Adds a packet to the `Nprint` of its flow, see `insert_with_wire_len()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet data.
+    /// * `timestamp` - Capture time of the packet, from any fixed origin, if known.
+    /// * `wire_len` - Original length of the packet on the wire, if known.
+    fn insert_packet(
+        &mut self,
+        packet: &[u8],
+        timestamp: Option<Duration>,
+        wire_len: Option<usize>,
+    ) {
+        let Some(offset) = ipv4_offset(packet) else {
+            return;
+        };
+        // Without a capture time, fragments and flows still expire following the table clock
+        let clock = timestamp.unwrap_or_else(|| self.start.elapsed());
+        let Some(packet) = self.reassembler.process(packet, offset, clock) else {
+            return;
+        };
+        let wire_len = wire_len.filter(|_| matches!(packet, Cow::Borrowed(_)));
+        if let Some(key) = FlowKey::from_packet(&packet) {
+            let flow = self.flows.entry(key.canonical()).or_insert_with(|| Flow {
+                nprint: Nprint::empty(self.protocols.clone()),
+                last_seen: clock,
+                fin: [false; 2],
+                closed: false,
+            });
+            match (timestamp, wire_len) {
+                (Some(timestamp), Some(wire_len)) => {
+                    flow.nprint.add_with_wire_len(&packet, timestamp, wire_len)
+                }
+                (Some(timestamp), None) => flow.nprint.add_with_timestamp(&packet, timestamp),
+                (None, _) => flow.nprint.add(&packet),
+            }
+            flow.update(&key, &packet, clock);
+        }
+    }
+
+    /// Removes and returns the flows that are over.
+    ///
+    /// A flow is over once idle for longer than `idle_timeout`, or as soon as its TCP
+    /// connection is closed: both sides sent a FIN, or any side sent a RST. Packets of a
+    /// closed flow received before this call, like the last ACK, are still added to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Current time, from the origin of the packet timestamps (see `insert_at`), or
+    ///   the creation of the table for packets inserted with `insert`.
+    /// * `idle_timeout` - Longest time without packets before a flow is over.
+    ///
+    /// # Returns
+    ///
+    /// The flows that are over with their canonical key, in no particular order.
+    pub fn expire(&mut self, now: Duration, idle_timeout: Duration) -> Vec<(FlowKey, Nprint)> {
+        let expired: Vec<FlowKey> = self
+            .flows
+            .iter()
+            .filter(|(_, flow)| flow.closed || now.saturating_sub(flow.last_seen) > idle_timeout)
+            .map(|(key, _)| *key)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|key| self.flows.remove(&key).map(|flow| (key, flow.nprint)))
+            .collect()
+    }
+
+    /// Pseudonymizes every flow with the same key, see `Nprint::anonymize_with_key()`.
+    ///
+    /// A host keeps the same pseudonym in every flow, so that the structure of the capture
+    /// survives the anonymization. Packets inserted afterwards are left as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Secret key of the pseudonymization, shared by every flow.
+    pub fn anonymize_all(&mut self, key: &[u8; 16]) {
+        for flow in self.flows.values_mut() {
+            flow.nprint.anonymize_with_key(key);
+        }
+    }
+
+    /// Returns the number of flows.
+    pub fn len(&self) -> usize {
+        self.flows.len()
+    }
+
+    /// Returns `true` if no flow has been created yet.
+    pub fn is_empty(&self) -> bool {
+        self.flows.is_empty()
+    }
+
+    /// Consumes the table and returns every flow indexed by its canonical key.
+    pub fn into_flows(self) -> HashMap<FlowKey, Nprint> {
+        self.flows
+            .into_iter()
+            .map(|(key, flow)| (key, flow.nprint))
+            .collect()
+    }
+}
+
+/// Returns the offset of the IPv4 header in a raw Ethernet packet, skipping LLC/SNAP and VLAN tags.
+///
+/// # Arguments
+/// * `packet` - A byte slice representing the raw packet data.
+///
+/// # Returns
+/// The offset, or `None` if the packet doesn't carry IPv4.
+fn ipv4_offset(packet: &[u8]) -> Option<usize> {
+    let (mut ethertype, payload) = link::strip(packet, LinkLayer::Ethernet)?;
+    let mut offset = packet.len() - payload.len();
+
+    let mut depth = 0;
+    while is_vlan(ethertype) && depth < MAX_VLAN_DEPTH {
+        ethertype = VlanPacket::new(&packet[offset..])?.get_ethertype();
+        offset += VlanPacket::minimum_packet_size();
+        depth += 1;
+    }
+    (ethertype == EtherTypes::Ipv4).then_some(offset)
+}
+
+/// Returns the TCP flags byte (`tcp_cwr` to `tcp_fin`) of a raw Ethernet packet.
+///
+/// # Arguments
+/// * `packet` - A byte slice representing the raw packet data.
+///
+/// # Returns
+/// The flags, or `None` if the packet doesn't carry TCP over IPv4.
+fn tcp_flags(packet: &[u8]) -> Option<u8> {
+    let ipv4 = Ipv4Packet::new(&packet[ipv4_offset(packet)?..])?;
+    if ipv4.get_next_level_protocol() != IpNextHeaderProtocols::Tcp {
+        return None;
+    }
+    TcpPacket::new(ipv4.payload()).map(|tcp| tcp.get_flags())
+}
+
+/// Returns the source and destination ports of a transport payload, `(0, 0)` if not applicable.
+///
+/// # Arguments
+/// * `proto` - IP next level protocol.
+/// * `payload` - Raw bytes of the IP payload.
+fn get_ports(proto: IpNextHeaderProtocol, payload: &[u8]) -> (u16, u16) {
+    match proto {
+        IpNextHeaderProtocols::Tcp => TcpPacket::new(payload)
+            .map(|tcp| (tcp.get_source(), tcp.get_destination()))
+            .unwrap_or((0, 0)),
+        IpNextHeaderProtocols::Udp => UdpPacket::new(payload)
+            .map(|udp| (udp.get_source(), udp.get_destination()))
+            .unwrap_or((0, 0)),
+        _ => (0, 0),
+    }
+}
+
+#[cfg(test)]
+mod flow_tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const PACKET: [u8; 74] = [
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00, 0x00,
+        0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25, 0xc6, 0x26,
+        0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00, 0x00, 0x00, 0xa0,
+        0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a,
+        0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x03, 0x07,
+    ];
+
+    /// Returns `PACKET` with IPs and ports swapped.
+    fn reversed() -> Vec<u8> {
+        let mut packet = PACKET.to_vec();
+        let (src_ip, dst_ip) = (packet[26..30].to_vec(), packet[30..34].to_vec());
+        packet[26..30].copy_from_slice(&dst_ip);
+        packet[30..34].copy_from_slice(&src_ip);
+        let (src_port, dst_port) = (packet[34..36].to_vec(), packet[36..38].to_vec());
+        packet[34..36].copy_from_slice(&dst_port);
+        packet[36..38].copy_from_slice(&src_port);
+        packet
+    }
+
+    #[test]
+    fn test_flow_key_from_packet() {
+        let key = FlowKey::from_packet(&PACKET).unwrap();
+        assert_eq!(key.src_ip, IpAddr::V4(Ipv4Addr::new(192, 168, 43, 37)));
+        assert_eq!(key.dst_ip, IpAddr::V4(Ipv4Addr::new(198, 38, 120, 136)));
+        assert_eq!(key.src_port, 38820);
+        assert_eq!(key.dst_port, 443);
+        assert_eq!(key.proto, 6);
+    }
+
+    #[test]
+    fn test_flow_key_canonical() {
+        let forward = FlowKey::from_packet(&PACKET).unwrap();
+        let backward = FlowKey::from_packet(&reversed()).unwrap();
+        assert_ne!(forward, backward);
+        assert_eq!(forward.canonical(), backward.canonical());
+    }
+
+    #[test]
+    fn test_flow_key_direction() {
+        let forward = FlowKey::from_packet(&PACKET).unwrap();
+        let backward = FlowKey::from_packet(&reversed()).unwrap();
+        assert_eq!(backward.reversed(), forward);
+        assert_eq!(forward.direction(&forward), Direction::Forward);
+        assert_eq!(backward.direction(&forward), Direction::Backward);
+        assert_eq!(forward.direction(&backward), Direction::Backward);
+        assert_eq!(
+            FlowKey::from_link_layer(&PACKET[14..], LinkLayer::RawIp),
+            Some(forward)
+        );
+    }
+
+    #[test]
+    fn test_flow_table_bidirectional() {
+        let mut table = FlowTable::new(vec![ProtocolType::Ipv4, ProtocolType::Tcp]);
+        table.insert(&PACKET);
+        table.insert(&reversed());
+        table.insert(&[0x0]);
+        assert_eq!(table.len(), 1, "Expected both directions in one flow");
+        let flows = table.into_flows();
+        let nprint = flows.values().next().unwrap();
+        assert_eq!(nprint.count(), 2, "Wrong number of packet!");
+        assert_eq!(nprint.flow_key(), FlowKey::from_packet(&PACKET).as_ref());
+        assert_eq!(
+            nprint.directions(),
+            vec![Direction::Forward, Direction::Backward]
+        );
+    }
+
+    #[test]
+    fn test_flow_table_anonymize_all() {
+        // Same source host, towards another port of another host
+        let mut other = PACKET.to_vec();
+        other[30..34].copy_from_slice(&[10, 0, 0, 1]);
+        other[36..38].copy_from_slice(&80u16.to_be_bytes());
+        let mut table = FlowTable::new(vec![ProtocolType::Ipv4, ProtocolType::Tcp]);
+        table.insert(&PACKET);
+        table.insert(&other);
+        assert_eq!(table.len(), 2);
+
+        table.anonymize_all(&[0x2a; 16]);
+        let flows = table.into_flows();
+        let sources: Vec<Vec<f32>> = flows
+            .values()
+            .map(|nprint| nprint.print()[96..128].to_vec())
+            .collect();
+        assert_eq!(sources[0], sources[1], "Expected the same pseudonym");
+        let original = Nprint::new(&PACKET, vec![ProtocolType::Ipv4]).print();
+        assert_ne!(
+            sources[0],
+            original[96..128],
+            "Expected a pseudonymized source"
+        );
+    }
+
+    /// Returns `PACKET` split in two IPv4 fragments, the first one holding 16 bytes.
+    fn fragments() -> (Vec<u8>, Vec<u8>) {
+        let mut first = PACKET.to_vec();
+        first[20..22].copy_from_slice(&[0x20, 0x00]);
+        first.truncate(14 + 20 + 16);
+        first[16..18].copy_from_slice(&36u16.to_be_bytes());
+        let mut second = PACKET[..34].to_vec();
+        second[20..22].copy_from_slice(&[0x00, 0x02]);
+        second[16..18].copy_from_slice(&44u16.to_be_bytes());
+        second.extend_from_slice(&PACKET[50..]);
+        (first, second)
+    }
+
+    #[test]
+    fn test_flow_table_fragments() {
+        let (first, second) = fragments();
+        let mut table = FlowTable::new(vec![ProtocolType::Tcp]);
+        table.insert_at(&second, Duration::ZERO);
+        assert!(table.is_empty(), "Expected the fragment to be buffered");
+        table.insert_at(&first, Duration::ZERO);
+        let flows = table.into_flows();
+        let key = FlowKey::from_packet(&PACKET).unwrap().canonical();
+        assert_eq!(flows.len(), 1);
+        assert_eq!(
+            flows[&key].print(),
+            Nprint::new(&PACKET, vec![ProtocolType::Tcp]).print(),
+            "Expected the reassembled TCP header"
+        );
+    }
+
+    #[test]
+    fn test_flow_table_truncated_fragment() {
+        // First fragment announcing 60 header bytes, holding only 20
+        let mut truncated = PACKET[..34].to_vec();
+        truncated[14] = 0x4f;
+        truncated[20..22].copy_from_slice(&[0x20, 0x00]);
+        let mut table = FlowTable::new(vec![ProtocolType::Ipv4, ProtocolType::Tcp]);
+        table.insert_at(&truncated, Duration::ZERO);
+        let flows = table.into_flows();
+        assert_eq!(flows.len(), 1, "Expected the fragment to be added as is");
+        let nprint = flows.values().next().unwrap();
+        assert_eq!(nprint.is_parsed(0, ProtocolType::Ipv4), Some(false));
+    }
+
+    #[test]
+    fn test_flow_table_insert_without_timestamp() {
+        let (first, second) = fragments();
+        let mut table = FlowTable::new(vec![ProtocolType::Timing, ProtocolType::Tcp]);
+        table.insert(&second);
+        assert!(table.is_empty(), "Expected the fragment to be buffered");
+        table.insert(&first);
+        table.insert(&PACKET);
+        let flows = table.into_flows();
+        let nprint = &flows[&FlowKey::from_packet(&PACKET).unwrap().canonical()];
+        assert_eq!(nprint.count(), 2, "Expected the reassembled datagram");
+        assert_eq!(
+            nprint.is_parsed(1, ProtocolType::Timing),
+            Some(false),
+            "Expected no inter-arrival time without timestamps"
+        );
+
+        let mut table = FlowTable::new(vec![ProtocolType::Timing]);
+        table.insert_at(&PACKET, Duration::from_secs(1));
+        table.insert_at(&PACKET, Duration::from_secs(2));
+        let nprint = table.into_flows().into_values().next().unwrap();
+        assert_eq!(nprint.is_parsed(1, ProtocolType::Timing), Some(true));
+    }
+
+    #[test]
+    fn test_flow_table_expire_idle() {
+        let mut table = FlowTable::new(vec![ProtocolType::Tcp]);
+        let mut other = PACKET;
+        other[34] = 0x98;
+        table.insert_at(&PACKET, Duration::from_secs(1));
+        table.insert_at(&other, Duration::from_secs(50));
+        let timeout = Duration::from_secs(60);
+        assert!(table.expire(Duration::from_secs(60), timeout).is_empty());
+        let expired = table.expire(Duration::from_secs(62), timeout);
+        assert_eq!(expired.len(), 1, "Expected the first flow to be idle");
+        assert_eq!(
+            expired[0].0,
+            FlowKey::from_packet(&PACKET).unwrap().canonical()
+        );
+        assert_eq!(table.len(), 1, "Expected the second flow to be kept");
+    }
+
+    #[test]
+    fn test_flow_table_expire_closed() {
+        let with_flags = |mut packet: Vec<u8>, flags: u8| {
+            packet[47] = flags;
+            packet
+        };
+        let mut table = FlowTable::new(vec![ProtocolType::Tcp]);
+        let timeout = Duration::from_secs(60);
+        table.insert_at(&PACKET, Duration::ZERO);
+        table.insert_at(&with_flags(PACKET.to_vec(), 0x11), Duration::ZERO);
+        assert!(
+            table.expire(Duration::ZERO, timeout).is_empty(),
+            "Expected a half-closed flow to be kept"
+        );
+        table.insert_at(&with_flags(reversed(), 0x11), Duration::ZERO);
+        table.insert_at(&with_flags(PACKET.to_vec(), 0x10), Duration::ZERO);
+        let expired = table.expire(Duration::ZERO, timeout);
+        assert_eq!(expired.len(), 1, "Expected the flow closed by both FIN");
+        assert_eq!(expired[0].1.count(), 4, "Expected the last ACK in the flow");
+        assert!(table.is_empty());
+
+        table.insert_at(&PACKET, Duration::ZERO);
+        table.insert_at(&with_flags(reversed(), 0x04), Duration::ZERO);
+        assert_eq!(
+            table.expire(Duration::ZERO, timeout).len(),
+            1,
+            "Expected the flow closed by a RST"
+        );
+    }
+}
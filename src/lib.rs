@@ -1,43 +1,328 @@
 //! nPrint is a standard data representation for network traffic, designed for direct use with machine learning algorithms, eliminating the need for feature engineering in various traffic analysis tasks. Developing a Rust implementation of nPrint will simplify the creation of network systems that leverage real-world ML deployments, rather than just training and deploying models offline.
+mod anonymization;
+#[cfg(feature = "ndarray")]
+mod array;
+mod builder;
+#[cfg(feature = "pcap")]
+mod capture;
+mod config;
+mod error;
+mod flow;
+mod packed;
 pub(crate) mod protocols;
-use crate::protocols::ipv4::Ipv4Header;
+mod reassembly;
+#[cfg(feature = "arrow")]
+mod record_batch;
+mod sequence;
+mod sink;
+mod stats;
+mod window;
+pub use crate::anonymization::AnonField;
+pub use crate::builder::NprintBuilder;
+pub use crate::config::{
+    BitOrder, FillValue, Layout, LinkLayer, NprintConfig, PayloadSource, TcpFlagsEncoding,
+    TruncationPolicy,
+};
+pub use crate::error::NprintError;
+pub use crate::flow::{Direction, FlowKey, FlowTable};
+use crate::protocols::ah::{skip_ah, AhHeader};
+use crate::protocols::arp::ArpHeader;
+use crate::protocols::dhcp::{is_dhcp, DhcpHeader};
+use crate::protocols::dns::{is_dns, DnsHeader};
+use crate::protocols::esp::EspHeader;
+use crate::protocols::ethernet::EthernetHeader;
+use crate::protocols::frame::{encode_lengths, FrameMetaHeader};
+use crate::protocols::gre::{decapsulate, GreHeader};
+use crate::protocols::gtp::{self, is_gtp_u, GtpHeader};
+use crate::protocols::icmpv6::Icmpv6Header;
+use crate::protocols::ipv4::{ipv4_checksum_valid, Ipv4Header, IPV4_ADDRESSES, IPV4_CHECKSUM};
+use crate::protocols::ipv6::skip_extensions;
+use crate::protocols::link;
+use crate::protocols::mpls::{is_mpls, pop_labels, MplsHeader};
 use crate::protocols::packet::PacketHeader;
-use crate::protocols::tcp::TcpHeader;
-use crate::protocols::udp::UdpHeader;
+use crate::protocols::payload::PayloadHeader;
+use crate::protocols::quic::{is_quic, QuicHeader};
+use crate::protocols::sctp::SctpHeader;
+use crate::protocols::tcp::{tcp_checksum_valid, TcpHeader, TCP_CHECKSUM};
+use crate::protocols::tcp_options::TcpOptionsHeader;
+use crate::protocols::timing::{encode_delta, TimingHeader};
+use crate::protocols::tls::{is_client_hello, TlsClientHelloHeader};
+use crate::protocols::udp::{
+    udp_checksum_valid, udp_lite_checksum_valid, udp_truncated, UdpHeader, UDP_CHECKSUM,
+};
+use crate::protocols::util::{
+    bits_to_bytes, bits_to_value, remove, update_checksum, value_to_bits,
+};
+use crate::protocols::vlan::{is_vlan, VlanHeader, MAX_VLAN_DEPTH};
+#[cfg(feature = "arrow")]
+pub use crate::record_batch::{SCHEMA_HASH_KEY, SCHEMA_VERSION_KEY};
+use crate::sequence::SequenceTracker;
+pub use crate::sink::NprintSink;
+use crate::stats::counted;
+pub use crate::stats::ParseStats;
+use crate::window::WindowScaleTracker;
 
-use pnet::packet::ethernet::{EtherTypes, EthernetPacket};
-use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::ethernet::{EtherType, EtherTypes, EthernetPacket};
+use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
 use pnet::packet::ipv4::Ipv4Packet;
+use pnet::packet::ipv6::Ipv6Packet;
+use pnet::packet::tcp::TcpPacket;
+use pnet::packet::udp::UdpPacket;
 use pnet::packet::vlan::VlanPacket;
 use pnet::packet::Packet;
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::IpAddr;
+use std::ops::Range;
+use std::time::Duration;
+
+/// Checksum fields reduced to their first bit when `checksum_validity` is set.
+const CHECKSUM_FIELDS: [&str; 3] = ["ipv4_cksum", "tcp_cksum", "udp_cksum"];
+
+/// Port fields reduced to their range when `port_categories` is set.
+const PORT_FIELDS: [&str; 4] = ["tcp_sprt", "tcp_dprt", "udp_sport", "udp_dport"];
+
+/// Number of bits of the range of a port, see `port_category()`.
+const PORT_CATEGORY_BITS: usize = 2;
+
+/// Version of the column schema, bumped whenever the fields of an existing protocol change.
+///
+/// See `Nprint::schema_hash()` to compare the columns of a given protocol stack.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Maximum number of nested tunnels, GRE or IP-in-IP, decapsulated before giving up on a packet.
+const MAX_TUNNEL_DEPTH: usize = 4;
+
+/// TCP flag bits left out with `TcpFlagsEncoding::Value`.
+const TCP_FLAG_FIELDS: [&str; 9] = [
+    "tcp_ns", "tcp_cwr", "tcp_ece", "tcp_urg", "tcp_ackf", "tcp_psh", "tcp_rst", "tcp_syn",
+    "tcp_fin",
+];
 
 /// The `Nprint` structure stores a collection of parsed packet headers,
 /// associated with a single network flow (e.g., a connection or tuple).
 ///
 /// It maintains the list of protocols used for parsing and tracks the number of packets processed.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Nprint {
-    /// Vector that contains all the parsed headers for each packet.
-    data: Vec<Headers>,
+    /// Queue that contains all the parsed headers for each packet, oldest first.
+    data: VecDeque<Headers>,
     /// Ordered list of Protocol selected for this Nprint.
     protocols: Vec<ProtocolType>,
     /// Number of packets processed.
     nb_pkt: usize,
+    /// Configuration applied to every parsed packet.
+    config: NprintConfig,
+    /// First TCP sequence numbers of the flow, used when `relative_seq` is set.
+    sequence: SequenceTracker,
+    /// Window scales of the flow, used when `scale_window` is set.
+    window: WindowScaleTracker,
+    /// Indexes of the selected fields' bits, `None` when every field is kept.
+    mask: Option<Vec<usize>>,
+    /// Outcome of the parsing of every added packet.
+    stats: ParseStats,
+    /// Capture time of the last stored packet, to compute the inter-arrival times.
+    last_timestamp: Option<Duration>,
+    /// 5-tuple of the first packet carrying IPv4, `None` until then.
+    flow_key: Option<FlowKey>,
+    /// Direction of each stored packet relative to `flow_key`, oldest first.
+    directions: VecDeque<Direction>,
+    /// Names of the values of a packet row, computed on first use.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    packet_headers: OnceCell<Vec<String>>,
+    /// Names of the values of `print()` with `Layout::ProtocolMajor`, reset on every stored packet.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    headers: OnceCell<Vec<String>>,
 }
 
-/// Internal structure handling the extracted information of ONE single packet.
-#[derive(Debug)]
-pub(crate) struct Headers {
-    /// Vector that contains ordered values extracted informations
-    pub data: Vec<Box<dyn PacketHeader>>,
+/// Extracted information of ONE single packet, a row of an `Nprint`.
+///
+/// Use `Headers::parse()` to parse a lone packet without the flow handling of an `Nprint`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Headers {
+    /// Contiguous bits of every protocol of the `Nprint`, in order, before the field selection.
+    pub(crate) data: Vec<f32>,
+    /// Whether the header of each protocol was parsed, `false` if its bits are the fill value.
+    pub(crate) parsed: Vec<bool>,
 }
 
 /// Enum that contains the current implemented type extractable
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ProtocolType {
+    Ethernet,
     Ipv4,
     Tcp,
     Udp,
+    Vlan,
+    Dns,
+    Arp,
+    Sctp,
+    /// Outermost GRE header, the encapsulated IPv4 and transport headers replace the outer ones.
+    Gre,
+    Icmpv6,
+    /// Transport payload, its width depends on `NprintConfig::max_payload_bytes`.
+    Payload,
+    Quic,
+    TcpOptions,
+    /// Inter-arrival time from the previous packet, see `Nprint::add_with_timestamp`.
+    Timing,
+    Dhcp,
+    /// Top entry of the MPLS label stack, the labeled IPv4 or IPv6 packet is parsed underneath.
+    Mpls,
+    /// TLS ClientHello starting a TCP payload: versions and the first 32 cipher suites.
+    TlsClientHello,
+    /// Captured length of the frame and its length on the wire, see `Nprint::add_with_wire_len`.
+    FrameMeta,
+    /// GTP-U header of a G-PDU, the tunneled IPv4 or IPv6 packet is parsed underneath.
+    Gtp,
+    /// Visible part of an ESP header, its encrypted payload isn't parsed.
+    Esp,
+    /// AH header, the upper-layer protocol it authenticates is parsed underneath.
+    Ah,
+}
+
+impl ProtocolType {
+    /// Every protocol that can be extracted.
+    pub const ALL: [ProtocolType; 21] = [
+        ProtocolType::Ethernet,
+        ProtocolType::Ipv4,
+        ProtocolType::Tcp,
+        ProtocolType::Udp,
+        ProtocolType::Vlan,
+        ProtocolType::Dns,
+        ProtocolType::Arp,
+        ProtocolType::Sctp,
+        ProtocolType::Gre,
+        ProtocolType::Icmpv6,
+        ProtocolType::Payload,
+        ProtocolType::Quic,
+        ProtocolType::TcpOptions,
+        ProtocolType::Timing,
+        ProtocolType::Dhcp,
+        ProtocolType::Mpls,
+        ProtocolType::TlsClientHello,
+        ProtocolType::FrameMeta,
+        ProtocolType::Gtp,
+        ProtocolType::Esp,
+        ProtocolType::Ah,
+    ];
+
+    /// Returns the number of bits extracted for this protocol in every packet.
+    ///
+    /// # Returns
+    ///
+    /// The width of the protocol, equal to the length of its header names, before any
+    /// field selection or checksum normalization.
+    pub fn width(&self) -> usize {
+        match self {
+            ProtocolType::Ethernet => EthernetHeader::WIDTH,
+            ProtocolType::Ipv4 => Ipv4Header::WIDTH,
+            ProtocolType::Tcp => TcpHeader::WIDTH,
+            ProtocolType::Udp => UdpHeader::WIDTH,
+            ProtocolType::Vlan => VlanHeader::WIDTH,
+            ProtocolType::Dns => DnsHeader::WIDTH,
+            ProtocolType::Arp => ArpHeader::WIDTH,
+            ProtocolType::Sctp => SctpHeader::WIDTH,
+            ProtocolType::Gre => GreHeader::WIDTH,
+            ProtocolType::Icmpv6 => Icmpv6Header::WIDTH,
+            ProtocolType::Quic => QuicHeader::WIDTH,
+            ProtocolType::TcpOptions => TcpOptionsHeader::WIDTH,
+            ProtocolType::Timing => TimingHeader::WIDTH,
+            ProtocolType::FrameMeta => FrameMetaHeader::WIDTH,
+            ProtocolType::Gtp => GtpHeader::WIDTH,
+            ProtocolType::Esp => EspHeader::WIDTH,
+            ProtocolType::Ah => AhHeader::WIDTH,
+            ProtocolType::Dhcp => DhcpHeader::WIDTH,
+            ProtocolType::Mpls => MplsHeader::WIDTH,
+            ProtocolType::TlsClientHello => TlsClientHelloHeader::WIDTH,
+            ProtocolType::Payload => PayloadHeader::WIDTH,
+        }
+    }
+
+    /// Returns the number of bits extracted for this protocol following the given configuration.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration of the parsing, setting in particular the payload length.
+    pub(crate) fn width_with_config(&self, config: &NprintConfig) -> usize {
+        match self {
+            ProtocolType::Payload => config.payload_bytes() * 8,
+            ProtocolType::Ipv4 => Ipv4Header::width(config),
+            ProtocolType::Tcp => TcpHeader::width(config),
+            _ => self.width(),
+        }
+    }
+
+    /// Returns the names of the bits extracted for this protocol in every packet.
+    ///
+    /// # Returns
+    ///
+    /// The header names with the default configuration, as many as `width()`, each one
+    /// suffixed with its index in the field (e.g., `tcp_sprt_0`).
+    pub fn output_headers(&self) -> Vec<String> {
+        self.headers_with_config(&NprintConfig::default())
+    }
+
+    /// Returns the names of the bits extracted for this protocol following the given
+    /// configuration, before any field selection or checksum normalization.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration of the parsing.
+    pub(crate) fn headers_with_config(&self, config: &NprintConfig) -> Vec<String> {
+        (self.ops().headers)(config)
+    }
+
+    /// Returns the bit-level operations of the header type of this protocol.
+    fn ops(&self) -> HeaderOps {
+        match self {
+            ProtocolType::Ethernet => HeaderOps::of::<EthernetHeader>(),
+            ProtocolType::Ipv4 => HeaderOps::of::<Ipv4Header>(),
+            ProtocolType::Tcp => HeaderOps::of::<TcpHeader>(),
+            ProtocolType::Udp => HeaderOps::of::<UdpHeader>(),
+            ProtocolType::Vlan => HeaderOps::of::<VlanHeader>(),
+            ProtocolType::Dns => HeaderOps::of::<DnsHeader>(),
+            ProtocolType::Arp => HeaderOps::of::<ArpHeader>(),
+            ProtocolType::Sctp => HeaderOps::of::<SctpHeader>(),
+            ProtocolType::Gre => HeaderOps::of::<GreHeader>(),
+            ProtocolType::Icmpv6 => HeaderOps::of::<Icmpv6Header>(),
+            ProtocolType::Quic => HeaderOps::of::<QuicHeader>(),
+            ProtocolType::TcpOptions => HeaderOps::of::<TcpOptionsHeader>(),
+            ProtocolType::Timing => HeaderOps::of::<TimingHeader>(),
+            ProtocolType::FrameMeta => HeaderOps::of::<FrameMetaHeader>(),
+            ProtocolType::Gtp => HeaderOps::of::<GtpHeader>(),
+            ProtocolType::Esp => HeaderOps::of::<EspHeader>(),
+            ProtocolType::Ah => HeaderOps::of::<AhHeader>(),
+            ProtocolType::Dhcp => HeaderOps::of::<DhcpHeader>(),
+            ProtocolType::Mpls => HeaderOps::of::<MplsHeader>(),
+            ProtocolType::TlsClientHello => HeaderOps::of::<TlsClientHelloHeader>(),
+            ProtocolType::Payload => HeaderOps::of::<PayloadHeader>(),
+        }
+    }
+}
+
+/// Bit-level operations of a header type, applied to its range of a packet row.
+struct HeaderOps {
+    /// See `PacketHeader::headers_with_config()`.
+    headers: fn(&NprintConfig) -> Vec<String>,
+    /// See `PacketHeader::anonymize_bits_with_key()`.
+    anonymize_with_key: fn(&mut [f32], &[u8; 16], BitOrder),
+    /// See `PacketHeader::reassemble()`.
+    reassemble: fn(&[f32], BitOrder) -> Option<Vec<u8>>,
+}
+
+impl HeaderOps {
+    /// Returns the operations of the header type `H`.
+    fn of<H: PacketHeader>() -> HeaderOps {
+        HeaderOps {
+            headers: H::headers_with_config,
+            anonymize_with_key: H::anonymize_bits_with_key,
+            reassemble: H::reassemble,
+        }
+    }
 }
 
 impl Nprint {
@@ -68,145 +353,2324 @@ impl Nprint {
     /// let nprint = Nprint::new(&packet, vec![ProtocolType::Ipv4, ProtocolType::Tcp,ProtocolType::Udp]);
     /// ```    
     pub fn new(packet: &[u8], protocols: Vec<ProtocolType>) -> Nprint {
-        Nprint {
-            data: vec![Headers::new(packet, &protocols)],
-            protocols,
-            nb_pkt: 1,
-        }
+        Nprint::new_with_config(packet, protocols, NprintConfig::default())
     }
 
-    /// Return all the nprint values in a vector of f32.
+    /// Creates a new `Nprint` like `new()`, the packet being given as a hex string.
     ///
-    /// This is useful for exporting structured packet data for ML models or analytics.
+    /// Whitespace and commas separate the digits as they please, and `0x` prefixes are
+    /// stripped, a prefixed number of an odd number of digits being padded with a leading zero.
+    /// Hex streams, hex dumps and byte array literals can all be pasted as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `hex` - Hex digits of the raw packet data.
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
     ///
     /// # Returns
     ///
-    /// A `Vec<f32>` containing all protocol data from each parsed packet in order.
-    pub fn print(&self) -> Vec<f32> {
-        let mut output = vec![];
-        for header in &self.data {
-            for proto in &header.data {
-                output.extend((*proto).get_data());
-            }
-        }
-        output
+    /// A new `Nprint` instance containing the parsed headers of the packet, or
+    /// `NprintError::MalformedHex` for a non-hex digit or an odd number of digits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    ///
+    /// let nprint = Nprint::from_hex(
+    ///     "000000000000 000000000000 0800
+    ///      4500003cf51b400040061b24c0a82b25c6267888
+    ///      97a401bb962e5e0b00000000a0027210 25d40000
+    ///      020405b40402080ae3e2142300000000 01030307",
+    ///     vec![ProtocolType::Ipv4, ProtocolType::Tcp],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(nprint.count(), 1);
+    /// ```
+    pub fn from_hex(hex: &str, protocols: Vec<ProtocolType>) -> Result<Nprint, NprintError> {
+        Ok(Nprint::new(&decode_hex(hex)?, protocols))
     }
 
-    /// Adds a new packet to the `Nprint` structure, parsing it using the existing protocols.
+    /// Creates a new `Nprint` like `new()`, parsing the packets following the given configuration.
     ///
     /// # Arguments
     ///
-    /// * `packet` - A byte slice representing the new raw packet.
-    pub fn add(&mut self, packet: &[u8]) {
-        self.data.push(Headers::new(packet, &self.protocols));
-        self.nb_pkt += 1;
+    /// * `packet` - A byte slice representing the raw packet data.
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
+    /// * `config` - A `NprintConfig` applied to this packet and all the added ones.
+    ///
+    /// # Returns
+    ///
+    /// A new `Nprint` instance containing the parsed headers of the packet.
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{FillValue, Nprint, NprintConfig, ProtocolType};
+    ///
+    /// let config = NprintConfig {
+    ///     fill_value: FillValue::Zero,
+    ///     ..Default::default()
+    /// };
+    /// let nprint = Nprint::new_with_config(&[0x0], vec![ProtocolType::Udp], config);
+    /// assert_eq!(nprint.print(), vec![0.; 64]);
+    /// ```
+    pub fn new_with_config(
+        packet: &[u8],
+        protocols: Vec<ProtocolType>,
+        config: NprintConfig,
+    ) -> Nprint {
+        let mut nprint = Nprint::empty_with_config(protocols, config);
+        nprint.add(packet);
+        nprint
     }
 
-    /// Returns the number of packets.
+    /// Creates a new `Nprint` like `new_with_config()`, from a packet captured at the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet data.
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
+    /// * `config` - A `NprintConfig` applied to this packet and all the added ones.
+    /// * `timestamp` - Capture time of the packet, from any fixed origin.
     ///
     /// # Returns
     ///
-    /// A `usize` representing the number of packets within the structure.
-    pub fn count(&self) -> usize {
-        self.nb_pkt
+    /// A new `Nprint` instance containing the parsed headers of the packet, with a null inter-arrival time.
+    pub fn new_with_timestamp(
+        packet: &[u8],
+        protocols: Vec<ProtocolType>,
+        config: NprintConfig,
+        timestamp: Duration,
+    ) -> Nprint {
+        let mut nprint = Nprint::empty_with_config(protocols, config);
+        nprint.add_with_timestamp(packet, timestamp);
+        nprint
     }
 
-    /// Return the name list of all fields of all the protocols present in this Nprint
+    /// Creates an `Nprint` from every packet of a flow at once, like `new()` followed by `add()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `packets` - Raw packets of the flow, in capture order.
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
     ///
     /// # Returns
     ///
-    /// A list of header names that are prefixed by the protocol and suffixed with an index (e.g., `tcp_sprt_0`, `tcp_sprt_1`).
-    pub fn get_headers(&self) -> Vec<String> {
-        let mut output = vec![];
-        for proto in &self.protocols {
-            match proto {
-                ProtocolType::Ipv4 => {
-                    output.extend(Ipv4Header::get_headers());
-                }
-                ProtocolType::Tcp => {
-                    output.extend(TcpHeader::get_headers());
-                }
-                ProtocolType::Udp => {
-                    output.extend(UdpHeader::get_headers());
-                }
-            }
+    /// A new `Nprint` holding the parsed headers of every packet, or `None` if `packets` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    ///
+    /// let packets: [&[u8]; 2] = [&[0x0], &[0x1]];
+    /// let nprint = Nprint::from_packets(&packets, vec![ProtocolType::Udp]).unwrap();
+    /// assert_eq!(nprint.count(), 2);
+    /// assert!(Nprint::from_packets(&[], vec![ProtocolType::Udp]).is_none());
+    /// ```
+    pub fn from_packets(packets: &[&[u8]], protocols: Vec<ProtocolType>) -> Option<Nprint> {
+        if packets.is_empty() {
+            return None;
         }
-        output
+        let mut nprint = Nprint::empty(protocols);
+        nprint.data.reserve(packets.len());
+        nprint.directions.reserve(packets.len());
+        for packet in packets {
+            nprint.add(packet);
+        }
+        Some(nprint)
     }
 
-    /// Remove sensitive data from the captured header
-    pub fn anonymize(&mut self) {
-        for packet in self.data.iter_mut() {
-            for header in packet.data.iter_mut() {
-                header.anonymize();
-            }
-        }
+    /// Creates a new `Nprint` from an Ethernet frame already decoded by `pnet`, like `new()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - Ethernet frame of the first packet.
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
+    ///
+    /// # Returns
+    ///
+    /// A new `Nprint` instance containing the parsed headers of the frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    /// use pnet::packet::ethernet::EthernetPacket;
+    ///
+    /// let bytes = [0x0; 14];
+    /// let frame = EthernetPacket::new(&bytes).unwrap();
+    /// let nprint = Nprint::from_ethernet(&frame, vec![ProtocolType::Ethernet]);
+    /// assert_eq!(nprint.print(), vec![0.; 112]);
+    /// ```
+    pub fn from_ethernet(frame: &EthernetPacket, protocols: Vec<ProtocolType>) -> Nprint {
+        let mut nprint = Nprint::empty(protocols);
+        nprint.add_ethernet(frame);
+        nprint
     }
-}
 
-impl Headers {
-    /// Creates a new `Headers` instance by parsing the given packet data
-    /// according to the specified list of protocols.
+    /// Creates a new `Nprint` from a packet whose network layer was already located, like
+    /// `new()`.
+    ///
+    /// The bytes preceding the offset are skipped, the IPv4 or IPv6 header being told apart
+    /// by its version nibble as with `LinkLayer::RawIp`. The Ethernet and VLAN headers are
+    /// absent.
     ///
     /// # Arguments
     ///
-    /// * `packet` - A byte slice representing the raw packet.
-    /// * `protocols` - A slice of `ProtocolType` enums specifying the protocol to parsed.
+    /// * `packet` - A byte slice representing the raw packet data.
+    /// * `l3_offset` - Offset of the IP header in the packet, in bytes.
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
     ///
     /// # Returns
     ///
-    /// A `Headers` struct containing the parsed protocol headers as specified.
+    /// A new `Nprint` instance containing the parsed headers of the packet, every header
+    /// being absent if the offset lies beyond the packet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
     ///
-    pub fn new(packet: &[u8], protocols: &[ProtocolType]) -> Headers {
-        let mut data: Vec<Box<dyn PacketHeader>> = Vec::with_capacity(protocols.len());
-        let mut ipv4 = None;
-        let mut tcp = None;
-        let mut udp = None;
+    /// let mut packet = vec![0xff; 6];
+    /// packet.extend([0x45, 0x00, 0x00, 0x14, 0x00, 0x01, 0x00, 0x00, 0x40, 0x06]);
+    /// packet.extend([0x00; 10]);
+    /// let nprint = Nprint::from_offset(&packet, 6, vec![ProtocolType::Ipv4]);
+    /// assert_eq!(nprint.ipv4_ttl(0), Some(64));
+    /// ```
+    pub fn from_offset(packet: &[u8], l3_offset: usize, protocols: Vec<ProtocolType>) -> Nprint {
+        let mut nprint = Nprint::empty(protocols);
+        nprint.add_from_offset(packet, l3_offset);
+        nprint
+    }
 
-        if let Some(ethernet) = EthernetPacket::new(packet) {
-            let mut ethertype = ethernet.get_ethertype();
-            let mut payload = ethernet.payload().to_vec();
+    /// Creates an `Nprint` without any packet, to be filled with `add()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    ///
+    /// let mut nprint = Nprint::empty(vec![ProtocolType::Udp]);
+    /// assert_eq!(nprint.count(), 0);
+    /// assert!(nprint.print().is_empty());
+    /// for _ in 0..3 {
+    ///     nprint.add(&[0x0]);
+    /// }
+    /// assert_eq!(nprint.count(), 3);
+    /// ```
+    pub fn empty(protocols: Vec<ProtocolType>) -> Nprint {
+        Nprint::empty_with_config(protocols, NprintConfig::default())
+    }
 
-            // Pop VLAN's Header
-            if ethertype == EtherTypes::Vlan {
-                if let Some(vlan_packet) = VlanPacket::new(&payload) {
-                    ethertype = vlan_packet.get_ethertype();
-                    payload = vlan_packet.payload().to_vec();
-                }
-            }
+    /// Creates an `Nprint` without any packet like `empty()`, parsing the packets following
+    /// the given configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
+    /// * `config` - A `NprintConfig` applied to all the added packets.
+    pub fn empty_with_config(protocols: Vec<ProtocolType>, config: NprintConfig) -> Nprint {
+        let mut nprint = Nprint {
+            data: VecDeque::new(),
+            protocols,
+            nb_pkt: 0,
+            config,
+            sequence: SequenceTracker::default(),
+            window: WindowScaleTracker::default(),
+            mask: None,
+            stats: ParseStats::default(),
+            last_timestamp: None,
+            flow_key: None,
+            directions: VecDeque::new(),
+            packet_headers: OnceCell::new(),
+            headers: OnceCell::new(),
+        };
+        nprint.mask = nprint.field_mask();
+        nprint
+    }
 
-            if ethertype == EtherTypes::Ipv4 {
-                if let Some(ipv4_packet) = Ipv4Packet::new(&payload) {
-                    ipv4 = Some(Ipv4Header::new(&payload));
+    /// Creates an `Nprint` without any packet, holding the last `k` added packets.
+    ///
+    /// Once `k` packets are held, every added packet evicts the oldest one, and `print()`
+    /// always returns `k` packets, padded with the fill value while fewer were added. Meant
+    /// for online inference, the model running again on every new packet.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
+    /// * `k` - Number of packets of the window.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    ///
+    /// let mut nprint = Nprint::with_window(vec![ProtocolType::Udp], 3);
+    /// assert_eq!(nprint.print(), vec![-1.; 3 * 64]);
+    /// for _ in 0..5 {
+    ///     nprint.add(&[0x0]);
+    /// }
+    /// assert_eq!(nprint.count(), 3);
+    /// ```
+    pub fn with_window(protocols: Vec<ProtocolType>, k: usize) -> Nprint {
+        let config = NprintConfig {
+            max_packets: Some(k),
+            truncation: TruncationPolicy::DropOldest,
+            pad_packets: true,
+            ..Default::default()
+        };
+        Nprint::empty_with_config(protocols, config)
+    }
 
-                    match ipv4_packet.get_next_level_protocol() {
-                        IpNextHeaderProtocols::Tcp => {
-                            tcp = Some(TcpHeader::new(ipv4_packet.payload()));
-                        }
-                        IpNextHeaderProtocols::Udp => {
-                            udp = Some(UdpHeader::new(ipv4_packet.payload()));
-                        }
-                        _ => {}
-                    }
+    /// Return all the nprint values in a vector of f32.
+    ///
+    /// This is useful for exporting structured packet data for ML models or analytics.
+    ///
+    /// The values are ordered following the configured `Layout`: packet after packet by
+    /// default, or protocol after protocol with `Layout::ProtocolMajor`. With `pad_packets`,
+    /// packets filled with the fill value are appended up to `max_packets`.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<f32>` containing all protocol data from each parsed packet in order.
+    pub fn print(&self) -> Vec<f32> {
+        let fill = self.config.fill_value.value();
+        match self.config.layout {
+            Layout::PacketMajor => {
+                let len = self.bits_per_packet() * self.printed_packets();
+                let mut output = Vec::with_capacity(len);
+                for headers in &self.data {
+                    self.extend_selected(&mut output, headers);
                 }
+                output.resize(len, fill);
+                output
+            }
+            Layout::ProtocolMajor => {
+                let mut rows: Vec<Vec<f32>> = self.iter_packets().collect();
+                rows.resize(self.printed_packets(), vec![fill; self.bits_per_packet()]);
+                self.protocol_ranges()
+                    .into_iter()
+                    .flat_map(|range| rows.iter().flat_map(move |row| row[range.clone()].to_vec()))
+                    .collect()
             }
-        } else {
-            eprintln!("Not an EthernetPacket packet, returning default...");
         }
+    }
 
-        for proto in protocols {
-            match proto {
-                ProtocolType::Ipv4 => {
-                    data.push(Box::new(ipv4.clone().unwrap_or_else(Ipv4Header::default)));
-                }
-                ProtocolType::Tcp => {
-                    data.push(Box::new(tcp.clone().unwrap_or_else(TcpHeader::default)));
-                }
-                ProtocolType::Udp => {
-                    data.push(Box::new(udp.clone().unwrap_or_else(UdpHeader::default)));
+    /// Returns all the nprint values as `i8` trits, a quarter of the memory of `print()`.
+    ///
+    /// The values are ordered as in `print()`. Bits are kept as `1` and `0`, and any other
+    /// value, such as absent fields whatever the fill value or non-binary features
+    /// (`tcp_flags_value`, inter-arrival times), becomes `-1`.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<i8>` of `-1`, `0` and `1`, as long as `print()`.
+    pub fn print_trits(&self) -> Vec<i8> {
+        self.print()
+            .into_iter()
+            .map(|value| {
+                if value == 1. {
+                    1
+                } else if value == 0. {
+                    0
+                } else {
+                    -1
                 }
+            })
+            .collect()
+    }
+
+    /// Returns the presence mask of the nprint values, e.g. the attention mask of a transformer.
+    ///
+    /// The values are ordered as in `print()`: `1.0` marks a bit of a parsed header, and `0.0`
+    /// a bit of a missing or unparsable one, or of a padding packet, whatever the fill value.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<f32>` of `0.0` and `1.0`, as long as `print()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    ///
+    /// let nprint = Nprint::new(&[0x0], vec![ProtocolType::Udp]);
+    /// assert_eq!(nprint.print_mask(), vec![0.; 64]);
+    /// ```
+    pub fn print_mask(&self) -> Vec<f32> {
+        let ranges = self.protocol_ranges();
+        let mut rows: Vec<&[bool]> = self
+            .data
+            .iter()
+            .map(|headers| &headers.parsed[..])
+            .collect();
+        let padding = vec![false; self.protocols.len()];
+        rows.resize(self.printed_packets(), &padding);
+        let presence = |parsed: bool, range: &Range<usize>| {
+            std::iter::repeat_n(f32::from(u8::from(parsed)), range.len())
+        };
+        match self.config.layout {
+            Layout::PacketMajor => rows
+                .iter()
+                .flat_map(|parsed| {
+                    parsed
+                        .iter()
+                        .zip(&ranges)
+                        .flat_map(|(&parsed, range)| presence(parsed, range))
+                })
+                .collect(),
+            Layout::ProtocolMajor => ranges
+                .iter()
+                .enumerate()
+                .flat_map(|(position, range)| {
+                    rows.iter()
+                        .flat_map(move |parsed| presence(parsed[position], range))
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the nprint values as a fixed-size matrix of `max_packets` rows.
+    ///
+    /// Extra packets are dropped, and missing packets are rows filled with the configured fill value.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_packets` - Number of rows of the matrix.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of `max_packets` rows, each one of `get_headers().len()` values.
+    pub fn print_matrix(&self, max_packets: usize) -> Vec<Vec<f32>> {
+        let width = self.bits_per_packet();
+        let fill = self.config.fill_value.value();
+        let mut matrix: Vec<Vec<f32>> = self.iter_packets().take(max_packets).collect();
+        matrix.resize(max_packets, vec![fill; width]);
+        matrix
+    }
+
+    /// Returns the nprint values of a single packet.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the packet in the `Nprint`, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// The concatenated protocol data of the packet, or `None` if `index` is out of range.
+    pub fn packet_data(&self, index: usize) -> Option<Vec<f32>> {
+        self.data.get(index).map(|headers| self.select(headers))
+    }
+
+    /// Reassembles the raw bytes of a header of a single packet.
+    ///
+    /// Supported for `ProtocolType::Ipv4`, `ProtocolType::Tcp` and `ProtocolType::Udp`.
+    /// Anonymized fields are rebuilt as zeroes.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the packet in the `Nprint`, starting at 0.
+    /// * `protocol` - Protocol of the header.
+    ///
+    /// # Returns
+    ///
+    /// The header bytes, or `None` if the packet or the header is absent, or the protocol unsupported.
+    pub fn header_bytes(&self, index: usize, protocol: ProtocolType) -> Option<Vec<u8>> {
+        let headers = self.data.get(index)?;
+        let range = header_range(&self.protocols, &self.config, &protocol)?;
+        let bits = wire_bits(protocol, &headers.data[range], &self.config);
+        (protocol.ops().reassemble)(&bits, self.config.bit_order)
+    }
+
+    /// Returns whether the header of a protocol was parsed for a single packet.
+    ///
+    /// A header that is missing from the packet, or that is malformed, is not parsed and
+    /// holds the fill value.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the packet in the `Nprint`, starting at 0.
+    /// * `protocol` - Protocol of the header.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the header was parsed, or `None` if `index` is out of range or the protocol
+    /// isn't part of the `Nprint`.
+    pub fn is_parsed(&self, index: usize, protocol: ProtocolType) -> Option<bool> {
+        let position = self.protocols.iter().position(|proto| *proto == protocol)?;
+        Some(self.data.get(index)?.parsed[position])
+    }
+
+    /// Returns the time to live of the IPv4 header of a single packet.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the packet in the `Nprint`, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// The time to live, or `None` if the packet is out of range or its IPv4 header absent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    ///
+    /// let nprint = Nprint::from_hex(
+    ///     "000000000000 000000000000 0800 45000014000100004006000000000000 00000000",
+    ///     vec![ProtocolType::Ipv4],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(nprint.ipv4_ttl(0), Some(64));
+    /// ```
+    pub fn ipv4_ttl(&self, index: usize) -> Option<u8> {
+        Ipv4Header::ttl(
+            self.parsed_header(index, ProtocolType::Ipv4)?,
+            self.config.bit_order,
+        )
+    }
+
+    /// Returns the window size of the TCP header of a single packet, unscaled.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the packet in the `Nprint`, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// The window size, or `None` if the packet is out of range or its TCP header absent.
+    pub fn tcp_window(&self, index: usize) -> Option<u16> {
+        TcpHeader::window(
+            self.parsed_header(index, ProtocolType::Tcp)?,
+            self.config.bit_order,
+        )
+    }
+
+    /// Returns the flags byte (`tcp_cwr` to `tcp_fin`) of the TCP header of a single packet.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the packet in the `Nprint`, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// The flags, or `None` if the packet is out of range or its TCP header absent.
+    pub fn tcp_flags(&self, index: usize) -> Option<u8> {
+        TcpHeader::flags(
+            self.parsed_header(index, ProtocolType::Tcp)?,
+            self.config.bit_order,
+        )
+    }
+
+    /// Returns the bits of a parsed header of a single packet, whatever the field selection.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the packet in the `Nprint`, starting at 0.
+    /// * `protocol` - Protocol of the header.
+    fn parsed_header(&self, index: usize, protocol: ProtocolType) -> Option<&[f32]> {
+        if !self.is_parsed(index, protocol)? {
+            return None;
+        }
+        let range = header_range(&self.protocols, &self.config, &protocol)?;
+        Some(&self.data[index].data[range])
+    }
+
+    /// Returns the share of the stored packets of which at least one header was parsed.
+    ///
+    /// Useful to leave out flows whose packets mostly fell back to the fill value.
+    ///
+    /// # Returns
+    ///
+    /// A ratio between 0 and 1, 0 if the `Nprint` holds no packet.
+    pub fn parsed_ratio(&self) -> f32 {
+        if self.data.is_empty() {
+            return 0.;
+        }
+        let parsed = self
+            .data
+            .iter()
+            .filter(|headers| headers.parsed.contains(&true))
+            .count();
+        parsed as f32 / self.data.len() as f32
+    }
+
+    /// Compares the values of two `Nprint` bit by bit, e.g. before and after anonymization.
+    ///
+    /// NaN values at the same position are considered equal.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Nprint` to compare with.
+    ///
+    /// # Returns
+    ///
+    /// The index in `print()` and both values of every differing bit, or `None` if the two
+    /// `Nprint` don't have the same protocols, packet count or number of values.
+    pub fn diff(&self, other: &Nprint) -> Option<Vec<(usize, f32, f32)>> {
+        if self.protocols != other.protocols || self.count() != other.count() {
+            return None;
+        }
+        let (left, right) = (self.print(), other.print());
+        if left.len() != right.len() {
+            return None;
+        }
+        Some(
+            left.into_iter()
+                .zip(right)
+                .enumerate()
+                .filter(|(_, (l, r))| l != r && !(l.is_nan() && r.is_nan()))
+                .map(|(index, (l, r))| (index, l, r))
+                .collect(),
+        )
+    }
+
+    /// Returns the number of packets of `print()`, the padding of `pad_packets` included.
+    fn printed_packets(&self) -> usize {
+        match self.config.max_packets {
+            Some(max_packets) if self.config.pad_packets => max_packets.max(self.data.len()),
+            _ => self.data.len(),
+        }
+    }
+
+    /// Returns an iterator over the nprint values of each packet, in order.
+    pub fn iter_packets(&self) -> impl Iterator<Item = Vec<f32>> + '_ {
+        self.data.iter().map(|headers| self.select(headers))
+    }
+
+    /// Returns an iterator over the values of each header of each packet, tagged with its protocol.
+    ///
+    /// Headers follow the order of the protocols, packet after packet. Each slice holds every
+    /// field of the protocol, the field selection being ignored, and borrows the stored values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    ///
+    /// let nprint = Nprint::new(&[0x0], vec![ProtocolType::Ipv4, ProtocolType::Udp]);
+    /// let udp: Vec<&[f32]> = nprint
+    ///     .headers_with_protocols()
+    ///     .filter(|(protocol, _)| **protocol == ProtocolType::Udp)
+    ///     .map(|(_, values)| values)
+    ///     .collect();
+    /// assert_eq!(udp, vec![&[-1.; 64][..]]);
+    /// ```
+    pub fn headers_with_protocols(&self) -> impl Iterator<Item = (&ProtocolType, &[f32])> + '_ {
+        self.data.iter().flat_map(move |headers| {
+            header_ranges(&self.protocols, &self.config)
+                .map(move |(proto, range)| (proto, &headers.data[range]))
+        })
+    }
+
+    /// Returns the values of a packet, restricted to the selected fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - Parsed headers of the packet.
+    fn select(&self, headers: &Headers) -> Vec<f32> {
+        let mut values = Vec::with_capacity(self.bits_per_packet());
+        self.extend_selected(&mut values, headers);
+        values
+    }
+
+    /// Appends the values of a packet, restricted to the selected fields, to `output`.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - Destination of the values.
+    /// * `headers` - Parsed headers of the packet.
+    fn extend_selected(&self, output: &mut Vec<f32>, headers: &Headers) {
+        match &self.mask {
+            Some(mask) => output.extend(mask.iter().map(|&index| headers.data[index])),
+            None => output.extend_from_slice(&headers.data),
+        }
+    }
+
+    /// Returns the indexes of the bits belonging to the configured fields.
+    ///
+    /// When `checksum_validity` is set, only the validity bit of each checksum is kept, and
+    /// when `port_categories` is set, only the range bits of each port. The TCP flag bits are left out with `TcpFlagsEncoding::Value`.
+    ///
+    /// # Returns
+    ///
+    /// The sorted indexes, or `None` if every bit is kept.
+    fn field_mask(&self) -> Option<Vec<usize>> {
+        let flag_bits = self.config.tcp_flags.has_bits();
+        if self.config.fields.is_none()
+            && !self.config.checksum_validity
+            && !self.config.port_categories
+            && flag_bits
+        {
+            return None;
+        }
+        let fields = self.config.fields.as_ref();
+        Some(
+            self.all_headers()
+                .iter()
+                .enumerate()
+                .filter(|(_, name)| {
+                    name.rsplit_once('_').is_some_and(|(field, index)| {
+                        let selected =
+                            fields.is_none_or(|fields| fields.iter().any(|f| f == field));
+                        let checksum_bit = !self.config.checksum_validity
+                            || index == "valid"
+                            || !CHECKSUM_FIELDS.contains(&field);
+                        let port_bit = !self.config.port_categories
+                            || index.starts_with("range")
+                            || !PORT_FIELDS.contains(&field);
+                        let flag_bit = flag_bits || !TCP_FLAG_FIELDS.contains(&field);
+                        selected && checksum_bit && port_bit && flag_bit
+                    })
+                })
+                .map(|(index, _)| index)
+                .collect(),
+        )
+    }
+
+    /// Adds a new packet to the `Nprint` structure, parsing it using the existing protocols.
+    ///
+    /// Once the configured `max_packets` is reached, the packet is either ignored or
+    /// replaces the oldest one, depending on the `TruncationPolicy`. Its TCP sequence
+    /// numbers are made relative if `relative_seq` is set, and it is anonymized right
+    /// away if the configuration asks for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the new raw packet.
+    pub fn add(&mut self, packet: &[u8]) {
+        self.add_packet(packet, self.config.link_layer, None, None);
+    }
+
+    /// Adds a new Ethernet frame already decoded by `pnet`, like `add()`.
+    ///
+    /// The frame is parsed as Ethernet whatever the configured link layer, without copying it.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - Ethernet frame of the new packet.
+    pub fn add_ethernet(&mut self, frame: &EthernetPacket) {
+        self.add_packet(frame.packet(), LinkLayer::Ethernet, None, None);
+    }
+
+    /// Adds a new packet whose network layer was already located, like `add()`.
+    ///
+    /// The packet is parsed from the given offset whatever the configured link layer, see
+    /// `from_offset()`. An offset beyond the packet leaves every header absent.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the new raw packet.
+    /// * `l3_offset` - Offset of the IP header in the packet, in bytes.
+    pub fn add_from_offset(&mut self, packet: &[u8], l3_offset: usize) {
+        let network = packet.get(l3_offset..).unwrap_or_default();
+        self.add_packet(network, LinkLayer::RawIp, None, None);
+    }
+
+    /// Adds a new packet captured at the given time, like `add()`.
+    ///
+    /// The `ProtocolType::Timing` header holds the time elapsed since the previous stored
+    /// packet, zero for the first one. It is filled for packets added without a timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the new raw packet.
+    /// * `timestamp` - Capture time of the packet, from any fixed origin.
+    pub fn add_with_timestamp(&mut self, packet: &[u8], timestamp: Duration) {
+        self.add_packet(packet, self.config.link_layer, Some(timestamp), None);
+    }
+
+    /// Adds a packet captured at the given time, like `add_with_timestamp()`, along with
+    /// its length on the wire.
+    ///
+    /// The `ProtocolType::FrameMeta` header holds the captured length of the packet and
+    /// `wire_len`, which exceeds it when the capture was truncated by a snapshot length.
+    /// The wire length is filled for packets added with any other method.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the new raw packet.
+    /// * `timestamp` - Capture time of the packet, from any fixed origin.
+    /// * `wire_len` - Original length of the packet on the wire, in bytes.
+    pub fn add_with_wire_len(&mut self, packet: &[u8], timestamp: Duration, wire_len: usize) {
+        self.add_packet(
+            packet,
+            self.config.link_layer,
+            Some(timestamp),
+            Some(wire_len),
+        );
+    }
+
+    /// Parses and stores a packet, see `add()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the new raw packet.
+    /// * `link_layer` - Link layer of the packet, usually the configured one.
+    /// * `timestamp` - Capture time of the packet, if known.
+    /// * `wire_len` - Length of the packet on the wire, if known.
+    fn add_packet(
+        &mut self,
+        packet: &[u8],
+        link_layer: LinkLayer,
+        timestamp: Option<Duration>,
+        wire_len: Option<usize>,
+    ) {
+        let full = self
+            .config
+            .max_packets
+            .is_some_and(|max_packets| self.nb_pkt >= max_packets);
+        if full && (self.config.truncation == TruncationPolicy::DropNewest || self.data.is_empty())
+        {
+            return;
+        }
+        let delta = timestamp.map(|timestamp| {
+            let previous = self.last_timestamp.replace(timestamp).unwrap_or(timestamp);
+            timestamp.saturating_sub(previous)
+        });
+        let mut headers = Headers::new(
+            packet,
+            link_layer,
+            &self.protocols,
+            &self.config,
+            delta,
+            wire_len,
+            &mut self.stats,
+        );
+        if let Some(range) = header_range(&self.protocols, &self.config, &ProtocolType::Tcp) {
+            if self.config.scale_window {
+                self.window
+                    .apply(&mut headers.data[range.clone()], &self.config);
+            }
+            if self.config.relative_seq {
+                self.sequence
+                    .apply(&mut headers.data[range], self.config.bit_order);
+            }
+        }
+        if self.config.anonymize {
+            headers.anonymize(&self.protocols, &self.config);
+        }
+        let direction = match FlowKey::from_link_layer(packet, link_layer) {
+            Some(key) => key.direction(self.flow_key.get_or_insert(key)),
+            None => Direction::Forward,
+        };
+        if full {
+            self.data.pop_front();
+            self.directions.pop_front();
+        } else {
+            self.nb_pkt += 1;
+        }
+        self.data.push_back(headers);
+        self.directions.push_back(direction);
+        self.headers.take();
+    }
+
+    /// Returns the 5-tuple of the flow, taken from the first packet carrying IPv4.
+    ///
+    /// # Returns
+    ///
+    /// The `FlowKey` of the first packet, or `None` if no packet carried IPv4.
+    pub fn flow_key(&self) -> Option<&FlowKey> {
+        self.flow_key.as_ref()
+    }
+
+    /// Returns the direction of each stored packet relative to the first one, oldest first.
+    ///
+    /// Packets without a 5-tuple are considered `Direction::Forward`.
+    ///
+    /// # Returns
+    ///
+    /// One `Direction` per packet, aligned with the rows of `iter_packets()`.
+    pub fn directions(&self) -> Vec<Direction> {
+        self.directions.iter().copied().collect()
+    }
+
+    /// Returns the direction of a single stored packet relative to the first one, see `directions()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the packet in the `Nprint`, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// The `Direction` of the packet, or `None` if `index` is out of range.
+    pub fn direction_of(&self, index: usize) -> Option<Direction> {
+        self.directions.get(index).copied()
+    }
+
+    /// Checks that the output is consistent with the header names.
+    ///
+    /// Every packet row must have one value per name of `get_headers()`, so that
+    /// `print().len() == get_headers().len() * count()`. With `Layout::ProtocolMajor`,
+    /// `get_headers()` must have one name per value of `print()` instead.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())`, or an `Err` describing the first mismatch.
+    pub fn validate(&self) -> Result<(), String> {
+        let names = self.packet_headers().len();
+        for (index, row) in self.iter_packets().enumerate() {
+            if row.len() != names {
+                return Err(format!(
+                    "packet {} has {} values for {} header names",
+                    index,
+                    row.len(),
+                    names
+                ));
+            }
+        }
+        let values = self.print().len();
+        if values != names * self.printed_packets() {
+            return Err(format!(
+                "{} values for {} header names and {} packets",
+                values,
+                names,
+                self.printed_packets()
+            ));
+        }
+        if self.config.layout == Layout::ProtocolMajor && self.get_headers().len() != values {
+            return Err(format!(
+                "{} header names for {} values",
+                self.get_headers().len(),
+                values
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the protocols parsed in every packet, in the order of their values in a row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    ///
+    /// let nprint = Nprint::new(&[0x0], vec![ProtocolType::Ipv4, ProtocolType::Udp]);
+    /// assert_eq!(nprint.protocols(), [ProtocolType::Ipv4, ProtocolType::Udp]);
+    /// ```
+    pub fn protocols(&self) -> &[ProtocolType] {
+        &self.protocols
+    }
+
+    /// Returns the number of packets.
+    ///
+    /// # Returns
+    ///
+    /// A `usize` representing the number of packets within the structure.
+    pub fn count(&self) -> usize {
+        self.nb_pkt
+    }
+
+    /// Returns the statistics of the parsing of every added packet.
+    ///
+    /// # Returns
+    ///
+    /// The `ParseStats`, counting in particular the headers that couldn't be parsed.
+    pub fn stats(&self) -> &ParseStats {
+        &self.stats
+    }
+
+    /// Returns the number of values of every packet, the sum of the selected protocols' widths.
+    ///
+    /// # Returns
+    ///
+    /// The length of each packet row, `print().len()` being `bits_per_packet() * count()`.
+    pub fn bits_per_packet(&self) -> usize {
+        match &self.mask {
+            Some(mask) => mask.len(),
+            None => self
+                .protocols
+                .iter()
+                .map(|proto| proto.width_with_config(&self.config))
+                .sum(),
+        }
+    }
+
+    /// Returns the range of the selected values of each protocol in a packet row.
+    fn protocol_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges = Vec::with_capacity(self.protocols.len());
+        let (mut start, mut offset) = (0, 0);
+        for proto in &self.protocols {
+            let width = proto.width_with_config(&self.config);
+            let selected = match &self.mask {
+                Some(mask) => mask
+                    .iter()
+                    .filter(|&&index| (offset..offset + width).contains(&index))
+                    .count(),
+                None => width,
+            };
+            ranges.push(start..start + selected);
+            start += selected;
+            offset += width;
+        }
+        ranges
+    }
+
+    /// Return the name list of all fields of all the protocols present in this Nprint
+    ///
+    /// Only the selected fields are listed when a field selection is configured.
+    /// With `Layout::ProtocolMajor`, one name is returned per value of `print()`, prefixed
+    /// with the index of its packet (e.g., `pkt0_tcp_sprt_0`, then `pkt1_tcp_sprt_0`).
+    ///
+    /// # Returns
+    ///
+    /// A list of header names that are prefixed by the protocol and suffixed with an index (e.g., `tcp_sprt_0`, `tcp_sprt_1`).
+    /// The names are computed once and reused by later calls.
+    pub fn get_headers(&self) -> &[String] {
+        match self.config.layout {
+            Layout::PacketMajor => self.packet_headers(),
+            Layout::ProtocolMajor => self.headers.get_or_init(|| {
+                let headers = self.packet_headers();
+                self.protocol_ranges()
+                    .into_iter()
+                    .flat_map(|range| {
+                        let headers = &headers[range];
+                        (0..self.printed_packets()).flat_map(move |packet| {
+                            headers
+                                .iter()
+                                .map(move |name| format!("pkt{}_{}", packet, name))
+                        })
+                    })
+                    .collect()
+            }),
+        }
+    }
+
+    /// Returns the names of the values of a single packet row, whatever the layout.
+    pub(crate) fn packet_headers(&self) -> &[String] {
+        self.packet_headers.get_or_init(|| {
+            let headers = self.all_headers();
+            match &self.mask {
+                Some(mask) => mask.iter().map(|&index| headers[index].clone()).collect(),
+                None => headers,
+            }
+        })
+    }
+
+    /// Returns the name list of all fields of all the protocols, ignoring the field selection.
+    fn all_headers(&self) -> Vec<String> {
+        let mut output: Vec<String> = self
+            .protocols
+            .iter()
+            .flat_map(|proto| proto.headers_with_config(&self.config))
+            .collect();
+        if self.config.checksum_validity {
+            for name in &mut output {
+                if let Some(field) = CHECKSUM_FIELDS.iter().find(|f| *name == format!("{}_0", f)) {
+                    *name = format!("{}_valid", field);
+                }
+            }
+        }
+        if self.config.port_categories {
+            for name in &mut output {
+                if let Some((field, index)) = name.rsplit_once('_') {
+                    let bit = index.parse::<usize>().ok();
+                    if PORT_FIELDS.contains(&field) && bit.is_some_and(|b| b < PORT_CATEGORY_BITS) {
+                        *name = format!("{}_range{}", field, index);
+                    }
+                }
+            }
+        }
+        output
+    }
+
+    /// Writes the `Nprint` as CSV into the given writer.
+    ///
+    /// The first line contains the header names of a packet row, as returned by `get_headers()`
+    /// with `Layout::PacketMajor`, followed by one row per packet whatever the layout. Absent fields are written as `-1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Any destination implementing `std::io::Write`.
+    ///
+    /// # Returns
+    ///
+    /// An `std::io::Result` reporting any error raised by the writer.
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writeln!(writer, "{}", self.packet_headers().join(","))?;
+        for packet in self.iter_packets() {
+            write_csv_row(&mut writer, &packet)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the version of the column schema of the crate, see `SCHEMA_VERSION`.
+    pub fn schema_version() -> u32 {
+        SCHEMA_VERSION
+    }
+
+    /// Returns a hash of the ordered header names of a packet row.
+    ///
+    /// The hash only depends on the columns, i.e. on the protocols and the configuration, and
+    /// is stable across runs and platforms. A dataset exported with a different hash has
+    /// differently ordered or named columns.
+    ///
+    /// # Returns
+    ///
+    /// The 64-bit FNV-1a hash of the comma-separated header names, as written by `to_csv()`.
+    pub fn schema_hash(&self) -> u64 {
+        schema_hash(self.packet_headers())
+    }
+
+    /// Checks that a CSV written by `to_csv()` has the columns of this `Nprint`.
+    ///
+    /// Only the header row is read, its hash being compared to `schema_hash()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source of the CSV, positioned at its header row.
+    ///
+    /// # Returns
+    ///
+    /// `NprintError::SchemaMismatch` if the columns differ, or the error raised by the reader.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    ///
+    /// let csv = Nprint::new(&[0x0], vec![ProtocolType::Udp]).to_csv_string();
+    /// let nprint = Nprint::new(&[0x0], vec![ProtocolType::Tcp]);
+    /// assert!(nprint.check_csv_schema(csv.as_bytes()).is_err());
+    /// ```
+    pub fn check_csv_schema<R: BufRead>(&self, mut reader: R) -> Result<(), NprintError> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let names: Vec<&str> = line.trim_end_matches(['\r', '\n']).split(',').collect();
+        let (expected, found) = (self.schema_hash(), schema_hash(&names));
+        if expected != found {
+            return Err(NprintError::SchemaMismatch { expected, found });
+        }
+        Ok(())
+    }
+
+    /// Reads back a CSV written by `to_csv()`, one packet per row.
+    ///
+    /// The header row must match the columns of the given protocols with the default
+    /// configuration, see `check_csv_schema()` and `from_csv_with_config()`. A protocol is considered parsed in a packet
+    /// unless all its values are the fill value. The packets are all `Direction::Forward`,
+    /// their 5-tuples being lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source of the CSV.
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack of the CSV.
+    ///
+    /// # Returns
+    ///
+    /// The `Nprint` holding every row, `NprintError::SchemaMismatch` if the header row
+    /// doesn't match, or `NprintError::MalformedCsv` for a row of the wrong width or holding
+    /// a non-numeric value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, ProtocolType};
+    ///
+    /// let nprint = Nprint::new(&[0x0], vec![ProtocolType::Udp]);
+    /// let csv = nprint.to_csv_string();
+    /// let read = Nprint::from_csv(csv.as_bytes(), vec![ProtocolType::Udp]).unwrap();
+    /// assert_eq!(read.print(), nprint.print());
+    /// ```
+    pub fn from_csv<R: Read>(
+        reader: R,
+        protocols: Vec<ProtocolType>,
+    ) -> Result<Nprint, NprintError> {
+        Nprint::from_csv_with_config(reader, protocols, NprintConfig::default())
+    }
+
+    /// Reads back a CSV written by `to_csv()` with the given configuration, see `from_csv()`.
+    ///
+    /// The header row must match `headers_with_config()` of the given protocols, the field
+    /// selection included. The values of the fields left out of the CSV are the fill value.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source of the CSV.
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack of the CSV.
+    /// * `config` - The `NprintConfig` the CSV was written with.
+    ///
+    /// # Returns
+    ///
+    /// The `Nprint` holding every row, or the errors of `from_csv()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Nprint, NprintConfig, ProtocolType};
+    ///
+    /// let config = NprintConfig {
+    ///     tcp_options_bits: Some(0),
+    ///     ..Default::default()
+    /// };
+    /// let nprint = Nprint::new_with_config(&[0x0], vec![ProtocolType::Tcp], config.clone());
+    /// let csv = nprint.to_csv_string();
+    /// assert!(Nprint::from_csv(csv.as_bytes(), vec![ProtocolType::Tcp]).is_err());
+    /// let read = Nprint::from_csv_with_config(csv.as_bytes(), vec![ProtocolType::Tcp], config)
+    ///     .unwrap();
+    /// assert_eq!(read.print(), nprint.print());
+    /// ```
+    pub fn from_csv_with_config<R: Read>(
+        reader: R,
+        protocols: Vec<ProtocolType>,
+        config: NprintConfig,
+    ) -> Result<Nprint, NprintError> {
+        let mut nprint = Nprint::empty_with_config(protocols, config);
+        let mut reader = BufReader::new(reader);
+        nprint.check_csv_schema(&mut reader)?;
+        let width = nprint.packet_headers().len();
+        let fill = nprint.config.fill_value.value();
+        let row_width: usize = header_ranges(&nprint.protocols, &nprint.config)
+            .map(|(_, range)| range.len())
+            .sum();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            // Line numbers start at 1, after the header row
+            let malformed = |reason| NprintError::MalformedCsv {
+                line: index + 2,
+                reason,
+            };
+            let values = line
+                .split(',')
+                .filter(|_| !line.is_empty())
+                .map(|value| {
+                    value
+                        .parse::<f32>()
+                        .map_err(|_| malformed(format!("non-numeric value {:?}", value)))
+                })
+                .collect::<Result<Vec<f32>, _>>()?;
+            if values.len() != width {
+                return Err(malformed(format!(
+                    "expected {} values, found {}",
+                    width,
+                    values.len()
+                )));
+            }
+            let data = match &nprint.mask {
+                Some(mask) => {
+                    let mut data = vec![fill; row_width];
+                    for (&index, value) in mask.iter().zip(values) {
+                        data[index] = value;
+                    }
+                    data
+                }
+                None => values,
+            };
+            let parsed = header_ranges(&nprint.protocols, &nprint.config)
+                .map(|(_, range)| {
+                    let is_fill = |value: &f32| *value == fill || value.is_nan() && fill.is_nan();
+                    !data[range].iter().all(is_fill)
+                })
+                .collect();
+            nprint.data.push_back(Headers { data, parsed });
+            nprint.directions.push_back(Direction::Forward);
+            nprint.nb_pkt += 1;
+        }
+        Ok(nprint)
+    }
+
+    /// Returns the `Nprint` as a CSV `String`, see `to_csv()`.
+    ///
+    /// Convenient for small captures and tests.
+    pub fn to_csv_string(&self) -> String {
+        let mut buffer = Vec::new();
+        self.to_csv(&mut buffer)
+            .expect("Writing into a Vec<u8> cannot fail");
+        String::from_utf8(buffer).expect("CSV output is always valid UTF-8")
+    }
+
+    /// Returns the `Nprint` as a human-readable grid, for debugging.
+    ///
+    /// Each line holds a header name, as in the CSV header row, followed by the value of the
+    /// field in every packet, one column per packet: `1`, `0`, or `.` for absent fields and
+    /// any non-binary value.
+    ///
+    /// # Returns
+    ///
+    /// One line per field, with the names padded so that the columns are aligned.
+    pub fn to_grid_string(&self) -> String {
+        let labels = self.packet_headers();
+        let matrix = self.print_matrix(self.count());
+        let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+        let mut grid = String::new();
+        for (field, label) in labels.iter().enumerate() {
+            grid.push_str(&format!("{:<width$}", label, width = label_width));
+            for packet in &matrix {
+                grid.push(' ');
+                let value = packet[field];
+                grid.push(if value == 1. {
+                    '1'
+                } else if value == 0. {
+                    '0'
+                } else {
+                    '.'
+                });
+            }
+            grid.push('\n');
+        }
+        grid
+    }
+
+    /// Remove sensitive data from the captured header
+    ///
+    /// Removes every field of `AnonField::DEFAULT`, see `anonymize_fields()`.
+    pub fn anonymize(&mut self) {
+        self.anonymize_fields(&AnonField::DEFAULT);
+    }
+
+    /// Removes the given sensitive fields from every stored packet, e.g. to keep the ports
+    /// but drop the IP addresses.
+    ///
+    /// Removed bits are set to 0, fields of absent headers included.
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - Fields to remove, those of unselected protocols being ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{AnonField, Nprint, ProtocolType};
+    ///
+    /// let mut nprint = Nprint::new(&[0x0], vec![ProtocolType::Udp]);
+    /// nprint.anonymize_fields(&[AnonField::UdpDstPort, AnonField::Ipv4Src]);
+    /// assert_eq!(nprint.print()[..16], [-1.; 16]);
+    /// assert_eq!(nprint.print()[16..32], [0.; 16]);
+    /// ```
+    pub fn anonymize_fields(&mut self, fields: &[AnonField]) {
+        for packet in self.data.iter_mut() {
+            packet.anonymize_fields(fields, &self.protocols, &self.config);
+        }
+    }
+
+    /// Pseudonymize sensitive data from the captured header with a key.
+    ///
+    /// IPv4 addresses are replaced by prefix-preserving pseudonyms (Crypto-PAn), so that
+    /// the same host keeps the same pseudonym across flows anonymized with the same key.
+    /// Other sensitive fields are removed as with `anonymize()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Secret key of the pseudonymization.
+    pub fn anonymize_with_key(&mut self, key: &[u8; 16]) {
+        for packet in self.data.iter_mut() {
+            packet.anonymize_with_key(key, &self.protocols, &self.config);
+        }
+    }
+}
+
+/// Writes the values of a packet as a CSV row.
+///
+/// # Arguments
+///
+/// * `writer` - Any destination implementing `std::io::Write`.
+/// * `values` - Values of the packet.
+pub(crate) fn write_csv_row<W: Write>(writer: &mut W, values: &[f32]) -> std::io::Result<()> {
+    let row: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    writeln!(writer, "{}", row.join(","))
+}
+
+/// Returns the 64-bit FNV-1a hash of comma-separated header names, see `Nprint::schema_hash()`.
+///
+/// # Arguments
+///
+/// * `names` - Ordered header names.
+pub(crate) fn schema_hash<S: AsRef<str>>(names: &[S]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let separated = names.iter().enumerate().flat_map(|(i, name)| {
+        let separator: &[u8] = if i == 0 { b"" } else { b"," };
+        separator.iter().chain(name.as_ref().as_bytes())
+    });
+    separated.fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Returns the bits of a header holding its raw bytes, see `Nprint::header_bytes()`.
+///
+/// The IPv4 and TCP options are moved back after the fixed fields, leaving out the options
+/// indicators and the fields extracted after the TCP options.
+///
+/// # Arguments
+/// * `protocol` - Protocol of the header.
+/// * `data` - Bits of the header.
+/// * `config` - Configuration the header was parsed with.
+pub(crate) fn wire_bits<'a>(
+    protocol: ProtocolType,
+    data: &'a [f32],
+    config: &NprintConfig,
+) -> Cow<'a, [f32]> {
+    let (fixed, options) = match protocol {
+        ProtocolType::Ipv4 => (Ipv4Header::FIXED_WIDTH, Ipv4Header::options_range(config)),
+        ProtocolType::Tcp => (TcpHeader::FIXED_WIDTH, TcpHeader::options_range(config)),
+        _ => return Cow::Borrowed(data),
+    };
+    if options.start == fixed {
+        Cow::Borrowed(&data[..options.end])
+    } else {
+        Cow::Owned([&data[..fixed], &data[options]].concat())
+    }
+}
+
+/// Decodes a hex string into bytes, see `Nprint::from_hex()`.
+///
+/// # Arguments
+///
+/// * `hex` - Hex digits, separated by whitespace or commas and optionally prefixed by `0x`.
+fn decode_hex(hex: &str) -> Result<Vec<u8>, NprintError> {
+    let mut digits = Vec::with_capacity(hex.len());
+    let tokens = hex.split(|c: char| c.is_whitespace() || c == ',');
+    for token in tokens.filter(|token| !token.is_empty()) {
+        let mut position = token.as_ptr() as usize - hex.as_ptr() as usize;
+        let prefixed = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"));
+        if prefixed.is_some() {
+            position += 2;
+        }
+        let token = prefixed.unwrap_or(token);
+        // A prefixed number stands on its own, e.g. `0x0` being one byte
+        if prefixed.is_some() && token.len() % 2 == 1 {
+            digits.push(0);
+        }
+        for (offset, c) in token.char_indices() {
+            let digit = c.to_digit(16).ok_or_else(|| NprintError::MalformedHex {
+                position: position + offset,
+                reason: format!("invalid hex digit {:?}", c),
+            })?;
+            digits.push(digit as u8);
+        }
+    }
+    if digits.len() % 2 == 1 {
+        return Err(NprintError::MalformedHex {
+            position: hex.len(),
+            reason: "odd number of hex digits".to_string(),
+        });
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
+
+/// Parses a single IPv4 header, without the link layer.
+///
+/// # Arguments
+///
+/// * `packet` - Raw bytes starting with the IPv4 header.
+///
+/// # Returns
+///
+/// The 480 bits of the header, filled with -1 if it can't be parsed.
+///
+/// # Example
+///
+/// ```
+/// let bits = nprint_rs::parse_ipv4(&[0x0]);
+/// assert_eq!(bits, vec![-1.; 480]);
+/// ```
+pub fn parse_ipv4(packet: &[u8]) -> Vec<f32> {
+    parse_layer::<Ipv4Header>(ProtocolType::Ipv4, packet)
+}
+
+/// Parses a single TCP header, without the IP and link layers.
+///
+/// # Arguments
+///
+/// * `segment` - Raw bytes starting with the TCP header.
+///
+/// # Returns
+///
+/// The 480 bits of the header, filled with -1 if it can't be parsed.
+pub fn parse_tcp(segment: &[u8]) -> Vec<f32> {
+    parse_layer::<TcpHeader>(ProtocolType::Tcp, segment)
+}
+
+/// Parses a single UDP header, without the IP and link layers.
+///
+/// # Arguments
+///
+/// * `datagram` - Raw bytes starting with the UDP header.
+///
+/// # Returns
+///
+/// The 64 bits of the header, filled with -1 if it can't be parsed.
+pub fn parse_udp(datagram: &[u8]) -> Vec<f32> {
+    parse_layer::<UdpHeader>(ProtocolType::Udp, datagram)
+}
+
+/// Parses a single IPv4 header into a caller-provided slice, like `parse_ipv4()` without
+/// allocating.
+///
+/// # Arguments
+///
+/// * `packet` - Raw bytes starting with the IPv4 header.
+/// * `out` - Destination of the 480 bits, e.g. a row of a preallocated buffer.
+///
+/// # Returns
+///
+/// `true` if the header was parsed, `false` if `out` was filled with -1 instead, or left
+/// untouched if its length isn't the width of the header.
+///
+/// # Example
+///
+/// ```
+/// let mut row = [0.; 480];
+/// assert!(!nprint_rs::parse_ipv4_into(&[0x0], &mut row));
+/// assert_eq!(row, [-1.; 480]);
+/// ```
+pub fn parse_ipv4_into(packet: &[u8], out: &mut [f32]) -> bool {
+    parse_layer_into::<Ipv4Header>(ProtocolType::Ipv4, packet, out)
+}
+
+/// Parses a single TCP header into a caller-provided slice, see `parse_ipv4_into()`.
+///
+/// # Arguments
+///
+/// * `segment` - Raw bytes starting with the TCP header.
+/// * `out` - Destination of the 480 bits.
+///
+/// # Returns
+///
+/// `true` if the header was parsed into `out`.
+pub fn parse_tcp_into(segment: &[u8], out: &mut [f32]) -> bool {
+    parse_layer_into::<TcpHeader>(ProtocolType::Tcp, segment, out)
+}
+
+/// Parses a single UDP header into a caller-provided slice, see `parse_ipv4_into()`.
+///
+/// # Arguments
+///
+/// * `datagram` - Raw bytes starting with the UDP header.
+/// * `out` - Destination of the 64 bits.
+///
+/// # Returns
+///
+/// `true` if the header was parsed into `out`.
+pub fn parse_udp_into(datagram: &[u8], out: &mut [f32]) -> bool {
+    parse_layer_into::<UdpHeader>(ProtocolType::Udp, datagram, out)
+}
+
+/// Parses a single header with the default configuration, see `parse_tcp()`.
+///
+/// # Arguments
+///
+/// * `protocol` - Protocol of the header.
+/// * `packet` - Raw bytes starting with the header.
+fn parse_layer<H: PacketHeader>(protocol: ProtocolType, packet: &[u8]) -> Vec<f32> {
+    let mut data = vec![0.; protocol.width()];
+    parse_layer_into::<H>(protocol, packet, &mut data);
+    data
+}
+
+/// Parses a single header with the default configuration into `out`, see `parse_ipv4_into()`.
+///
+/// # Arguments
+///
+/// * `protocol` - Protocol of the header.
+/// * `packet` - Raw bytes starting with the header.
+/// * `out` - Destination of the bits.
+fn parse_layer_into<H: PacketHeader>(
+    protocol: ProtocolType,
+    packet: &[u8],
+    out: &mut [f32],
+) -> bool {
+    if out.len() != protocol.width() {
+        return false;
+    }
+    let config = NprintConfig::default();
+    let parsed = H::parse_into(packet, &config, out).is_some();
+    if !parsed {
+        out.fill(config.fill_value.value());
+    }
+    parsed
+}
+
+/// Parses the headers following the link layer of a packet into its row.
+///
+/// VLAN tags, MPLS labels, GRE, GTP-U and IP-in-IP tunnels are popped before the network
+/// and transport layers, only their outermost header and the innermost IP header being kept.
+///
+/// # Arguments
+///
+/// * `row` - Row of the packet, its link layer already parsed.
+/// * `ethertype` - Ethertype announced by the link layer.
+/// * `payload` - Bytes following the link layer.
+/// * `config` - Configuration of the parsing.
+/// * `stats` - Statistics updated with the outcome of the parsing.
+fn parse_network(
+    row: &mut RowWriter,
+    mut ethertype: EtherType,
+    payload: &[u8],
+    config: &NprintConfig,
+    stats: &mut ParseStats,
+) {
+    // Every layer borrows from the packet, nothing is copied along the way
+    let mut payload = payload;
+
+    // Pop VLAN's Headers, only the outermost tag is kept
+    let mut depth = 0;
+    let mut vlan = None;
+    while is_vlan(ethertype) && depth < MAX_VLAN_DEPTH {
+        let Some(vlan_packet) = VlanPacket::new(payload) else {
+            stats.truncated_other += 1;
+            break;
+        };
+        if vlan.is_none() {
+            vlan = row.parse::<VlanHeader>(ProtocolType::Vlan, payload);
+        }
+        ethertype = vlan_packet.get_ethertype();
+        payload = &payload[VlanPacket::minimum_packet_size()..];
+        depth += 1;
+    }
+
+    // Pop the MPLS label stack, only the top entry is kept
+    if is_mpls(ethertype) {
+        let _ = row.parse::<MplsHeader>(ProtocolType::Mpls, payload);
+        match pop_labels(payload) {
+            Some((inner_ethertype, inner)) => {
+                ethertype = inner_ethertype;
+                payload = inner;
+            }
+            None => stats.truncated_other += 1,
+        }
+    }
+
+    // Decapsulate GRE, GTP-U and IP-in-IP tunnels, only the outermost GRE and GTP-U headers
+    // and the innermost IP header are kept
+    let mut depth = 0;
+    let mut gre = None;
+    let mut gtp = None;
+    while depth < MAX_TUNNEL_DEPTH {
+        let Some((next_header, ip_payload)) = ip_payload(ethertype, payload) else {
+            break;
+        };
+        let (inner_ethertype, inner) = match next_header {
+            IpNextHeaderProtocols::Gre => {
+                if gre.is_none() {
+                    gre = row.parse::<GreHeader>(ProtocolType::Gre, ip_payload);
+                }
+                let Some(decapsulated) = decapsulate(ip_payload) else {
+                    stats.truncated_other += 1;
+                    break;
+                };
+                decapsulated
+            }
+            // User plane of mobile networks, tunneled over UDP
+            IpNextHeaderProtocols::Udp
+                if UdpPacket::new(ip_payload)
+                    .is_some_and(|udp| is_gtp_u(udp.get_source(), udp.get_destination())) =>
+            {
+                let message = &ip_payload[8..];
+                if gtp.is_none() {
+                    gtp = row.parse::<GtpHeader>(ProtocolType::Gtp, message);
+                }
+                // Signalling messages are left to the UDP parsing
+                let Some(decapsulated) = gtp::decapsulate(message) else {
+                    break;
+                };
+                decapsulated
+            }
+            // Protocol 4, IPv4 encapsulation (RFC 2003)
+            IpNextHeaderProtocols::Ipv4 => (EtherTypes::Ipv4, ip_payload),
+            // Protocol 41, IPv6 encapsulation (RFC 2473)
+            IpNextHeaderProtocols::Ipv6 => (EtherTypes::Ipv6, ip_payload),
+            _ => break,
+        };
+        ethertype = inner_ethertype;
+        payload = inner;
+        depth += 1;
+    }
+
+    // Upper-layer protocol, its raw bytes, and the addresses of its IP pseudo-header
+    let mut transport = None;
+    match ethertype {
+        EtherTypes::Arp => {
+            counted(
+                row.parse::<ArpHeader>(ProtocolType::Arp, payload),
+                &mut stats.truncated_other,
+            );
+        }
+        // IPv6 headers aren't extracted yet, only their upper-layer protocols
+        EtherTypes::Ipv6 => {
+            if let Some((ipv6_packet, upper)) = split_ipv6(payload) {
+                match skip_extensions(ipv6_packet.get_next_header(), upper) {
+                    Some((next_header, upper)) => {
+                        transport = Some((
+                            next_header,
+                            upper,
+                            IpAddr::V6(ipv6_packet.get_source()),
+                            IpAddr::V6(ipv6_packet.get_destination()),
+                        ));
+                    }
+                    None => stats.truncated_other += 1,
+                }
+            }
+        }
+        EtherTypes::Ipv4 => {
+            let ipv4 = counted(
+                row.parse::<Ipv4Header>(ProtocolType::Ipv4, payload),
+                &mut stats.truncated_ipv4,
+            );
+            if let Some((ipv4_packet, upper)) = split_ipv4(payload) {
+                if config.checksum_validity && ipv4.is_some() {
+                    row.write_checksum_validity(
+                        ProtocolType::Ipv4,
+                        IPV4_CHECKSUM,
+                        ipv4_checksum_valid(&ipv4_packet),
+                    );
+                }
+                // The payload is bounded by the total length, leaving out any Ethernet padding
+                transport = Some((
+                    ipv4_packet.get_next_level_protocol(),
+                    upper,
+                    IpAddr::V4(ipv4_packet.get_source()),
+                    IpAddr::V4(ipv4_packet.get_destination()),
+                ));
+            }
+        }
+        _ => stats.unsupported_ethertype += 1,
+    }
+
+    // AH authenticates the packet without encrypting it, the upper-layer header follows it
+    transport = match transport {
+        Some((IpNextHeaderProtocols::Ah, segment, source, destination)) => {
+            let _ = row.parse::<AhHeader>(ProtocolType::Ah, segment);
+            match skip_ah(segment).and_then(|(next, upper)| skip_extensions(next, upper)) {
+                Some((next_header, upper)) => Some((next_header, upper, source, destination)),
+                None => {
+                    stats.truncated_other += 1;
+                    None
+                }
+            }
+        }
+        transport => transport,
+    };
+
+    if let Some((protocol, segment, source, destination)) = transport {
+        let transport_payload = config.payload_source == PayloadSource::TransportPayload;
+        if !transport_payload {
+            let _ = row.parse::<PayloadHeader>(ProtocolType::Payload, segment);
+        }
+        match protocol {
+            IpNextHeaderProtocols::Tcp => {
+                let tcp = counted(
+                    row.parse::<TcpHeader>(ProtocolType::Tcp, segment),
+                    &mut stats.truncated_tcp,
+                );
+                if config.checksum_validity && tcp.is_some() {
+                    row.write_checksum_validity(
+                        ProtocolType::Tcp,
+                        TCP_CHECKSUM,
+                        tcp_checksum_valid(segment, source, destination),
+                    );
+                }
+                if config.port_categories && tcp.is_some() {
+                    row.write_port_categories(ProtocolType::Tcp, segment);
+                }
+                let _ = row.parse::<TcpOptionsHeader>(ProtocolType::TcpOptions, segment);
+                if let Some(tcp_packet) = TcpPacket::new(segment) {
+                    if transport_payload {
+                        let _ =
+                            row.parse::<PayloadHeader>(ProtocolType::Payload, tcp_packet.payload());
+                    }
+                    if is_client_hello(tcp_packet.payload()) {
+                        counted(
+                            row.parse::<TlsClientHelloHeader>(
+                                ProtocolType::TlsClientHello,
+                                tcp_packet.payload(),
+                            ),
+                            &mut stats.truncated_other,
+                        );
+                    }
+                }
+            }
+            IpNextHeaderProtocols::Sctp => {
+                counted(
+                    row.parse::<SctpHeader>(ProtocolType::Sctp, segment),
+                    &mut stats.truncated_other,
+                );
+            }
+            IpNextHeaderProtocols::Esp => {
+                counted(
+                    row.parse::<EspHeader>(ProtocolType::Esp, segment),
+                    &mut stats.truncated_other,
+                );
+            }
+            // UDP-Lite shares the UDP header, its coverage replacing the length
+            IpNextHeaderProtocols::Udp | IpNextHeaderProtocols::UdpLite => {
+                let udp = counted(
+                    row.parse::<UdpHeader>(ProtocolType::Udp, segment),
+                    &mut stats.truncated_udp,
+                );
+                if config.checksum_validity && udp.is_some() {
+                    let valid = if protocol == IpNextHeaderProtocols::UdpLite {
+                        udp_lite_checksum_valid(segment, source, destination)
+                    } else {
+                        udp_checksum_valid(segment, source, destination)
+                    };
+                    row.write_checksum_validity(ProtocolType::Udp, UDP_CHECKSUM, valid);
+                }
+                if config.port_categories && udp.is_some() {
+                    row.write_port_categories(ProtocolType::Udp, segment);
+                }
+                if udp.is_some() && protocol == IpNextHeaderProtocols::Udp && udp_truncated(segment)
+                {
+                    stats.truncated_udp_payload += 1;
+                }
+                if let Some(udp_packet) = UdpPacket::new(segment) {
+                    let (src, dst) = (udp_packet.get_source(), udp_packet.get_destination());
+                    if transport_payload {
+                        let _ =
+                            row.parse::<PayloadHeader>(ProtocolType::Payload, udp_packet.payload());
+                    }
+                    if is_quic(src, dst, udp_packet.payload()) {
+                        counted(
+                            row.parse::<QuicHeader>(ProtocolType::Quic, udp_packet.payload()),
+                            &mut stats.truncated_other,
+                        );
+                    }
+                    if is_dhcp(src, dst) {
+                        counted(
+                            row.parse::<DhcpHeader>(ProtocolType::Dhcp, udp_packet.payload()),
+                            &mut stats.truncated_other,
+                        );
+                    }
+                    if is_dns(src, dst) {
+                        counted(
+                            row.parse::<DnsHeader>(ProtocolType::Dns, udp_packet.payload()),
+                            &mut stats.truncated_other,
+                        );
+                    }
+                }
+            }
+            IpNextHeaderProtocols::Icmpv6 if source.is_ipv6() => {
+                counted(
+                    row.parse::<Icmpv6Header>(ProtocolType::Icmpv6, segment),
+                    &mut stats.truncated_other,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the range of a TCP or UDP port: 0 for well-known ports (below 1024), 1 for
+/// registered ports (below 49152) and 2 for ephemeral ones.
+///
+/// # Arguments
+///
+/// * `port` - Value of the port.
+fn port_category(port: u16) -> u8 {
+    match port {
+        0..=1023 => 0,
+        1024..=49151 => 1,
+        _ => 2,
+    }
+}
+
+/// Returns the upper-layer protocol of an IP packet and the bytes it announces.
+///
+/// IPv6 extension headers are skipped, see `skip_extensions()`, as well as an AH header.
+///
+/// # Arguments
+///
+/// * `ethertype` - Ethertype of the packet.
+/// * `packet` - Raw bytes of the packet.
+///
+/// # Returns
+///
+/// The upper-layer protocol and its bytes, or `None` if the packet isn't a valid IP packet.
+fn ip_payload(ethertype: EtherType, packet: &[u8]) -> Option<(IpNextHeaderProtocol, &[u8])> {
+    let (next_header, upper) = match ethertype {
+        EtherTypes::Ipv4 => {
+            let (ipv4_packet, upper) = split_ipv4(packet)?;
+            Some((ipv4_packet.get_next_level_protocol(), upper))
+        }
+        EtherTypes::Ipv6 => {
+            let (ipv6_packet, upper) = split_ipv6(packet)?;
+            skip_extensions(ipv6_packet.get_next_header(), upper)
+        }
+        _ => None,
+    }?;
+    match next_header {
+        IpNextHeaderProtocols::Ah => {
+            let (next_header, upper) = skip_ah(upper)?;
+            skip_extensions(next_header, upper)
+        }
+        _ => Some((next_header, upper)),
+    }
+}
+
+/// Splits an IPv4 packet into its header and the bytes following it, borrowed from the packet.
+///
+/// # Arguments
+///
+/// * `packet` - Raw bytes of the packet.
+///
+/// # Returns
+///
+/// The packet and its payload bounded by the total length, leaving out any Ethernet padding,
+/// or `None` if the packet is shorter than an IPv4 header.
+fn split_ipv4(packet: &[u8]) -> Option<(Ipv4Packet<'_>, &[u8])> {
+    let ipv4_packet = Ipv4Packet::new(packet)?;
+    // Same bounds as `Ipv4Packet::payload()`, which only lends its bytes to the packet view
+    let start = (ipv4_packet.get_header_length() as usize * 4)
+        .max(Ipv4Packet::minimum_packet_size())
+        .min(packet.len());
+    let end = start + ipv4_packet.payload().len();
+    Some((ipv4_packet, &packet[start..end]))
+}
+
+/// Splits an IPv6 packet into its fixed header and the bytes following it, borrowed from the
+/// packet.
+///
+/// # Arguments
+///
+/// * `packet` - Raw bytes of the packet.
+///
+/// # Returns
+///
+/// The packet and its payload bounded by the payload length, or `None` if the packet is
+/// shorter than an IPv6 header.
+fn split_ipv6(packet: &[u8]) -> Option<(Ipv6Packet<'_>, &[u8])> {
+    let ipv6_packet = Ipv6Packet::new(packet)?;
+    let start = Ipv6Packet::minimum_packet_size();
+    let end = start + ipv6_packet.payload().len();
+    Some((ipv6_packet, &packet[start..end]))
+}
+
+/// Returns the range of the bits of each protocol in a packet row, before the field selection.
+///
+/// # Arguments
+///
+/// * `protocols` - Ordered protocols of the `Nprint`.
+/// * `config` - Configuration of the parsing.
+fn header_ranges<'a>(
+    protocols: &'a [ProtocolType],
+    config: &'a NprintConfig,
+) -> impl Iterator<Item = (&'a ProtocolType, Range<usize>)> + 'a {
+    protocols.iter().scan(0, move |offset, proto| {
+        let start = *offset;
+        *offset += proto.width_with_config(config);
+        Some((proto, start..*offset))
+    })
+}
+
+/// Returns the range of the bits of a protocol in a packet row, see `header_ranges()`.
+///
+/// # Arguments
+///
+/// * `protocols` - Ordered protocols of the `Nprint`.
+/// * `config` - Configuration of the parsing.
+/// * `protocol` - Protocol to look for, its first occurrence being returned.
+///
+/// # Returns
+///
+/// The range, or `None` if the protocol isn't selected.
+fn header_range(
+    protocols: &[ProtocolType],
+    config: &NprintConfig,
+    protocol: &ProtocolType,
+) -> Option<Range<usize>> {
+    header_ranges(protocols, config)
+        .find(|(proto, _)| *proto == protocol)
+        .map(|(_, range)| range)
+}
+
+/// Packet row being parsed, each header being written into the range of its protocol.
+struct RowWriter<'a> {
+    /// Bits of the row, filled with the configured fill value until a header is parsed.
+    data: Vec<f32>,
+    /// Whether the header of each protocol is parsed, in the order of the protocols.
+    parsed: Vec<bool>,
+    /// Ordered protocols of the `Nprint`.
+    protocols: &'a [ProtocolType],
+    /// Configuration of the parsing.
+    config: &'a NprintConfig,
+}
+
+impl<'a> RowWriter<'a> {
+    /// Returns a row of absent headers.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocols` - Ordered protocols of the `Nprint`.
+    /// * `config` - Configuration of the parsing.
+    fn new(protocols: &'a [ProtocolType], config: &'a NprintConfig) -> Self {
+        let width = protocols
+            .iter()
+            .map(|proto| proto.width_with_config(config))
+            .sum();
+        RowWriter {
+            data: vec![config.fill_value.value(); width],
+            parsed: vec![false; protocols.len()],
+            protocols,
+            config,
+        }
+    }
+
+    /// Parses a header into the range of its protocol.
+    ///
+    /// Headers of protocols that aren't selected are still parsed, only to report their failures.
+    /// The range is filled back with the fill value if the parsing fails. Debug builds assert
+    /// that the header names match the width of the range.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocol` - Protocol of the header `H`.
+    /// * `packet` - Raw bytes of the header.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the header couldn't be parsed.
+    fn parse<H: PacketHeader>(&mut self, protocol: ProtocolType, packet: &[u8]) -> Option<()> {
+        let Some(range) = header_range(self.protocols, self.config, &protocol) else {
+            return H::parse(packet, self.config).map(|_| ());
+        };
+        debug_assert!(
+            {
+                let absent = H::absent(self.config);
+                absent.protocol_type() == protocol
+                    && H::headers_with_config(self.config).len() == range.len()
+                    && absent.get_data().len() == range.len()
+            },
+            "{:?} header names don't match its {} bits",
+            protocol,
+            range.len()
+        );
+        let out = &mut self.data[range];
+        let parsed = H::parse_into(packet, self.config, out);
+        if let Some(index) = self.protocols.iter().position(|proto| *proto == protocol) {
+            self.parsed[index] = parsed.is_some();
+        }
+        if parsed.is_none() {
+            out.fill(self.config.fill_value.value());
+        }
+        parsed
+    }
+
+    /// Replaces the checksum bits of a parsed header with its validity.
+    ///
+    /// The first bit of the checksum is set to 1 if the checksum is valid, 0 otherwise,
+    /// and the remaining bits are zeroed. Nothing is written if the protocol isn't selected.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocol` - Protocol of the parsed header.
+    /// * `range` - Bit range of the checksum in the header data.
+    /// * `valid` - Outcome of the checksum verification.
+    fn write_checksum_validity(
+        &mut self,
+        protocol: ProtocolType,
+        range: Range<usize>,
+        valid: bool,
+    ) {
+        if let Some(header) = header_range(self.protocols, self.config, &protocol) {
+            let checksum = &mut self.data[header][range];
+            checksum.fill(0.);
+            checksum[0] = if valid { 1. } else { 0. };
+        }
+    }
+
+    /// Replaces the source and destination ports of a parsed TCP or UDP header with their
+    /// range, see `port_category()`, the other bits of the ports being zeroed.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocol` - Protocol of the parsed header, starting with both ports.
+    /// * `segment` - Raw bytes of the header.
+    fn write_port_categories(&mut self, protocol: ProtocolType, segment: &[u8]) {
+        if let Some(header) = header_range(self.protocols, self.config, &protocol) {
+            let ports = &mut self.data[header][..32];
+            for (port, bytes) in ports.chunks_mut(16).zip(segment.chunks(2)) {
+                let category = port_category(u16::from_be_bytes([bytes[0], bytes[1]]));
+                port.fill(0.);
+                for (i, bit) in port[..PORT_CATEGORY_BITS].iter_mut().enumerate() {
+                    let shift = match self.config.bit_order {
+                        BitOrder::Msb0 => PORT_CATEGORY_BITS - 1 - i,
+                        BitOrder::Lsb0 => i,
+                    };
+                    *bit = f32::from((category >> shift) & 1);
+                }
+            }
+        }
+    }
+}
+
+impl Headers {
+    /// Parses a single packet with the default configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet, starting with its Ethernet header.
+    /// * `protocols` - A slice of `ProtocolType` enums specifying the protocol to parsed.
+    ///
+    /// # Returns
+    ///
+    /// The parsed headers, holding the same values as the row of the packet in an `Nprint`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nprint_rs::{Headers, Nprint, ProtocolType};
+    ///
+    /// let packet = vec![
+    ///      0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+    ///      0x00, 0x1c, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+    ///      0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x08, 0x85, 0x00];
+    /// let protocols = vec![ProtocolType::Ipv4, ProtocolType::Udp];
+    /// let headers = Headers::parse(&packet, &protocols);
+    /// assert_eq!(headers.to_vec(), Nprint::new(&packet, protocols).print());
+    /// ```
+    pub fn parse(packet: &[u8], protocols: &[ProtocolType]) -> Headers {
+        let config = NprintConfig::default();
+        Headers::new(
+            packet,
+            config.link_layer,
+            protocols,
+            &config,
+            None,
+            None,
+            &mut ParseStats::default(),
+        )
+    }
+
+    /// Returns the bits of every protocol, in the order of the protocols given to `parse()`.
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    /// Returns whether the header of each protocol was parsed, `false` if its bits are the
+    /// fill value.
+    pub fn parsed(&self) -> &[bool] {
+        &self.parsed
+    }
+
+    /// Returns the bits of every protocol as a vector, see `data()`.
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.data.clone()
+    }
+
+    /// Creates a new `Headers` instance by parsing the given packet data
+    /// according to the specified list of protocols.
+    ///
+    /// Every header is written in place into a single row of bits, absent headers keeping
+    /// the configured fill value.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet.
+    /// * `link_layer` - Link layer of the packet, usually the configured one.
+    /// * `protocols` - A slice of `ProtocolType` enums specifying the protocol to parsed.
+    /// * `config` - Configuration of the parsing.
+    /// * `delta` - Time elapsed since the previous packet, if known.
+    /// * `wire_len` - Length of the packet on the wire, if known.
+    /// * `stats` - Statistics updated with the outcome of the parsing.
+    ///
+    /// # Returns
+    ///
+    /// A `Headers` struct containing the parsed protocol headers as specified.
+    ///
+    pub(crate) fn new(
+        packet: &[u8],
+        link_layer: LinkLayer,
+        protocols: &[ProtocolType],
+        config: &NprintConfig,
+        delta: Option<Duration>,
+        wire_len: Option<usize>,
+        stats: &mut ParseStats,
+    ) -> Headers {
+        let mut row = RowWriter::new(protocols, config);
+
+        stats.packets += 1;
+        let failures = stats.failures();
+        let link_layer = link::resolve(packet, link_layer);
+        if let Some((ethertype, payload)) = link::strip(packet, link_layer) {
+            if link_layer == LinkLayer::Ethernet {
+                let _ = row.parse::<EthernetHeader>(ProtocolType::Ethernet, packet);
+            }
+            parse_network(&mut row, ethertype, payload, config, stats);
+        } else {
+            stats.non_ethernet += 1;
+        }
+        if stats.failures() == failures {
+            stats.parsed += 1;
+        }
+        if let Some(delta) = delta {
+            let _ = row.parse::<TimingHeader>(ProtocolType::Timing, &encode_delta(delta));
+        }
+        if protocols.contains(&ProtocolType::FrameMeta) {
+            let (lengths, len) = encode_lengths(packet.len(), wire_len);
+            let _ = row.parse::<FrameMetaHeader>(ProtocolType::FrameMeta, &lengths[..len]);
+        }
+
+        Headers {
+            data: row.data,
+            parsed: row.parsed,
+        }
+    }
+
+    /// Remove sensitive data from every parsed header.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocols` - Ordered protocols of the `Nprint`.
+    /// * `config` - Configuration of the parsing.
+    pub(crate) fn anonymize(&mut self, protocols: &[ProtocolType], config: &NprintConfig) {
+        self.anonymize_fields(&AnonField::DEFAULT, protocols, config);
+    }
+
+    /// Remove the given sensitive fields from every parsed header.
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - Fields to remove.
+    /// * `protocols` - Ordered protocols of the `Nprint`.
+    /// * `config` - Configuration of the parsing.
+    pub(crate) fn anonymize_fields(
+        &mut self,
+        fields: &[AnonField],
+        protocols: &[ProtocolType],
+        config: &NprintConfig,
+    ) {
+        let original = self.checksum_snapshot(config);
+        self.keeping_port_categories(protocols, config, |headers| {
+            for field in fields {
+                if let Some(range) = header_range(protocols, config, &field.protocol()) {
+                    let bits = field.bits();
+                    remove(&mut headers.data[range], *bits.start(), *bits.end());
+                }
+            }
+        });
+        if let Some(original) = original {
+            self.recompute_checksums(&original, protocols, config);
+        }
+    }
+
+    /// Pseudonymize sensitive data of every parsed header with a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Secret key of the pseudonymization.
+    /// * `protocols` - Ordered protocols of the `Nprint`.
+    /// * `config` - Configuration of the parsing, setting in particular the bit order.
+    pub(crate) fn anonymize_with_key(
+        &mut self,
+        key: &[u8; 16],
+        protocols: &[ProtocolType],
+        config: &NprintConfig,
+    ) {
+        let original = self.checksum_snapshot(config);
+        self.keeping_port_categories(protocols, config, |headers| {
+            for (proto, range) in header_ranges(protocols, config) {
+                (proto.ops().anonymize_with_key)(&mut headers.data[range], key, config.bit_order);
+            }
+        });
+        if let Some(original) = original {
+            self.recompute_checksums(&original, protocols, config);
+        }
+    }
+
+    /// Runs an anonymization of the row, leaving the TCP and UDP ports untouched when they're
+    /// reduced to their range, which no longer identifies the hosts.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocols` - Ordered protocols of the `Nprint`.
+    /// * `config` - Configuration of the parsing.
+    /// * `anonymize` - Anonymization of the row.
+    fn keeping_port_categories(
+        &mut self,
+        protocols: &[ProtocolType],
+        config: &NprintConfig,
+        anonymize: impl FnOnce(&mut Headers),
+    ) {
+        let ports: Vec<(Range<usize>, Vec<f32>)> = [ProtocolType::Tcp, ProtocolType::Udp]
+            .iter()
+            .filter(|_| config.port_categories)
+            .filter_map(|protocol| header_range(protocols, config, protocol))
+            .map(|header| {
+                let ports = header.start..header.start + 32;
+                (ports.clone(), self.data[ports].to_vec())
+            })
+            .collect();
+        anonymize(self);
+        for (range, bits) in ports {
+            self.data[range].copy_from_slice(&bits);
+        }
+    }
+
+    /// Copies the row before anonymization if its checksums are recomputed afterwards.
+    fn checksum_snapshot(&self, config: &NprintConfig) -> Option<Vec<f32>> {
+        (config.recompute_checksums && !config.checksum_validity).then(|| self.data.clone())
+    }
+
+    /// Updates the IPv4, TCP and UDP checksums to cover the anonymized fields.
+    ///
+    /// Only the words changed by the anonymization are accounted for (RFC 1624), so the
+    /// payload isn't needed and a checksum invalid before stays invalid. Checksums of headers
+    /// with absent bits, and UDP checksums left unset (0), are kept as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `original` - Row before anonymization.
+    /// * `protocols` - Ordered protocols of the `Nprint`.
+    /// * `config` - Configuration of the parsing.
+    fn recompute_checksums(
+        &mut self,
+        original: &[f32],
+        protocols: &[ProtocolType],
+        config: &NprintConfig,
+    ) {
+        let order = config.bit_order;
+        let addresses = |data: &[f32]| {
+            header_range(protocols, config, &ProtocolType::Ipv4)
+                .and_then(|range| bits_to_bytes(&data[range][IPV4_ADDRESSES], order))
+        };
+        // Addresses of the pseudo-header, only if they were present before anonymization.
+        let pseudo = addresses(original)
+            .zip(addresses(&self.data))
+            .unwrap_or_default();
+        for (protocol, field) in [
+            (ProtocolType::Ipv4, IPV4_CHECKSUM),
+            (ProtocolType::Tcp, TCP_CHECKSUM),
+            (ProtocolType::Udp, UDP_CHECKSUM),
+        ] {
+            let Some(range) = header_range(protocols, config, &protocol) else {
+                continue;
+            };
+            let reassemble = |data: &[f32]| {
+                (protocol.ops().reassemble)(&wire_bits(protocol, data, config), order)
+            };
+            let (Some(mut old), Some(mut new)) = (
+                reassemble(&original[range.clone()]),
+                reassemble(&self.data[range.clone()]),
+            ) else {
+                continue;
+            };
+            let bits = &mut self.data[range][field];
+            let Some(checksum) = bits_to_value(bits, order) else {
+                continue;
+            };
+            if protocol == ProtocolType::Udp && checksum == 0 {
+                continue;
+            }
+            if protocol != ProtocolType::Ipv4 {
+                old.splice(0..0, pseudo.0.iter().copied());
+                new.splice(0..0, pseudo.1.iter().copied());
             }
+            let updated = match update_checksum(checksum as u16, &old, &new) {
+                0 if protocol == ProtocolType::Udp => 0xffff,
+                updated => updated,
+            };
+            value_to_bits(bits, updated.into(), order);
         }
-        Headers { data }
     }
 }
@@ -0,0 +1,138 @@
+//! Compact binary encoding of the nprint values, 2 bits per value.
+//!
+//! Layout, all integers big-endian:
+//!
+//! | Bytes | Content                                                 |
+//! |-------|---------------------------------------------------------|
+//! | 0..4  | Number of packets (`u32`)                               |
+//! | 4..8  | Number of values per packet (`u32`)                     |
+//! | 8..   | Values of every packet in order, 4 per byte             |
+//!
+//! Each value is a 2-bit code, the first value of a byte in its most significant bits:
+//! `00` for `0`, `01` for `1` and `10` for any other value (absent bit). The last byte is
+//! padded with `00`.
+use crate::{Nprint, NprintError};
+
+/// Length of the layout header: number of packets and values per packet.
+const PACKED_HEADER_LEN: usize = 8;
+
+impl Nprint {
+    /// Returns the nprint values packed on 2 bits each, see the module layout.
+    ///
+    /// Absent bits are encoded the same whatever the fill value, except with `FillValue::Zero`
+    /// where they can't be told apart from null bits.
+    pub fn to_packed_bytes(&self) -> Vec<u8> {
+        let width = self.bits_per_packet();
+        let count = self.data.len();
+        let mut bytes = Vec::with_capacity(PACKED_HEADER_LEN + (count * width).div_ceil(4));
+        bytes.extend((count as u32).to_be_bytes());
+        bytes.extend((width as u32).to_be_bytes());
+        let mut codes = self.iter_packets().flatten().map(|value| match value {
+            0. => 0b00,
+            1. => 0b01,
+            _ => 0b10,
+        });
+        loop {
+            let mut byte = 0u8;
+            let mut len = 0;
+            for code in codes.by_ref().take(4) {
+                byte |= code << (6 - 2 * len);
+                len += 1;
+            }
+            if len == 0 {
+                break;
+            }
+            bytes.push(byte);
+        }
+        bytes
+    }
+
+    /// Unpacks values written by `to_packed_bytes()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Packed values, see the module layout.
+    ///
+    /// # Returns
+    ///
+    /// The values of every packet, absent bits decoded as `-1`, or an error if the bytes are
+    /// malformed, e.g. packets without any value.
+    pub fn from_packed_bytes(bytes: &[u8]) -> Result<Vec<Vec<f32>>, NprintError> {
+        let (header, codes) = bytes
+            .split_first_chunk::<PACKED_HEADER_LEN>()
+            .ok_or(NprintError::MalformedPackedBytes)?;
+        let count = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let width = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if width == 0 && count > 0 {
+            // No bytes would back the rows, which could be allocated without bound
+            return Err(NprintError::MalformedPackedBytes);
+        }
+        let len = count
+            .checked_mul(width)
+            .filter(|len| len.div_ceil(4) == codes.len())
+            .ok_or(NprintError::MalformedPackedBytes)?;
+        let values = (0..len)
+            .map(|i| match (codes[i / 4] >> (6 - 2 * (i % 4))) & 0b11 {
+                0b00 => Ok(0.),
+                0b01 => Ok(1.),
+                0b10 => Ok(-1.),
+                _ => Err(NprintError::MalformedPackedBytes),
+            })
+            .collect::<Result<Vec<f32>, _>>()?;
+        Ok((0..count)
+            .map(|row| values[row * width..(row + 1) * width].to_vec())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod packed_tests {
+    use crate::{Nprint, NprintError, ProtocolType};
+
+    const PACKET: [u8; 54] = [
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00, 0x00,
+        0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25, 0xc6, 0x26,
+        0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00, 0x00, 0x00, 0x50,
+        0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn test_packed_round_trip() {
+        let mut nprint = Nprint::new(&PACKET, vec![ProtocolType::Ipv4, ProtocolType::Tcp]);
+        for _ in 0..99 {
+            nprint.add(&PACKET);
+        }
+        let bytes = nprint.to_packed_bytes();
+        assert_eq!(bytes.len(), 8 + 100 * 960 / 4, "Expected 2 bits per value");
+        let rows = Nprint::from_packed_bytes(&bytes).unwrap();
+        assert_eq!(rows.len(), 100);
+        assert_eq!(rows.concat(), nprint.print(), "Expected print() back");
+    }
+
+    #[test]
+    fn test_packed_padding() {
+        let nprint = Nprint::new(&[0x0], vec![ProtocolType::Vlan, ProtocolType::Gre]);
+        let mut bytes = nprint.to_packed_bytes();
+        assert_eq!(bytes[..8], [0, 0, 0, 1, 0, 0, 0, 48]);
+        assert_eq!(bytes[8], 0b10101010, "Expected absent bits");
+        assert_eq!(
+            Nprint::from_packed_bytes(&bytes).unwrap(),
+            vec![nprint.print()]
+        );
+        bytes[8] = 0xff;
+        assert!(matches!(
+            Nprint::from_packed_bytes(&bytes),
+            Err(NprintError::MalformedPackedBytes)
+        ));
+        assert!(Nprint::from_packed_bytes(&bytes[..19]).is_err());
+        assert!(Nprint::from_packed_bytes(&bytes[..4]).is_err());
+        assert!(matches!(
+            Nprint::from_packed_bytes(&[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0]),
+            Err(NprintError::MalformedPackedBytes)
+        ));
+        assert_eq!(
+            Nprint::from_packed_bytes(&[0; 8]).unwrap(),
+            Vec::<Vec<f32>>::new()
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+use pnet::packet::ip::IpNextHeaderProtocol;
+
+/// Implementation of AH header, without its integrity check value: next header, payload
+/// length, SPI and sequence number.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct AhHeader {
+    /// A flat vector of parsed bit values, size of 96 bits as it's the fixed AH header length
+    data: Vec<f32>,
+}
+
+impl Default for AhHeader {
+    /// Returns an `AhHeader` filled with 96 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for AhHeader {
+    /// Number of bits extracted, the 12 fixed bytes of the AH header.
+    const WIDTH: usize = 96;
+
+    /// Constructs an `AhHeader` from a raw bytes AH packet.
+    ///
+    /// If the input holds at least the 12 fixed bytes, they are parsed bit by bit.
+    /// Returns `None` if the packet is too short.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing an AH packet.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let header = packet.get(..12)?;
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(header, config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> AhHeader {
+        AhHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Ah`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Ah
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `ah_nh_0`, `ah_nh_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("ah_nh", 8),
+            ("ah_len", 8),
+            ("ah_reserved", 16),
+            ("ah_spi", 32),
+            ("ah_seq", 32),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in an AH header, the SPI only identifies a security association.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl AhHeader {
+    /// Returns an `AhHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Skips the AH header of a packet, integrity check value included.
+///
+/// # Arguments
+/// * `packet` - Raw bytes representing an AH packet.
+///
+/// # Returns
+/// The next header and the bytes it announces, or `None` if the header is truncated.
+pub(crate) fn skip_ah(packet: &[u8]) -> Option<(IpNextHeaderProtocol, &[u8])> {
+    let next_header = IpNextHeaderProtocol(*packet.first()?);
+    // Payload length in 4-byte units, minus 2
+    let len = (*packet.get(1)? as usize + 2) * 4;
+    Some((next_header, packet.get(len..)?))
+}
+
+#[cfg(test)]
+mod ah_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+    use pnet::packet::ip::IpNextHeaderProtocols;
+
+    const PACKET: [u8; 26] = [
+        0x06, 0x04, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0xbb,
+    ];
+
+    #[test]
+    fn test_ah_header_creation() {
+        let ah_header = AhHeader::new(&PACKET);
+        let data = ah_header.get_data();
+        assert_eq!(data.len(), 96, "Expected 96 bits in AhHeader data.");
+        assert_eq!(
+            data[..8],
+            [0., 0., 0., 0., 0., 1., 1., 0.],
+            "Next header doesn't match expected."
+        );
+        assert_eq!(
+            data[56..64],
+            [0., 0., 0., 0., 0., 0., 1., 0.],
+            "SPI doesn't match expected."
+        );
+    }
+
+    #[test]
+    fn test_ah_header_get_headers() {
+        let headers = AhHeader::get_headers();
+        assert_eq!(headers.len(), 96, "Expected 96 header names.");
+        assert_eq!(headers[0], "ah_nh_0");
+        assert_eq!(headers[8], "ah_len_0");
+        assert_eq!(headers[32], "ah_spi_0");
+        assert_eq!(headers[95], "ah_seq_31");
+    }
+
+    #[test]
+    fn test_ah_header_bad_header() {
+        let ah_header = AhHeader::new(&PACKET[..11]);
+        assert!(
+            ah_header.bit_eq(&AhHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            AhHeader::new_with_config(&PACKET[..11], &config).bit_eq(&AhHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+
+    #[test]
+    fn test_skip_ah() {
+        let (next_header, upper) = skip_ah(&PACKET).unwrap();
+        assert_eq!(next_header, IpNextHeaderProtocols::Tcp);
+        assert_eq!(upper, [0x01, 0xbb], "Expected the ICV to be skipped.");
+        assert_eq!(skip_ah(&PACKET[..20]), None);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, remove, BitWriter};
+use crate::ProtocolType;
+use pnet::packet::arp::ArpPacket;
+use pnet::packet::Packet;
+
+/// Implementation of ARP header.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct ArpHeader {
+    /// A flat vector of parsed bit values, size of 224 bits as it's the Ethernet/IPv4 ARP length
+    data: Vec<f32>,
+}
+
+impl Default for ArpHeader {
+    /// Returns an `ArpHeader` filled with 224 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for ArpHeader {
+    /// Number of bits extracted, the 28 bytes of an Ethernet/IPv4 ARP message.
+    const WIDTH: usize = 224;
+
+    /// Constructs an `ArpHeader` from a raw bytes ARP packet.
+    ///
+    /// If the input is a valid Ethernet/IPv4 ARP packet, its fields are parsed bit by bit.
+    /// Returns `None` if the packet is invalid or cannot be parsed.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing an ARP packet.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let packet = ArpPacket::new(packet)?;
+        let mut data = BitWriter::new(out);
+        let packet = packet.packet();
+        data.extend(bytes_to_bits(&packet[..28], config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> ArpHeader {
+        ArpHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Arp`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Arp
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `arp_htype_0`, `arp_htype_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("arp_htype", 16),
+            ("arp_ptype", 16),
+            ("arp_hlen", 8),
+            ("arp_plen", 8),
+            ("arp_oper", 16),
+            ("arp_sha", 48),
+            ("arp_spa", 32),
+            ("arp_tha", 48),
+            ("arp_tpa", 32),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Anonymize sender and target addresses
+    fn anonymize_bits(data: &mut [f32]) {
+        remove(data, 64, 111); // Sender hardware address
+        remove(data, 112, 143); // Sender protocol address
+        remove(data, 144, 191); // Target hardware address
+        remove(data, 192, 223); // Target protocol address
+    }
+}
+
+impl ArpHeader {
+    /// Returns an `ArpHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+#[cfg(test)]
+mod arp_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    const PACKET: [u8; 28] = [
+        0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0xc0,
+        0xa8, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0xa8, 0x01, 0x02,
+    ];
+
+    #[test]
+    fn test_arp_header_creation() {
+        let arp_header = ArpHeader::new(&PACKET);
+        let data = arp_header.get_data();
+        assert_eq!(data.len(), 224, "Expected 224 bits in ArpHeader data.");
+        assert_eq!(
+            data[..16],
+            [0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1.],
+            "Hardware type doesn't match expected."
+        );
+        assert_eq!(
+            data[48..64],
+            [0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1.],
+            "Operation doesn't match expected."
+        );
+        assert_eq!(
+            data[112..120],
+            [1., 1., 0., 0., 0., 0., 0., 0.],
+            "Sender protocol address doesn't match expected."
+        );
+        assert_eq!(
+            data[223], 0.,
+            "Target protocol address doesn't match expected."
+        );
+        assert_eq!(
+            data[222], 1.,
+            "Target protocol address doesn't match expected."
+        );
+    }
+
+    #[test]
+    fn test_arp_header_get_headers() {
+        let headers = ArpHeader::get_headers();
+        assert_eq!(headers.len(), 224, "Expected 224 header names.");
+        assert_eq!(headers[0], "arp_htype_0");
+        assert_eq!(headers[48], "arp_oper_0");
+        assert_eq!(headers[64], "arp_sha_0");
+        assert_eq!(headers[112], "arp_spa_0");
+        assert_eq!(headers[144], "arp_tha_0");
+        assert_eq!(headers[223], "arp_tpa_31");
+    }
+
+    #[test]
+    fn test_arp_header_bad_header() {
+        let arp_header = ArpHeader::new(&PACKET[..20]);
+        assert!(
+            arp_header.bit_eq(&ArpHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            ArpHeader::new_with_config(&PACKET[..20], &config).bit_eq(&ArpHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+
+    #[test]
+    fn test_arp_header_anonymize() {
+        let mut arp_header = ArpHeader::new(&PACKET);
+        arp_header.anonymize();
+        let anon = arp_header.get_data();
+        for addr_bit in anon.iter().skip(64) {
+            assert_eq!(*addr_bit, 0., "Expected data bit 64-223 to be 0.");
+        }
+        assert_eq!(anon[15], 1., "Expected hardware type to be kept.");
+    }
+}
@@ -0,0 +1,88 @@
+//! Prefix-preserving IPv4 pseudonymization, following Crypto-PAn (Xu et al., 2002).
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes128;
+
+/// Keyed, prefix-preserving permutation of IPv4 addresses.
+///
+/// Two addresses sharing a `n`-bit prefix are mapped to pseudonyms sharing a `n`-bit prefix,
+/// and the same address is always mapped to the same pseudonym under the same key.
+pub(crate) struct CryptoPan {
+    /// Block cipher used as pseudorandom function.
+    cipher: Aes128,
+    /// Bits completing each prefix into a full cipher block.
+    pad: [u8; 16],
+}
+
+impl CryptoPan {
+    /// Creates the permutation of the given key.
+    ///
+    /// # Arguments
+    /// * `key` - Secret key, used both as AES key and to derive the padding.
+    pub fn new(key: &[u8; 16]) -> CryptoPan {
+        let cipher = Aes128::new(GenericArray::from_slice(key));
+        let mut pad = GenericArray::default();
+        cipher.encrypt_block(&mut pad);
+        CryptoPan {
+            cipher,
+            pad: pad.into(),
+        }
+    }
+
+    /// Returns the pseudonym of an IPv4 address.
+    ///
+    /// Each bit is flipped depending on a keyed function of the bits preceding it.
+    ///
+    /// # Arguments
+    /// * `address` - IPv4 address as a big-endian integer.
+    pub fn anonymize(&self, address: u32) -> u32 {
+        let pad = u32::from_be_bytes([self.pad[0], self.pad[1], self.pad[2], self.pad[3]]);
+        let mut flips = 0;
+        for position in 0..32 {
+            let mask = u32::MAX.checked_shl(32 - position).unwrap_or(0);
+            let mut block = GenericArray::from(self.pad);
+            block[..4].copy_from_slice(&((address & mask) | (pad & !mask)).to_be_bytes());
+            self.cipher.encrypt_block(&mut block);
+            flips |= u32::from(block[0] >> 7) << (31 - position);
+        }
+        address ^ flips
+    }
+}
+
+#[cfg(test)]
+mod cryptopan_tests {
+    use super::*;
+
+    const KEY: [u8; 16] = *b"nprint-rs secret";
+
+    #[test]
+    fn test_cryptopan_deterministic() {
+        let cryptopan = CryptoPan::new(&KEY);
+        let address = u32::from_be_bytes([192, 168, 43, 37]);
+        assert_eq!(cryptopan.anonymize(address), cryptopan.anonymize(address));
+        assert_ne!(
+            cryptopan.anonymize(address),
+            CryptoPan::new(&[0; 16]).anonymize(address),
+            "Expected the pseudonym to depend on the key"
+        );
+    }
+
+    #[test]
+    fn test_cryptopan_prefix_preserving() {
+        let cryptopan = CryptoPan::new(&KEY);
+        let first = cryptopan.anonymize(u32::from_be_bytes([192, 168, 43, 37]));
+        let second = cryptopan.anonymize(u32::from_be_bytes([192, 168, 43, 200]));
+        let other = cryptopan.anonymize(u32::from_be_bytes([10, 0, 0, 1]));
+        assert_eq!(
+            first >> 8,
+            second >> 8,
+            "Expected the /24 prefix to be kept"
+        );
+        assert_ne!(first, second);
+        assert_eq!(
+            (first ^ other).leading_zeros(),
+            0,
+            "Expected the first bit to differ"
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, remove, BitWriter};
+use crate::ProtocolType;
+
+/// Length in bytes of the fixed BOOTP fields, up to the client hardware address.
+const DHCP_HEADER_LEN: usize = 44;
+
+/// Implementation of DHCP (BOOTP) header, its fixed fields up to the client hardware address.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct DhcpHeader {
+    /// A flat vector of parsed bit values, size of 352 bits as it's the length of the fixed fields up to chaddr
+    data: Vec<f32>,
+}
+
+impl Default for DhcpHeader {
+    /// Returns a `DhcpHeader` filled with 352 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for DhcpHeader {
+    /// Number of bits extracted, op to chaddr, the fixed BOOTP fields preceding the server name.
+    const WIDTH: usize = 352;
+
+    /// Constructs a `DhcpHeader` from a raw bytes DHCP message.
+    ///
+    /// If the input is long enough to hold the fixed fields, they are parsed bit by bit.
+    /// The server name, boot file name and options aren't extracted.
+    /// Returns `None` if the message is too short.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing a DHCP message (UDP payload).
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let header = packet.get(..DHCP_HEADER_LEN)?;
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(header, config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> DhcpHeader {
+        DhcpHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Dhcp`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Dhcp
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `dhcp_op_0`, `dhcp_op_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("dhcp_op", 8),
+            ("dhcp_htype", 8),
+            ("dhcp_hlen", 8),
+            ("dhcp_hops", 8),
+            ("dhcp_xid", 32),
+            ("dhcp_secs", 16),
+            ("dhcp_flags", 16),
+            ("dhcp_ciaddr", 32),
+            ("dhcp_yiaddr", 32),
+            ("dhcp_siaddr", 32),
+            ("dhcp_giaddr", 32),
+            ("dhcp_chaddr", 128),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Remove the client, your, server and gateway IPs and the client hardware address.
+    fn anonymize_bits(data: &mut [f32]) {
+        remove(data, 96, 223); // IP Client, Your, Server and Gateway
+        remove(data, 224, 351); // Client hardware address
+    }
+}
+
+impl DhcpHeader {
+    /// Returns a `DhcpHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Returns `true` if one of the UDP ports is used by DHCP (BOOTP).
+///
+/// # Arguments
+/// * `src_port` - UDP source port.
+/// * `dst_port` - UDP destination port.
+pub(crate) fn is_dhcp(src_port: u16, dst_port: u16) -> bool {
+    [67, 68].contains(&src_port) || [67, 68].contains(&dst_port)
+}
+
+#[cfg(test)]
+mod dhcp_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    /// Returns a DHCP discover from the client `02:00:00:00:00:01`, relayed by 10.0.0.1.
+    fn discover() -> Vec<u8> {
+        let mut packet = vec![0x01, 0x01, 0x06, 0x01, 0x39, 0x03, 0xf3, 0x26, 0x00, 0x05];
+        packet.extend([0x80, 0x00]);
+        packet.extend([0; 12]);
+        packet.extend([10, 0, 0, 1]);
+        packet.extend([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+        packet.extend([0; 10 + 192]);
+        packet.extend([0x63, 0x82, 0x53, 0x63, 0x35, 0x01, 0x01, 0xff]);
+        packet
+    }
+
+    #[test]
+    fn test_dhcp_header_creation() {
+        let dhcp_header = DhcpHeader::new(&discover());
+        let data = dhcp_header.get_data();
+        assert_eq!(data.len(), 352, "Expected 352 bits in DhcpHeader data.");
+        assert_eq!(
+            data[..8],
+            [0., 0., 0., 0., 0., 0., 0., 1.],
+            "Expected a BOOTREQUEST."
+        );
+        assert_eq!(
+            data[16..24],
+            [0., 0., 0., 0., 0., 1., 1., 0.],
+            "Expected hlen 6."
+        );
+        assert_eq!(data[80], 1., "Expected the broadcast flag.");
+        assert_eq!(
+            data[192..200],
+            [0., 0., 0., 0., 1., 0., 1., 0.],
+            "Expected giaddr 10.0.0.1."
+        );
+        assert_eq!(data[230], 1., "Expected a locally administered chaddr.");
+    }
+
+    #[test]
+    fn test_dhcp_header_get_headers() {
+        let headers = DhcpHeader::get_headers();
+        assert_eq!(headers.len(), 352, "Expected 352 header names.");
+        assert_eq!(headers[0], "dhcp_op_0");
+        assert_eq!(headers[32], "dhcp_xid_0");
+        assert_eq!(headers[96], "dhcp_ciaddr_0");
+        assert_eq!(headers[192], "dhcp_giaddr_0");
+        assert_eq!(headers[224], "dhcp_chaddr_0");
+        assert_eq!(headers[351], "dhcp_chaddr_127");
+    }
+
+    #[test]
+    fn test_dhcp_header_bad_header() {
+        let dhcp_header = DhcpHeader::new(&discover()[..40]);
+        assert!(
+            dhcp_header.bit_eq(&DhcpHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            DhcpHeader::new_with_config(&discover()[..40], &config)
+                .bit_eq(&DhcpHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+
+    #[test]
+    fn test_dhcp_header_anonymize() {
+        let mut dhcp_header = DhcpHeader::new(&discover());
+        let original = dhcp_header.clone();
+        dhcp_header.anonymize();
+        let anon = dhcp_header.get_data();
+        assert_eq!(
+            anon[..96],
+            original.get_data()[..96],
+            "Expected fields to be kept."
+        );
+        assert!(
+            anon[96..].iter().all(|bit| *bit == 0.),
+            "Expected addresses to be 0."
+        );
+    }
+
+    #[test]
+    fn test_is_dhcp() {
+        assert!(is_dhcp(68, 67));
+        assert!(is_dhcp(67, 68));
+        assert!(!is_dhcp(53, 40000));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+
+/// Length in bytes of the DNS header.
+const DNS_HEADER_LEN: usize = 12;
+
+/// Implementation of DNS header.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct DnsHeader {
+    /// A flat vector of parsed bit values, size of 96 bits as it's the DNS header length
+    data: Vec<f32>,
+}
+
+impl Default for DnsHeader {
+    /// Returns a `DnsHeader` filled with 96 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for DnsHeader {
+    /// Number of bits extracted, the 12 bytes of the DNS header.
+    const WIDTH: usize = 96;
+
+    /// Constructs a `DnsHeader` from a raw bytes DNS message.
+    ///
+    /// If the input is long enough to hold a DNS header, its fields are parsed bit by bit.
+    /// Returns `None` if the message is too short.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing a DNS message (UDP payload).
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        if packet.len() < DNS_HEADER_LEN {
+            return None;
+        }
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(&packet[..12], config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> DnsHeader {
+        DnsHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Dns`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Dns
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `dns_id_0`, `dns_id_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("dns_id", 16),
+            ("dns_qr", 1),
+            ("dns_opcode", 4),
+            ("dns_aa", 1),
+            ("dns_tc", 1),
+            ("dns_rd", 1),
+            ("dns_ra", 1),
+            ("dns_z", 3),
+            ("dns_rcode", 4),
+            ("dns_qdcount", 16),
+            ("dns_ancount", 16),
+            ("dns_nscount", 16),
+            ("dns_arcount", 16),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in a DNS header.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl DnsHeader {
+    /// Returns a `DnsHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Returns `true` if one of the UDP ports is used by DNS or mDNS.
+///
+/// # Arguments
+/// * `src_port` - UDP source port.
+/// * `dst_port` - UDP destination port.
+pub(crate) fn is_dns(src_port: u16, dst_port: u16) -> bool {
+    [53, 5353].contains(&src_port) || [53, 5353].contains(&dst_port)
+}
+
+#[cfg(test)]
+mod dns_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    #[test]
+    fn test_dns_header_creation() {
+        // Standard query, recursion desired, one question
+        let raw_packet: Vec<u8> = vec![
+            0xab, 0xcd, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65,
+        ];
+        let dns_header = DnsHeader::new(&raw_packet);
+        let data = dns_header.get_data();
+        assert_eq!(data.len(), 96, "Expected 96 bits in DnsHeader data.");
+        assert_eq!(
+            data[..16],
+            [1., 0., 1., 0., 1., 0., 1., 1., 1., 1., 0., 0., 1., 1., 0., 1.],
+            "DNS id doesn't match expected."
+        );
+        assert_eq!(
+            data[16..32],
+            [0., 0., 0., 0., 0., 0., 0., 1., 0., 0., 0., 0., 0., 0., 0., 0.],
+            "DNS flags don't match expected."
+        );
+        assert_eq!(data[47], 1., "Expected QDCOUNT to be 1.");
+        assert!(data[48..].iter().all(|bit| *bit == 0.));
+    }
+
+    #[test]
+    fn test_dns_header_get_headers() {
+        let headers = DnsHeader::get_headers();
+        assert_eq!(headers.len(), 96, "Expected 96 header names.");
+        assert_eq!(headers[0], "dns_id_0");
+        assert_eq!(headers[16], "dns_qr_0");
+        assert_eq!(headers[17], "dns_opcode_0");
+        assert_eq!(headers[23], "dns_rd_0");
+        assert_eq!(headers[28], "dns_rcode_0");
+        assert_eq!(headers[31], "dns_rcode_3");
+        assert_eq!(headers[32], "dns_qdcount_0");
+        assert_eq!(headers[95], "dns_arcount_15");
+    }
+
+    #[test]
+    fn test_dns_header_bad_header() {
+        let raw_packet: Vec<u8> = vec![0xab, 0xcd, 0x01, 0x00];
+        let dns_header = DnsHeader::new(&raw_packet);
+        assert!(
+            dns_header.bit_eq(&DnsHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            DnsHeader::new_with_config(&raw_packet, &config).bit_eq(&DnsHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+
+    #[test]
+    fn test_is_dns() {
+        assert!(is_dns(40000, 53));
+        assert!(is_dns(53, 40000));
+        assert!(is_dns(5353, 5353));
+        assert!(!is_dns(57621, 57621));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+
+/// Implementation of ESP header, only its visible part: SPI and sequence number.
+///
+/// The payload following them is encrypted and isn't parsed.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct EspHeader {
+    /// A flat vector of parsed bit values, size of 64 bits as it's the visible ESP header length
+    data: Vec<f32>,
+}
+
+impl Default for EspHeader {
+    /// Returns an `EspHeader` filled with 64 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for EspHeader {
+    /// Number of bits extracted, the 8 bytes of the SPI and sequence number.
+    const WIDTH: usize = 64;
+
+    /// Constructs an `EspHeader` from a raw bytes ESP packet.
+    ///
+    /// If the input holds at least the SPI and sequence number, they are parsed bit by bit.
+    /// Returns `None` if the packet is too short.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing an ESP packet.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let header = packet.get(..8)?;
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(header, config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> EspHeader {
+        EspHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Esp`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Esp
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `esp_spi_0`, `esp_spi_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [("esp_spi", 32), ("esp_seq", 32)];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in an ESP header, the SPI only identifies a security association.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl EspHeader {
+    /// Returns an `EspHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+#[cfg(test)]
+mod esp_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    const PACKET: [u8; 12] = [
+        0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x05, 0xde, 0xad, 0xbe, 0xef,
+    ];
+
+    #[test]
+    fn test_esp_header_creation() {
+        let esp_header = EspHeader::new(&PACKET);
+        let data = esp_header.get_data();
+        assert_eq!(data.len(), 64, "Expected 64 bits in EspHeader data.");
+        assert_eq!(
+            data[24..32],
+            [0., 0., 0., 0., 0., 0., 1., 0.],
+            "SPI doesn't match expected."
+        );
+        assert_eq!(
+            data[56..64],
+            [0., 0., 0., 0., 0., 1., 0., 1.],
+            "Sequence number doesn't match expected."
+        );
+    }
+
+    #[test]
+    fn test_esp_header_get_headers() {
+        let headers = EspHeader::get_headers();
+        assert_eq!(headers.len(), 64, "Expected 64 header names.");
+        assert_eq!(headers[0], "esp_spi_0");
+        assert_eq!(headers[31], "esp_spi_31");
+        assert_eq!(headers[32], "esp_seq_0");
+        assert_eq!(headers[63], "esp_seq_31");
+    }
+
+    #[test]
+    fn test_esp_header_bad_header() {
+        let esp_header = EspHeader::new(&PACKET[..7]);
+        assert!(
+            esp_header.bit_eq(&EspHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            EspHeader::new_with_config(&PACKET[..7], &config).bit_eq(&EspHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, remove, BitWriter};
+use crate::ProtocolType;
+use pnet::packet::ethernet::EthernetPacket;
+use pnet::packet::Packet;
+
+/// Implementation of Ethernet II header.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct EthernetHeader {
+    /// A flat vector of parsed bit values, size of 112 bits as it's the Ethernet II header length
+    data: Vec<f32>,
+}
+
+impl Default for EthernetHeader {
+    /// Returns an `EthernetHeader` filled with 112 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for EthernetHeader {
+    /// Number of bits extracted, the 14 bytes of the Ethernet header.
+    const WIDTH: usize = 112;
+
+    /// Constructs an `EthernetHeader` from a raw bytes Ethernet frame.
+    ///
+    /// If the input is a valid Ethernet frame, its fields are parsed bit by bit.
+    /// Returns `None` if the frame is invalid or cannot be parsed.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing an Ethernet frame.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let packet = EthernetPacket::new(packet)?;
+        let mut data = BitWriter::new(out);
+        let packet = packet.packet();
+        data.extend(bytes_to_bits(&packet[..6], config.bit_order));
+        data.extend(bytes_to_bits(&packet[6..12], config.bit_order));
+        data.extend(bytes_to_bits(&packet[12..14], config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> EthernetHeader {
+        EthernetHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Ethernet`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Ethernet
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `eth_dhost_0`, `eth_dhost_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [("eth_dhost", 48), ("eth_shost", 48), ("eth_ethertype", 16)];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Anonymize MAC destination and source
+    fn anonymize_bits(data: &mut [f32]) {
+        remove(data, 0, 47); // MAC destination
+        remove(data, 48, 95); // MAC source
+    }
+}
+
+impl EthernetHeader {
+    /// Returns an `EthernetHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+#[cfg(test)]
+mod ethernet_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    const FRAME: [u8; 14] = [
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0xff, 0xee, 0xdd, 0xcc, 0xbb, 0xaa, 0x08, 0x00,
+    ];
+
+    #[test]
+    fn test_ethernet_header_creation() {
+        let ethernet_header = EthernetHeader::new(&FRAME);
+        let data = ethernet_header.get_data();
+        assert_eq!(data.len(), 112, "Expected 112 bits in EthernetHeader data.");
+        assert_eq!(
+            data[..16],
+            [0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1., 0., 0., 0., 1.],
+            "Destination MAC doesn't match expected."
+        );
+        assert_eq!(
+            data[48..56],
+            [1., 1., 1., 1., 1., 1., 1., 1.],
+            "Source MAC doesn't match expected."
+        );
+        assert_eq!(
+            data[96..],
+            [0., 0., 0., 0., 1., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0.],
+            "Ethertype doesn't match expected."
+        );
+    }
+
+    #[test]
+    fn test_ethernet_header_get_headers() {
+        let headers = EthernetHeader::get_headers();
+        assert_eq!(headers.len(), 112, "Expected 112 header names.");
+        assert_eq!(headers[0], "eth_dhost_0");
+        assert_eq!(headers[47], "eth_dhost_47");
+        assert_eq!(headers[48], "eth_shost_0");
+        assert_eq!(headers[96], "eth_ethertype_0");
+        assert_eq!(headers[111], "eth_ethertype_15");
+    }
+
+    #[test]
+    fn test_ethernet_header_bad_header() {
+        let ethernet_header = EthernetHeader::new(&FRAME[..10]);
+        assert!(
+            ethernet_header.bit_eq(&EthernetHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            EthernetHeader::new_with_config(&FRAME[..10], &config)
+                .bit_eq(&EthernetHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+
+    #[test]
+    fn test_ethernet_header_anonymize() {
+        let mut ethernet_header = EthernetHeader::new(&FRAME);
+        ethernet_header.anonymize();
+        let anon = ethernet_header.get_data();
+        for mac_bit in anon.iter().take(96) {
+            assert_eq!(*mac_bit, 0., "Expected data bit 0-95 to be 0.");
+        }
+        assert_eq!(anon[100], 1., "Expected ethertype to be kept.");
+    }
+}
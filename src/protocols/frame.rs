@@ -0,0 +1,161 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+
+/// Implementation of the frame lengths, a pseudo-protocol computed from the captured packet.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct FrameMetaHeader {
+    /// A flat vector of parsed bit values, size of 32 bits as it's the captured and wire lengths
+    data: Vec<f32>,
+}
+
+impl Default for FrameMetaHeader {
+    /// Returns a `FrameMetaHeader` filled with 32 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for FrameMetaHeader {
+    /// Number of bits extracted, the 16 bits of both lengths.
+    const WIDTH: usize = 32;
+
+    /// Constructs a `FrameMetaHeader` from encoded frame lengths, see `encode_lengths()`.
+    ///
+    /// The captured length is parsed bit by bit, followed by the wire length if present,
+    /// filled otherwise.
+    /// Returns `None` if the input is shorter than 2 bytes.
+    ///
+    /// # Arguments
+    /// * `packet` - Big-endian captured length, optionally followed by the wire length.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let caplen = packet.get(..2)?;
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(caplen, config.bit_order));
+        match packet.get(2..4) {
+            Some(wirelen) => data.extend(bytes_to_bits(wirelen, config.bit_order)),
+            None => data.fill(config.fill_value.value()),
+        }
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> FrameMetaHeader {
+        FrameMetaHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::FrameMeta`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::FrameMeta
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `frame_caplen_0`, `frame_caplen_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [("frame_caplen", 16), ("frame_wirelen", 16)];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in the frame lengths.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl FrameMetaHeader {
+    /// Returns a `FrameMetaHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Encodes the lengths of a frame as the input of `FrameMetaHeader::parse()`.
+///
+/// Lengths saturate at 65535 bytes.
+///
+/// # Arguments
+/// * `caplen` - Number of captured bytes.
+/// * `wire_len` - Length of the frame on the wire, if known.
+///
+/// # Returns
+/// The encoded lengths, and the number of bytes used, 4 with the wire length and 2 without.
+pub(crate) fn encode_lengths(caplen: usize, wire_len: Option<usize>) -> ([u8; 4], usize) {
+    let encode = |len: usize| u16::try_from(len).unwrap_or(u16::MAX).to_be_bytes();
+    let mut lengths = [0; 4];
+    lengths[..2].copy_from_slice(&encode(caplen));
+    match wire_len {
+        Some(wire_len) => {
+            lengths[2..].copy_from_slice(&encode(wire_len));
+            (lengths, 4)
+        }
+        None => (lengths, 2),
+    }
+}
+
+#[cfg(test)]
+mod frame_meta_header_tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_meta_header_creation() {
+        let (lengths, len) = encode_lengths(0x3c, Some(0x05ea));
+        let data = FrameMetaHeader::new(&lengths[..len]).get_data().clone();
+        assert_eq!(data.len(), 32, "Expected 32 bits in FrameMetaHeader data.");
+        assert_eq!(
+            data[8..16],
+            [0., 0., 1., 1., 1., 1., 0., 0.],
+            "Captured length doesn't match expected."
+        );
+        assert_eq!(
+            data[16..24],
+            [0., 0., 0., 0., 0., 1., 0., 1.],
+            "Wire length doesn't match expected."
+        );
+    }
+
+    #[test]
+    fn test_frame_meta_header_without_wire_length() {
+        let (lengths, len) = encode_lengths(70000, None);
+        assert_eq!(len, 2);
+        let data = FrameMetaHeader::new(&lengths[..len]).get_data().clone();
+        assert!(
+            data[..16].iter().all(|bit| *bit == 1.),
+            "Expected saturation."
+        );
+        assert!(
+            data[16..].iter().all(|bit| *bit == -1.),
+            "Expected an absent wire length."
+        );
+        assert!(FrameMetaHeader::new(&[0x01]).bit_eq(&FrameMetaHeader::default()));
+    }
+
+    #[test]
+    fn test_frame_meta_header_get_headers() {
+        let headers = FrameMetaHeader::get_headers();
+        assert_eq!(headers.len(), FrameMetaHeader::WIDTH);
+        assert_eq!(headers[0], "frame_caplen_0");
+        assert_eq!(headers[16], "frame_wirelen_0");
+    }
+}
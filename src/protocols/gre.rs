@@ -0,0 +1,193 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+use pnet::packet::ethernet::EtherType;
+
+/// Checksum present bit, or the routing present bit of RFC 1701, both adding 4 bytes.
+const GRE_CHECKSUM: u16 = 0x8000 | 0x4000;
+/// Key present bit.
+const GRE_KEY: u16 = 0x2000;
+/// Sequence number present bit.
+const GRE_SEQUENCE: u16 = 0x1000;
+/// Version bits.
+const GRE_VERSION: u16 = 0x0007;
+
+/// Implementation of GRE header, only its mandatory part: flags, version and protocol type.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct GreHeader {
+    /// A flat vector of parsed bit values, size of 32 bits as it's the mandatory GRE header length
+    data: Vec<f32>,
+}
+
+impl Default for GreHeader {
+    /// Returns a `GreHeader` filled with 32 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for GreHeader {
+    /// Number of bits extracted, the 4 mandatory bytes of the GRE header.
+    const WIDTH: usize = 32;
+
+    /// Constructs a `GreHeader` from a raw bytes GRE packet.
+    ///
+    /// If the input holds at least the 4 mandatory bytes, they are parsed bit by bit.
+    /// Returns `None` if the packet is too short.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing a GRE packet.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        if packet.len() < 4 {
+            return None;
+        }
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(&packet[..4], config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> GreHeader {
+        GreHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Gre`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Gre
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `gre_c_0`, `gre_c_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("gre_c", 1),
+            ("gre_r", 1),
+            ("gre_k", 1),
+            ("gre_s", 1),
+            ("gre_reserved", 9),
+            ("gre_ver", 3),
+            ("gre_proto", 16),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in a GRE header.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl GreHeader {
+    /// Returns a `GreHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Skips the GRE header of a packet, including its optional checksum, key and sequence number.
+///
+/// Only version 0 is decapsulated, version 1 (PPTP) carries PPP frames.
+///
+/// # Arguments
+/// * `packet` - Raw bytes representing a GRE packet.
+///
+/// # Returns
+/// The protocol type and the encapsulated packet, or `None` if the header is invalid.
+pub(crate) fn decapsulate(packet: &[u8]) -> Option<(EtherType, &[u8])> {
+    let flags = u16::from_be_bytes([*packet.first()?, *packet.get(1)?]);
+    let protocol = u16::from_be_bytes([*packet.get(2)?, *packet.get(3)?]);
+    if flags & GRE_VERSION != 0 {
+        return None;
+    }
+    let mut len = 4;
+    for flag in [GRE_CHECKSUM, GRE_KEY, GRE_SEQUENCE] {
+        if flags & flag != 0 {
+            len += 4;
+        }
+    }
+    Some((EtherType(protocol), packet.get(len..)?))
+}
+
+#[cfg(test)]
+mod gre_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    use pnet::packet::ethernet::EtherTypes;
+
+    const PACKET: [u8; 12] = [
+        0x20, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x2a, 0x45, 0x00, 0x00, 0x14,
+    ];
+
+    #[test]
+    fn test_gre_header_creation() {
+        let gre_header = GreHeader::new(&PACKET);
+        let data = gre_header.get_data();
+        assert_eq!(data.len(), 32, "Expected 32 bits in GreHeader data.");
+        assert_eq!(data[2], 1., "Expected key present bit.");
+        assert_eq!(
+            data[16..32],
+            [0., 0., 0., 0., 1., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0.],
+            "Protocol type doesn't match expected."
+        );
+    }
+
+    #[test]
+    fn test_gre_header_get_headers() {
+        let headers = GreHeader::get_headers();
+        assert_eq!(headers.len(), 32, "Expected 32 header names.");
+        assert_eq!(headers[0], "gre_c_0");
+        assert_eq!(headers[2], "gre_k_0");
+        assert_eq!(headers[4], "gre_reserved_0");
+        assert_eq!(headers[13], "gre_ver_0");
+        assert_eq!(headers[31], "gre_proto_15");
+    }
+
+    #[test]
+    fn test_gre_header_bad_header() {
+        let gre_header = GreHeader::new(&PACKET[..2]);
+        assert!(
+            gre_header.bit_eq(&GreHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            GreHeader::new_with_config(&PACKET[..2], &config).bit_eq(&GreHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+
+    #[test]
+    fn test_gre_decapsulate() {
+        let (ethertype, inner) = decapsulate(&PACKET).unwrap();
+        assert_eq!(ethertype, EtherTypes::Ipv4);
+        assert_eq!(inner, &PACKET[8..], "Expected the key to be skipped.");
+        assert_eq!(decapsulate(&[0x80, 0x00, 0x08, 0x00]), None);
+        assert_eq!(decapsulate(&[0x00, 0x01, 0x88, 0x0b]), None);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, remove, BitWriter};
+use crate::ProtocolType;
+use pnet::packet::ethernet::{EtherType, EtherTypes};
+
+/// Registered UDP port of GTP-U.
+const GTP_U_PORT: u16 = 2152;
+/// Length of the mandatory GTP-U header.
+const GTP_HEADER_LEN: usize = 8;
+/// Extension header, sequence number and N-PDU number present bits, any adding 4 bytes.
+const GTP_OPTIONAL: u8 = 0x07;
+/// Extension header present bit.
+const GTP_EXTENSION: u8 = 0x04;
+/// Message type of a G-PDU, carrying a user packet.
+const GTP_G_PDU: u8 = 0xff;
+
+/// Implementation of GTP-U header, only its mandatory part: flags, message type, length and TEID.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct GtpHeader {
+    /// A flat vector of parsed bit values, size of 64 bits as it's the mandatory GTP-U header length
+    data: Vec<f32>,
+}
+
+impl Default for GtpHeader {
+    /// Returns a `GtpHeader` filled with 64 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for GtpHeader {
+    /// Number of bits extracted, the 8 mandatory bytes of the GTP-U header.
+    const WIDTH: usize = 64;
+
+    /// Constructs a `GtpHeader` from a raw bytes GTP-U packet.
+    ///
+    /// If the input holds at least the 8 mandatory bytes, they are parsed bit by bit.
+    /// Returns `None` if the packet is too short.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing a GTP-U packet, following its UDP header.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let header = packet.get(..GTP_HEADER_LEN)?;
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(header, config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> GtpHeader {
+        GtpHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Gtp`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Gtp
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `gtp_version_0`, `gtp_version_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("gtp_version", 3),
+            ("gtp_pt", 1),
+            ("gtp_reserved", 1),
+            ("gtp_e", 1),
+            ("gtp_s", 1),
+            ("gtp_pn", 1),
+            ("gtp_type", 8),
+            ("gtp_length", 16),
+            ("gtp_teid", 32),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Removes the tunnel endpoint identifier, tied to a subscriber session.
+    fn anonymize_bits(data: &mut [f32]) {
+        remove(data, 32, 63); // TEID
+    }
+}
+
+impl GtpHeader {
+    /// Returns a `GtpHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Returns whether a UDP datagram carries GTP-U, based on its ports.
+///
+/// # Arguments
+/// * `src` - Source port of the datagram.
+/// * `dst` - Destination port of the datagram.
+pub(crate) fn is_gtp_u(src: u16, dst: u16) -> bool {
+    src == GTP_U_PORT || dst == GTP_U_PORT
+}
+
+/// Skips the GTP-U header of a G-PDU, including its optional fields and extension headers.
+///
+/// Only version 1 G-PDUs are decapsulated, the other messages (echo, end marker, ...) don't
+/// carry a user packet.
+///
+/// # Arguments
+/// * `packet` - Raw bytes representing a GTP-U packet, following its UDP header.
+///
+/// # Returns
+/// The ethertype of the user packet, from its IP version, and its bytes, or `None` if the
+/// packet isn't a valid G-PDU.
+pub(crate) fn decapsulate(packet: &[u8]) -> Option<(EtherType, &[u8])> {
+    let flags = *packet.first()?;
+    if flags >> 5 != 1 || flags & 0x10 == 0 || *packet.get(1)? != GTP_G_PDU {
+        return None;
+    }
+    let length = u16::from_be_bytes([*packet.get(2)?, *packet.get(3)?]) as usize;
+    let mut len = GTP_HEADER_LEN;
+    if flags & GTP_OPTIONAL != 0 {
+        len += 4;
+        // Each extension header announces its length in 4-byte units, the last byte
+        // holding the type of the next one
+        let mut next = if flags & GTP_EXTENSION != 0 {
+            *packet.get(len - 1)?
+        } else {
+            0
+        };
+        while next != 0 {
+            let extension_len = *packet.get(len)? as usize * 4;
+            if extension_len == 0 {
+                return None;
+            }
+            len += extension_len;
+            next = *packet.get(len - 1)?;
+        }
+    }
+    let end = packet.len().min(GTP_HEADER_LEN + length);
+    let inner = packet.get(len..end)?;
+    let ethertype = match inner.first()? >> 4 {
+        4 => EtherTypes::Ipv4,
+        6 => EtherTypes::Ipv6,
+        _ => return None,
+    };
+    Some((ethertype, inner))
+}
+
+#[cfg(test)]
+mod gtp_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    const PACKET: [u8; 12] = [
+        0x30, 0xff, 0x00, 0x04, 0x12, 0x34, 0x56, 0x78, 0x45, 0x00, 0x00, 0x14,
+    ];
+
+    #[test]
+    fn test_gtp_header_creation() {
+        let gtp_header = GtpHeader::new(&PACKET);
+        let data = gtp_header.get_data();
+        assert_eq!(data.len(), 64, "Expected 64 bits in GtpHeader data.");
+        assert_eq!(data[..4], [0., 0., 1., 1.], "Expected version 1 of GTP.");
+        assert!(
+            data[8..16].iter().all(|bit| *bit == 1.),
+            "Expected a G-PDU."
+        );
+        assert_eq!(
+            data[32..40],
+            [0., 0., 0., 1., 0., 0., 1., 0.],
+            "TEID doesn't match expected."
+        );
+    }
+
+    #[test]
+    fn test_gtp_header_get_headers() {
+        let headers = GtpHeader::get_headers();
+        assert_eq!(headers.len(), 64, "Expected 64 header names.");
+        assert_eq!(headers[0], "gtp_version_0");
+        assert_eq!(headers[5], "gtp_e_0");
+        assert_eq!(headers[8], "gtp_type_0");
+        assert_eq!(headers[16], "gtp_length_0");
+        assert_eq!(headers[63], "gtp_teid_31");
+    }
+
+    #[test]
+    fn test_gtp_header_bad_header() {
+        let gtp_header = GtpHeader::new(&PACKET[..6]);
+        assert!(
+            gtp_header.bit_eq(&GtpHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            GtpHeader::new_with_config(&PACKET[..6], &config).bit_eq(&GtpHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+
+    #[test]
+    fn test_gtp_header_anonymize() {
+        let mut gtp_header = GtpHeader::new(&PACKET);
+        gtp_header.anonymize();
+        assert!(gtp_header.get_data()[32..].iter().all(|bit| *bit == 0.));
+        assert_eq!(
+            gtp_header.get_data()[..32],
+            GtpHeader::new(&PACKET).get_data()[..32]
+        );
+    }
+
+    #[test]
+    fn test_gtp_decapsulate() {
+        let (ethertype, inner) = decapsulate(&PACKET).unwrap();
+        assert_eq!(ethertype, EtherTypes::Ipv4);
+        assert_eq!(inner, &PACKET[8..]);
+        // Echo request
+        assert_eq!(
+            decapsulate(&[0x32, 0x01, 0x00, 0x04, 0, 0, 0, 0, 0, 0, 0, 0]),
+            None
+        );
+        // GTP' (protocol type 0)
+        assert_eq!(
+            decapsulate(&[0x20, 0xff, 0x00, 0x04, 0, 0, 0, 0, 0x45]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_gtp_decapsulate_extension_headers() {
+        // Sequence number and a PDU session container extension header of 4 bytes
+        let packet = [
+            0x34, 0xff, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x85, 0x01, 0x00,
+            0x09, 0x00, 0x60, 0x00, 0x00, 0x00,
+        ];
+        let (ethertype, inner) = decapsulate(&packet).unwrap();
+        assert_eq!(ethertype, EtherTypes::Ipv6);
+        assert_eq!(inner, &packet[16..]);
+        let mut looping = packet;
+        looping[12] = 0;
+        assert_eq!(decapsulate(&looping), None);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+
+/// Length in bytes of the parsed part of an ICMPv6 message.
+const ICMPV6_HEADER_LEN: usize = 8;
+
+/// Implementation of ICMPv6 header.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Icmpv6Header {
+    /// A flat vector of parsed bit values, size of 64 bits as it's the ICMPv6 header length with its first message word
+    data: Vec<f32>,
+}
+
+impl Default for Icmpv6Header {
+    /// Returns an `Icmpv6Header` filled with 64 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for Icmpv6Header {
+    /// Number of bits extracted, the first 8 bytes of the ICMPv6 message.
+    const WIDTH: usize = 64;
+
+    /// Constructs an `Icmpv6Header` from a raw bytes ICMPv6 message.
+    ///
+    /// If the input holds at least 8 bytes, the type, code, checksum and the first 32 bits
+    /// of the message body are parsed bit by bit.
+    /// Returns `None` if the message is too short.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing an ICMPv6 message.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        if packet.len() < ICMPV6_HEADER_LEN {
+            return None;
+        }
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(&packet[..8], config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> Icmpv6Header {
+        Icmpv6Header::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Icmpv6`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Icmpv6
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `icmpv6_type_0`, `icmpv6_type_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("icmpv6_type", 8),
+            ("icmpv6_code", 8),
+            ("icmpv6_cksum", 16),
+            ("icmpv6_body", 32),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in an ICMPv6 header.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl Icmpv6Header {
+    /// Returns an `Icmpv6Header` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+#[cfg(test)]
+mod icmpv6_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    // Router advertisement: type 134, code 0, hop limit 64, flags 0, lifetime 1800
+    const PACKET: [u8; 16] = [
+        0x86, 0x00, 0x12, 0x34, 0x40, 0x00, 0x07, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00,
+    ];
+
+    #[test]
+    fn test_icmpv6_header_creation() {
+        let icmpv6_header = Icmpv6Header::new(&PACKET);
+        let data = icmpv6_header.get_data();
+        assert_eq!(data.len(), 64, "Expected 64 bits in Icmpv6Header data.");
+        assert_eq!(
+            data[..8],
+            [1., 0., 0., 0., 0., 1., 1., 0.],
+            "Type doesn't match expected."
+        );
+        assert_eq!(
+            data[32..40],
+            [0., 1., 0., 0., 0., 0., 0., 0.],
+            "Message body doesn't match expected."
+        );
+    }
+
+    #[test]
+    fn test_icmpv6_header_get_headers() {
+        let headers = Icmpv6Header::get_headers();
+        assert_eq!(headers.len(), 64, "Expected 64 header names.");
+        assert_eq!(headers[0], "icmpv6_type_0");
+        assert_eq!(headers[7], "icmpv6_type_7");
+        assert_eq!(headers[8], "icmpv6_code_0");
+        assert_eq!(headers[16], "icmpv6_cksum_0");
+        assert_eq!(headers[63], "icmpv6_body_31");
+    }
+
+    #[test]
+    fn test_icmpv6_header_bad_header() {
+        let icmpv6_header = Icmpv6Header::new(&PACKET[..4]);
+        assert!(
+            icmpv6_header.bit_eq(&Icmpv6Header::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            Icmpv6Header::new_with_config(&PACKET[..4], &config)
+                .bit_eq(&Icmpv6Header::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+}
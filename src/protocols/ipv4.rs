@@ -1,61 +1,118 @@
+use crate::config::{BitOrder, NprintConfig};
+use crate::protocols::cryptopan::CryptoPan;
 use crate::protocols::packet::PacketHeader;
-use pnet::packet::ipv4::Ipv4Packet;
+use crate::protocols::util::{
+    bits_to_bytes, bits_to_value, bytes_to_bits, field_range, options_to_bits, remove,
+    value_to_bits, BitWriter, DEFAULT_OPTIONS_BITS,
+};
+use crate::ProtocolType;
+use pnet::packet::ipv4::{checksum, Ipv4Packet};
 use pnet::packet::Packet;
+use std::ops::Range;
+
+/// Fixed fields of the IPv4 header and their number of bits, followed by the options.
+const FIELDS: [(&str, usize); 15] = [
+    ("ipv4_ver", 4),
+    ("ipv4_hl", 4),
+    ("ipv4_dscp", 6),
+    ("ipv4_ecn", 2),
+    ("ipv4_tl", 16),
+    ("ipv4_id", 16),
+    ("ipv4_rbit", 1),
+    ("ipv4_dfbit", 1),
+    ("ipv4_mfbit", 1),
+    ("ipv4_foff", 13),
+    ("ipv4_ttl", 8),
+    ("ipv4_proto", 8),
+    ("ipv4_cksum", 16),
+    ("ipv4_src", 32),
+    ("ipv4_dst", 32),
+];
+
+/// Bit range of the identification in the IPv4 data.
+pub(crate) const IPV4_ID: Range<usize> = field_range(&FIELDS, "ipv4_id");
+/// Bit range of the time to live in the IPv4 data.
+pub(crate) const IPV4_TTL: Range<usize> = field_range(&FIELDS, "ipv4_ttl");
+/// Bit range of the header checksum in the IPv4 data.
+pub(crate) const IPV4_CHECKSUM: Range<usize> = field_range(&FIELDS, "ipv4_cksum");
+/// Bit range of the source address in the IPv4 data.
+pub(crate) const IPV4_SOURCE: Range<usize> = field_range(&FIELDS, "ipv4_src");
+/// Bit range of the destination address in the IPv4 data.
+pub(crate) const IPV4_DESTINATION: Range<usize> = field_range(&FIELDS, "ipv4_dst");
+/// Bit range of the source and destination addresses in the IPv4 data.
+pub(crate) const IPV4_ADDRESSES: Range<usize> = IPV4_SOURCE.start..IPV4_DESTINATION.end;
 
 /// Implementation of IPv4 header.
 ///
 #[derive(Clone, PartialEq, Debug)]
 pub(crate) struct Ipv4Header {
-    /// A flat vector of parsed bit values, size up to 480 bits as it's the max IPv4 header length
-    data: Vec<f32>, // 480 = IHL max size
+    /// A flat vector of parsed bit values: the 160 bits of the fixed fields followed by the
+    /// options, padded or truncated to `NprintConfig::ipv4_options_width()` bits, named by
+    /// `headers_with_config()`
+    data: Vec<f32>,
 }
 
 impl Default for Ipv4Header {
-    /// Returns an `Ipv4Header` filled with 480 "-1"
+    /// Returns an `Ipv4Header` of the default width filled with "-1"
     fn default() -> Self {
-        Self {
-            data: vec![-1.; 480],
-        }
+        Self::with_fill(-1.)
     }
 }
 
 impl PacketHeader for Ipv4Header {
+    /// Number of bits extracted by default, the fixed fields followed by the padded options.
+    const WIDTH: usize = Self::FIXED_WIDTH + DEFAULT_OPTIONS_BITS;
+
     /// Constructs an `Ipv4Header` from a raw bytes IPv4 packet.
     ///
     /// If the input is a valid IPv4 packet, its fields are parsed bit by bit.
-    /// If the packet is invalid or cannot be parsed, return Default.
+    /// Returns `None` if the packet is invalid or cannot be parsed, in particular if the
+    /// IHL is below 5 or announces more option bytes than the buffer holds.
     ///
     /// # Arguments
     /// * `packet` - Raw bytes representing an IPv4 packet.
-    fn new(packet: &[u8]) -> Ipv4Header {
-        if let Some(packet) = Ipv4Packet::new(packet) {
-            let option = packet.get_options_raw();
-            let mut data = Vec::with_capacity(480);
-            let packet = packet.packet();
-            data.extend((0..4).rev().map(|i| ((packet[0] >> (4 + i)) & 1) as f32));
-            data.extend((0..4).rev().map(|i| ((packet[0] >> i) & 1) as f32));
-            data.extend((0..6).rev().map(|i| ((packet[1] >> (2 + i)) & 1) as f32));
-            data.extend((0..2).rev().map(|i| ((packet[1] >> i) & 1) as f32));
-            data.extend((0..16).map(|i| ((packet[2 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..16).map(|i| ((packet[4 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..3).rev().map(|i| ((packet[6] >> (5 + i)) & 1) as f32));
-            data.extend((0..13).map(|i| {
-                if i < 5 {
-                    ((packet[6] >> (4 - i)) & 1) as f32
-                } else {
-                    ((packet[7] >> (7 - (i - 5))) & 1) as f32
-                }
-            }));
-            data.extend((0..8).rev().map(|i| ((packet[8] >> i) & 1) as f32));
-            data.extend((0..8).rev().map(|i| ((packet[9] >> i) & 1) as f32));
-            data.extend((0..16).map(|i| ((packet[10 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..32).map(|i| ((packet[12 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..32).map(|i| ((packet[16 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend(get_options_bits(option));
-            Ipv4Header { data }
-        } else {
-            eprintln!("Not an IPv4 packet, returnin default...");
-            Ipv4Header::default()
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let packet = Ipv4Packet::new(packet)?;
+        let options_len = (packet.get_header_length() as usize * 4).checked_sub(20)?;
+        let option = packet.get_options_raw();
+        if option.len() < options_len {
+            return None;
+        }
+        let mut data = BitWriter::new(out);
+        let packet = packet.packet();
+        data.extend(bytes_to_bits(&packet[..1], config.bit_order)); // Version and IHL
+        data.extend(bytes_to_bits(&packet[1..2], config.bit_order)); // DSCP and ECN
+        data.extend(bytes_to_bits(&packet[2..4], config.bit_order));
+        data.extend(bytes_to_bits(&packet[4..6], config.bit_order));
+        data.extend(bytes_to_bits(&packet[6..8], config.bit_order)); // Flags and fragment offset
+        data.extend(bytes_to_bits(&packet[8..9], config.bit_order));
+        data.extend(bytes_to_bits(&packet[9..10], config.bit_order));
+        data.extend(bytes_to_bits(&packet[10..12], config.bit_order));
+        data.extend(bytes_to_bits(&packet[12..16], config.bit_order));
+        data.extend(bytes_to_bits(&packet[16..20], config.bit_order));
+        if config.options_indicator {
+            data.extend([f32::from(u8::from(options_len > 0))]);
+        }
+        data.extend(options_to_bits(
+            option,
+            config.ipv4_options_width(),
+            config.fill_value.value(),
+            config.bit_order,
+        ));
+        debug_assert!(
+            data.is_full(),
+            "IPv4 data must be exactly {} bits",
+            Self::width(config)
+        );
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> Ipv4Header {
+        Ipv4Header {
+            data: vec![config.fill_value.value(); Ipv4Header::width(config)],
         }
     }
 
@@ -64,71 +121,227 @@ impl PacketHeader for Ipv4Header {
         &self.data
     }
 
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Ipv4`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Ipv4
+    }
+
     /// Returns the list of all field names of the protocols.
     ///
     /// Header names are suffixed with an index (e.g., `ipv4_ver_0`, `ipv4_ver_1`).
     fn get_headers() -> Vec<String> {
-        let fields = vec![
-            ("ipv4_ver", 4),
-            ("ipv4_hl", 4),
-            ("ipv4_tos", 8),
-            ("ipv4_tl", 16),
-            ("ipv4_id", 16),
-            ("ipv4_rbit", 1),
-            ("ipv4_dfbit", 1),
-            ("ipv4_mfbit", 1),
-            ("ipv4_foff", 13),
-            ("ipv4_ttl", 8),
-            ("ipv4_proto", 8),
-            ("ipv4_cksum", 16),
-            ("ipv4_src", 32),
-            ("ipv4_dst", 32),
-            ("ipv4_opt", 320),
-        ];
-        fields
-            .iter()
-            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
-            .collect()
+        Self::headers(DEFAULT_OPTIONS_BITS, false)
+    }
+
+    /// Returns the list of all field names, with `ipv4_has_opt_0` if extracted and the
+    /// configured number of options bits.
+    fn headers_with_config(config: &NprintConfig) -> Vec<String> {
+        Self::headers(config.ipv4_options_width(), config.options_indicator)
     }
 
     /// Remove IPs to anonymized header.
-    fn anonymize(&mut self) {
-        self.remove(96, 127); // IP Source
-        self.remove(128, 159); // IP Destination
+    fn anonymize_bits(data: &mut [f32]) {
+        remove(data, IPV4_SOURCE.start, IPV4_SOURCE.end - 1);
+        remove(data, IPV4_DESTINATION.start, IPV4_DESTINATION.end - 1);
+    }
+
+    /// Reassembles the IPv4 header, options included up to the header length.
+    ///
+    /// The options must directly follow the fixed fields, without `ipv4_has_opt_0`.
+    /// Returns `None` if the extracted options are shorter than the header length.
+    fn reassemble(data: &[f32], order: BitOrder) -> Option<Vec<u8>> {
+        let first = bits_to_value(&data[..8], order)?;
+        let header_len = ((first & 0x0f) as usize * 4).max(20);
+        bits_to_bytes(data.get(..header_len * 8)?, order)
+    }
+
+    /// Replace IPs with prefix-preserving pseudonyms, absent IPs are kept as is.
+    fn anonymize_bits_with_key(data: &mut [f32], key: &[u8; 16], order: BitOrder) {
+        let cryptopan = CryptoPan::new(key);
+        for range in [IPV4_SOURCE, IPV4_DESTINATION] {
+            if let Some(address) = bits_to_value(&data[range.clone()], order) {
+                value_to_bits(
+                    &mut data[range],
+                    cryptopan.anonymize(address as u32).into(),
+                    order,
+                );
+            }
+        }
     }
 }
 
 impl Ipv4Header {
-    /// Remove a given range.
+    /// Number of bits of the fixed fields, preceding the options.
+    pub const FIXED_WIDTH: usize = IPV4_DESTINATION.end;
+
+    /// Returns the number of bits extracted following the given configuration, the fixed
+    /// fields followed by `ipv4_has_opt_0` if extracted and the configured options.
     ///
     /// # Arguments
-    /// * `start` - Starting bit index (inclusive).
-    /// * `end` - Ending bit index (inclusive).
-    pub fn remove(&mut self, start: usize, end: usize) {
-        self.data[start..=end].fill(0.);
+    /// * `config` - Configuration of the parsing, setting in particular the options width.
+    pub fn width(config: &NprintConfig) -> usize {
+        Self::options_range(config).end
+    }
+
+    /// Returns the bit range of the options in the data extracted following the given
+    /// configuration, after the fixed fields and `ipv4_has_opt_0`.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration of the parsing, setting in particular the options width.
+    pub fn options_range(config: &NprintConfig) -> Range<usize> {
+        let start = Self::FIXED_WIDTH + usize::from(config.options_indicator);
+        start..start + config.ipv4_options_width()
+    }
+
+    /// Returns the time to live decoded from the bits of an IPv4 header.
+    ///
+    /// # Arguments
+    /// * `data` - Bits of the header.
+    /// * `order` - Order of the bits of every byte.
+    ///
+    /// # Returns
+    /// The time to live, or `None` if its bits hold anything but 0 and 1, e.g. the fill value.
+    pub fn ttl(data: &[f32], order: BitOrder) -> Option<u8> {
+        bits_to_value(data.get(IPV4_TTL)?, order).map(|value| value as u8)
+    }
+
+    /// Returns the list of all field names, see `get_headers()`.
+    ///
+    /// # Arguments
+    /// * `options_bits` - Number of `ipv4_opt` bits.
+    /// * `has_opt` - `true` to list `ipv4_has_opt_0` before the options.
+    pub fn headers(options_bits: usize, has_opt: bool) -> Vec<String> {
+        FIELDS
+            .into_iter()
+            .chain([
+                ("ipv4_has_opt", usize::from(has_opt)),
+                ("ipv4_opt", options_bits),
+            ])
+            .flat_map(|(name, bits)| (0..bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Returns an `Ipv4Header` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
     }
 }
 
-/// Converts raw options bytes into a bit vector of 320 `f32`.
-///
-/// Fill with `-1.0` all the fields not present.
+/// Returns `true` if the header checksum of an IPv4 packet is valid.
 ///
 /// # Arguments
-/// * `options` - Slice of bits from the option field of an IPv4 header.
-fn get_options_bits(options: &[u8]) -> Vec<f32> {
-    let mut data = Vec::new();
-    for option in options {
-        data.extend((0..8).rev().map(|i| ((option >> i) & 1) as f32));
-    }
-    while data.len() < 320 {
-        data.push(-1.);
-    }
-    data
+/// * `packet` - IPv4 packet.
+pub(crate) fn ipv4_checksum_valid(packet: &Ipv4Packet) -> bool {
+    checksum(packet) == packet.get_checksum()
 }
 
 #[cfg(test)]
 mod ipv4_header_tests {
     use super::*;
+    use crate::config::FillValue;
+
+    #[test]
+    fn test_ipv4_fragment_offset() {
+        let config = NprintConfig::default();
+        let decode =
+            |data: &[f32], name| bits_to_value(&data[field_range(&FIELDS, name)], BitOrder::Msb0);
+        // Fragment offset, then the reserved, DF and MF flags
+        for (offset, flags) in [
+            (0, 0b010),
+            (1, 0b001),
+            (8191, 0b001),
+            (0x100, 0b000),
+            (0x1000, 0b100),
+        ] {
+            let word: u16 = (flags << 13) | offset;
+            let mut packet = [
+                0x45, 0x00, 0x00, 0x14, 0x00, 0x01, 0x00, 0x00, 0x40, 0x06, 0x00, 0x00, 0x0a, 0x00,
+                0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+            ];
+            packet[6..8].copy_from_slice(&word.to_be_bytes());
+            let mut data = vec![0.; Ipv4Header::width(&config)];
+            assert!(Ipv4Header::parse_into(&packet, &config, &mut data).is_some());
+
+            let foff = field_range(&FIELDS, "ipv4_foff");
+            assert_eq!(foff.len(), 13);
+            assert_eq!(
+                decode(&data, "ipv4_foff"),
+                Some(u64::from(offset)),
+                "Wrong offset {}",
+                offset
+            );
+            let expected: Vec<f32> = (0..13)
+                .rev()
+                .map(|i| f32::from((offset >> i) & 1))
+                .collect();
+            assert_eq!(data[foff], expected, "Wrong bits of offset {}", offset);
+            assert_eq!(decode(&data, "ipv4_rbit"), Some(u64::from(flags >> 2)));
+            assert_eq!(
+                decode(&data, "ipv4_dfbit"),
+                Some(u64::from((flags >> 1) & 1))
+            );
+            assert_eq!(decode(&data, "ipv4_mfbit"), Some(u64::from(flags & 1)));
+            // The neighbouring fields are left untouched
+            assert_eq!(decode(&data, "ipv4_id"), Some(1));
+            assert_eq!(decode(&data, "ipv4_ttl"), Some(64));
+        }
+    }
+
+    #[test]
+    fn test_ipv4_ttl() {
+        let packet = [
+            0x45, 0x00, 0x00, 0x14, 0x00, 0x01, 0x00, 0x00, 0x40, 0x06, 0x00, 0x00, 0x0a, 0x00,
+            0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+        ];
+        let config = NprintConfig::default();
+        let mut data = vec![0.; Ipv4Header::width(&config)];
+        assert!(Ipv4Header::parse_into(&packet, &config, &mut data).is_some());
+        assert_eq!(Ipv4Header::ttl(&data, BitOrder::Msb0), Some(64));
+        assert_eq!(
+            Ipv4Header::ttl(Ipv4Header::default().get_data(), BitOrder::Msb0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ipv4_field_offsets() {
+        let headers = Ipv4Header::get_headers();
+        let offset = |name: &str| headers.iter().position(|header| header == name);
+        assert_eq!(offset("ipv4_src_0"), Some(96));
+        assert_eq!(offset("ipv4_dst_0"), Some(128));
+        assert_eq!(IPV4_SOURCE, 96..128);
+        assert_eq!(IPV4_DESTINATION, 128..160);
+        assert_eq!(offset("ipv4_id_0"), Some(IPV4_ID.start));
+        assert_eq!(offset("ipv4_cksum_0"), Some(IPV4_CHECKSUM.start));
+        assert_eq!(offset("ipv4_opt_0"), Some(Ipv4Header::FIXED_WIDTH));
+    }
+
+    #[test]
+    fn test_ipv4_checksum_valid() {
+        let mut raw_packet: Vec<u8> = vec![
+            0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8,
+            0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b,
+            0x00, 0x00, 0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04,
+            0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x03, 0x03, 0x07,
+        ];
+        assert!(ipv4_checksum_valid(&Ipv4Packet::new(&raw_packet).unwrap()));
+        raw_packet[11] ^= 0x01;
+        assert!(
+            !ipv4_checksum_valid(&Ipv4Packet::new(&raw_packet).unwrap()),
+            "Expected the corrupted checksum to be invalid"
+        );
+    }
 
     #[test]
     fn test_ipv4_header_creation() {
@@ -192,14 +405,14 @@ mod ipv4_header_tests {
             "ipv4_hl_1",
             "ipv4_hl_2",
             "ipv4_hl_3",
-            "ipv4_tos_0",
-            "ipv4_tos_1",
-            "ipv4_tos_2",
-            "ipv4_tos_3",
-            "ipv4_tos_4",
-            "ipv4_tos_5",
-            "ipv4_tos_6",
-            "ipv4_tos_7",
+            "ipv4_dscp_0",
+            "ipv4_dscp_1",
+            "ipv4_dscp_2",
+            "ipv4_dscp_3",
+            "ipv4_dscp_4",
+            "ipv4_dscp_5",
+            "ipv4_ecn_0",
+            "ipv4_ecn_1",
             "ipv4_tl_0",
             "ipv4_tl_1",
             "ipv4_tl_2",
@@ -679,6 +892,8 @@ mod ipv4_header_tests {
                 i
             );
         }
+        assert_eq!(headers.len(), Ipv4Header::WIDTH);
+        assert_eq!(Ipv4Header::default().get_data().len(), Ipv4Header::WIDTH);
     }
     #[test]
     fn test_ipv4_header_options() {
@@ -728,11 +943,18 @@ mod ipv4_header_tests {
     fn test_ipv4_header_bad_header() {
         let raw_packet: Vec<u8> = vec![0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b];
         let ipv4_header = Ipv4Header::new(&raw_packet);
-        assert_eq!(
-            ipv4_header,
-            Ipv4Header::default(),
+        assert!(
+            ipv4_header.bit_eq(&Ipv4Header::default()),
             "Expected data to be default."
         );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            Ipv4Header::new_with_config(&raw_packet, &config).bit_eq(&Ipv4Header::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
     }
 
     #[test]
@@ -751,4 +973,109 @@ mod ipv4_header_tests {
             assert_eq!(*ip_bit, 0., "Expected data bit 96-160 to be 0.");
         }
     }
+
+    #[test]
+    fn test_ipv4_header_anonymize_with_key() {
+        let raw_packet: Vec<u8> = vec![
+            0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8,
+            0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88,
+        ];
+        let mut ipv4_header = Ipv4Header::new(&raw_packet);
+        let original = ipv4_header.clone();
+        ipv4_header.anonymize_with_key(&[7; 16], BitOrder::Msb0);
+        let anon = ipv4_header.get_data();
+        assert_eq!(
+            anon[..96],
+            original.get_data()[..96],
+            "Expected fields to be kept."
+        );
+        assert_ne!(
+            anon[96..160],
+            original.get_data()[96..160],
+            "Expected IPs to change."
+        );
+        assert!(anon[96..160].iter().all(|bit| *bit == 0. || *bit == 1.));
+
+        let mut again = original.clone();
+        again.anonymize_with_key(&[7; 16], BitOrder::Msb0);
+        assert_eq!(
+            *again.get_data(),
+            *anon,
+            "Expected a deterministic pseudonym."
+        );
+
+        let mut absent = Ipv4Header::default();
+        absent.anonymize_with_key(&[7; 16], BitOrder::Msb0);
+        assert!(
+            absent.bit_eq(&Ipv4Header::default()),
+            "Expected absent IPs to be kept."
+        );
+    }
+
+    #[test]
+    fn test_ipv4_header_to_bytes() {
+        let raw_packet: Vec<u8> = vec![
+            0x4b, 0x0, 0x0, 0x6c, 0x78, 0x37, 0x0, 0x0, 0x40, 0x1, 0x75, 0x2d, 0x7f, 0x0, 0x0, 0x1,
+            0x7f, 0x0, 0x0, 0x1, 0x86, 0x16, 0x0, 0x0, 0x0, 0x2, 0x2, 0x10, 0x0, 0x2, 0x0, 0x0,
+            0x0, 0x2, 0x0, 0x4, 0x0, 0x5, 0x0, 0x6, 0x0, 0xef, 0x0, 0x0,
+        ];
+        let mut ipv4_header = Ipv4Header::new(&raw_packet);
+        assert_eq!(
+            ipv4_header.to_bytes(BitOrder::Msb0),
+            Some(raw_packet.clone()),
+            "Expected the header and its options back."
+        );
+        ipv4_header.anonymize();
+        let mut anonymized = raw_packet.clone();
+        anonymized[12..20].fill(0);
+        assert_eq!(ipv4_header.to_bytes(BitOrder::Msb0), Some(anonymized));
+        assert_eq!(Ipv4Header::default().to_bytes(BitOrder::Msb0), None);
+    }
+
+    #[test]
+    fn test_ipv4_header_truncated_options() {
+        let raw_packet: Vec<u8> = vec![
+            0x46, 0x00, 0x00, 0x18, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8,
+            0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x01, 0x01,
+        ];
+        assert!(
+            Ipv4Header::new(&raw_packet).bit_eq(&Ipv4Header::default()),
+            "Expected data to be default with IHL=6 and 2 option bytes."
+        );
+        let mut complete = raw_packet.clone();
+        complete.extend([0x01, 0x00]);
+        assert!(!Ipv4Header::new(&complete).bit_eq(&Ipv4Header::default()));
+        let mut short_ihl = complete.clone();
+        short_ihl[0] = 0x44;
+        assert!(Ipv4Header::new(&short_ihl).bit_eq(&Ipv4Header::default()));
+    }
+
+    #[test]
+    fn test_ipv4_header_options_length() {
+        let fixed: Vec<u8> = vec![
+            0x45, 0x00, 0x00, 0x14, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8,
+            0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88,
+        ];
+        let no_options = Ipv4Header::new(&fixed);
+        assert_eq!(no_options.get_data().len(), Ipv4Header::WIDTH);
+        assert!(no_options.get_data()[..160].iter().all(|bit| *bit != -1.));
+        assert!(no_options.get_data()[160..].iter().all(|bit| *bit == -1.));
+
+        let mut max_options = fixed.clone();
+        max_options[0] = 0x4f;
+        max_options.extend([0x01; (DEFAULT_OPTIONS_BITS / 8)]);
+        let header = Ipv4Header::new(&max_options);
+        assert_eq!(header.get_data().len(), Ipv4Header::WIDTH);
+        assert!(header.get_data().iter().all(|bit| *bit != -1.));
+
+        let mut trailing = max_options.clone();
+        trailing.extend([0xff; 8]);
+        let header = Ipv4Header::new(&trailing);
+        assert_eq!(header.get_data().len(), Ipv4Header::WIDTH);
+        assert_eq!(
+            header.to_bytes(BitOrder::Msb0),
+            Some(max_options),
+            "Expected the bytes beyond the IHL to be ignored."
+        );
+    }
 }
@@ -0,0 +1,92 @@
+//! IPv6 extension headers preceding the upper-layer header.
+use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
+
+/// Maximum number of IPv6 extension headers skipped before giving up on a packet.
+pub(crate) const MAX_EXTENSION_DEPTH: usize = 8;
+
+/// Skips the IPv6 extension headers preceding the upper-layer header.
+///
+/// Hop-by-Hop, Routing, Fragment, Destination Options and Mobility headers are walked
+/// using their length field. The walk stops on any other next header, AH included as it's
+/// extracted like an upper-layer protocol, on a non-first fragment, as its upper-layer
+/// header is carried by the first one, or after `MAX_EXTENSION_DEPTH` extensions.
+///
+/// # Arguments
+/// * `next_header` - Next header field of the IPv6 header.
+/// * `packet` - Raw bytes following the IPv6 header.
+///
+/// # Returns
+/// The last next header and the bytes it announces, or `None` if an extension is truncated.
+pub(crate) fn skip_extensions(
+    mut next_header: IpNextHeaderProtocol,
+    mut packet: &[u8],
+) -> Option<(IpNextHeaderProtocol, &[u8])> {
+    for _ in 0..MAX_EXTENSION_DEPTH {
+        let len = match next_header {
+            IpNextHeaderProtocols::Hopopt
+            | IpNextHeaderProtocols::Ipv6Route
+            | IpNextHeaderProtocols::Ipv6Opts
+            | IpNextHeaderProtocols::MobilityHeader => (*packet.get(1)? as usize + 1) * 8,
+            IpNextHeaderProtocols::Ipv6Frag => {
+                let offset = u16::from_be_bytes([*packet.get(2)?, *packet.get(3)?]) >> 3;
+                if offset != 0 {
+                    break;
+                }
+                8
+            }
+            _ => break,
+        };
+        next_header = IpNextHeaderProtocol(*packet.first()?);
+        packet = packet.get(len..)?;
+    }
+    Some((next_header, packet))
+}
+
+#[cfg(test)]
+mod ipv6_tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_extensions() {
+        let packet = [
+            0x2c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x2a, 0x00, 0x35,
+        ];
+        let (next_header, upper) = skip_extensions(IpNextHeaderProtocols::Hopopt, &packet).unwrap();
+        assert_eq!(next_header, IpNextHeaderProtocols::Udp);
+        assert_eq!(
+            upper,
+            [0x00, 0x35],
+            "Expected both extensions to be skipped"
+        );
+
+        let (next_header, upper) = skip_extensions(IpNextHeaderProtocols::Tcp, &packet).unwrap();
+        assert_eq!(next_header, IpNextHeaderProtocols::Tcp);
+        assert_eq!(upper, packet);
+    }
+
+    #[test]
+    fn test_skip_extensions_stops() {
+        let fragment = [0x11, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x35];
+        assert_eq!(
+            skip_extensions(IpNextHeaderProtocols::Ipv6Frag, &fragment),
+            Some((IpNextHeaderProtocols::Ipv6Frag, &fragment[..])),
+            "Expected to stop on a non-first fragment"
+        );
+        assert_eq!(
+            skip_extensions(IpNextHeaderProtocols::Hopopt, &[0x11, 0x01, 0x00, 0x00]),
+            None,
+            "Expected a truncated extension"
+        );
+
+        let looping = [0x00; 8 * (MAX_EXTENSION_DEPTH + 1)];
+        let (next_header, upper) =
+            skip_extensions(IpNextHeaderProtocols::Hopopt, &looping).unwrap();
+        assert_eq!(next_header, IpNextHeaderProtocols::Hopopt);
+        assert_eq!(
+            upper.len(),
+            8,
+            "Expected the walk to stop at the depth limit"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Link layers preceding the network layer of the parsed packets.
+use crate::config::LinkLayer;
+use pnet::packet::ethernet::{EtherType, EtherTypes, EthernetPacket};
+use pnet::packet::Packet;
+
+/// Length of a Linux cooked capture (SLL) header.
+pub(crate) const SLL_HEADER_LEN: usize = 16;
+/// Length of a Linux cooked capture v2 (SLL2) header.
+pub(crate) const SLL2_HEADER_LEN: usize = 20;
+/// Largest value of the Ethernet type field read as an IEEE 802.3 length.
+const MAX_8023_LENGTH: u16 = 1500;
+/// LLC header announcing a SNAP header: DSAP and SSAP 0xAA, unnumbered information.
+const LLC_SNAP: [u8; 3] = [0xaa, 0xaa, 0x03];
+/// Length of the LLC header followed by the SNAP organization code and ethertype.
+const LLC_SNAP_LEN: usize = 8;
+
+/// Returns the link layer of a packet, guessed from its first nibble for `LinkLayer::Auto`.
+///
+/// # Arguments
+/// * `packet` - Raw bytes of the packet.
+/// * `link_layer` - Configured link layer.
+pub(crate) fn resolve(packet: &[u8], link_layer: LinkLayer) -> LinkLayer {
+    match link_layer {
+        LinkLayer::Auto => match packet.first().map(|byte| byte >> 4) {
+            Some(4 | 6) => LinkLayer::RawIp,
+            _ => LinkLayer::Ethernet,
+        },
+        other => other,
+    }
+}
+
+/// Strips the link layer of a packet.
+///
+/// Ethernet frames whose type field is a length (IEEE 802.3) are decapsulated from their
+/// LLC/SNAP header, other 802.3 frames are returned with the length as ethertype.
+///
+/// # Arguments
+/// * `packet` - Raw bytes of the packet.
+/// * `link_layer` - Link layer of the packet, already resolved.
+///
+/// # Returns
+/// The ethertype of the network layer and its bytes, or `None` if the packet is too short.
+pub(crate) fn strip(packet: &[u8], link_layer: LinkLayer) -> Option<(EtherType, &[u8])> {
+    match link_layer {
+        LinkLayer::Ethernet | LinkLayer::Auto => {
+            let ethernet = EthernetPacket::new(packet)?;
+            let header_len = packet.len() - ethernet.payload().len();
+            let (ethertype, payload) = (ethernet.get_ethertype(), &packet[header_len..]);
+            if ethertype.0 <= MAX_8023_LENGTH {
+                if let Some(snap) = strip_snap(payload) {
+                    return Some(snap);
+                }
+            }
+            Some((ethertype, payload))
+        }
+        LinkLayer::RawIp => {
+            let ethertype = match packet.first()? >> 4 {
+                4 => EtherTypes::Ipv4,
+                6 => EtherTypes::Ipv6,
+                _ => EtherType(0),
+            };
+            Some((ethertype, packet))
+        }
+        LinkLayer::LinuxSll => {
+            let protocol = packet.get(SLL_HEADER_LEN - 2..SLL_HEADER_LEN)?;
+            let ethertype = EtherType(u16::from_be_bytes([protocol[0], protocol[1]]));
+            Some((ethertype, &packet[SLL_HEADER_LEN..]))
+        }
+        // SLL2 leads with the protocol type, followed by the interface index
+        LinkLayer::LinuxSll2 => {
+            let header = packet.get(..SLL2_HEADER_LEN)?;
+            let ethertype = EtherType(u16::from_be_bytes([header[0], header[1]]));
+            Some((ethertype, &packet[SLL2_HEADER_LEN..]))
+        }
+    }
+}
+
+/// Strips the LLC/SNAP header of an IEEE 802.3 frame.
+///
+/// # Arguments
+/// * `payload` - Bytes following the length field of the frame.
+///
+/// # Returns
+/// The ethertype carried by the SNAP header and its bytes, or `None` if the frame isn't
+/// SNAP-encapsulated.
+fn strip_snap(payload: &[u8]) -> Option<(EtherType, &[u8])> {
+    let header = payload.get(..LLC_SNAP_LEN)?;
+    if header[..LLC_SNAP.len()] != LLC_SNAP {
+        return None;
+    }
+    let ethertype = EtherType(u16::from_be_bytes([header[6], header[7]]));
+    Some((ethertype, &payload[LLC_SNAP_LEN..]))
+}
+
+#[cfg(test)]
+mod link_tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_auto() {
+        assert_eq!(resolve(&[0x45, 0x00], LinkLayer::Auto), LinkLayer::RawIp);
+        assert_eq!(resolve(&[0x60, 0x00], LinkLayer::Auto), LinkLayer::RawIp);
+        assert_eq!(resolve(&[0x00, 0x00], LinkLayer::Auto), LinkLayer::Ethernet);
+        assert_eq!(resolve(&[0x45], LinkLayer::LinuxSll), LinkLayer::LinuxSll);
+    }
+
+    #[test]
+    fn test_strip() {
+        let mut sll = vec![0x00; 16];
+        sll[14..16].copy_from_slice(&[0x08, 0x00]);
+        sll.push(0x45);
+        assert_eq!(
+            strip(&sll, LinkLayer::LinuxSll),
+            Some((EtherTypes::Ipv4, &[0x45][..]))
+        );
+        assert_eq!(strip(&sll[..10], LinkLayer::LinuxSll), None);
+        let mut sll2 = vec![0x86, 0xdd];
+        sll2.extend([0x00; 18]);
+        sll2.push(0x60);
+        assert_eq!(
+            strip(&sll2, LinkLayer::LinuxSll2),
+            Some((EtherTypes::Ipv6, &[0x60][..]))
+        );
+        assert_eq!(strip(&sll2[..16], LinkLayer::LinuxSll2), None);
+        assert_eq!(
+            strip(&[0x60, 0x00], LinkLayer::RawIp),
+            Some((EtherTypes::Ipv6, &[0x60, 0x00][..]))
+        );
+        assert_eq!(
+            strip(&[0x00], LinkLayer::RawIp),
+            Some((EtherType(0), &[0x00][..]))
+        );
+        assert_eq!(strip(&[], LinkLayer::RawIp), None);
+        assert_eq!(strip(&[0x00], LinkLayer::Ethernet), None);
+    }
+
+    #[test]
+    fn test_strip_snap() {
+        let mut frame = vec![0x00; 12];
+        frame.extend([
+            0x00, 0x09, 0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00, 0x45,
+        ]);
+        assert_eq!(
+            strip(&frame, LinkLayer::Ethernet),
+            Some((EtherTypes::Ipv4, &[0x45][..]))
+        );
+        assert_eq!(
+            strip(&frame, LinkLayer::Auto),
+            Some((EtherTypes::Ipv4, &[0x45][..]))
+        );
+
+        let mut stp = frame.clone();
+        stp[14..17].copy_from_slice(&[0x42, 0x42, 0x03]);
+        assert_eq!(
+            strip(&stp, LinkLayer::Ethernet),
+            Some((EtherType(9), &stp[14..]))
+        );
+        assert_eq!(
+            strip(&frame[..18], LinkLayer::Ethernet),
+            Some((EtherType(9), &frame[14..18])),
+            "Expected a truncated SNAP header to be kept"
+        );
+        let mut ethernet2 = frame.clone();
+        ethernet2[12..14].copy_from_slice(&[0x08, 0x00]);
+        assert_eq!(
+            strip(&ethernet2, LinkLayer::Ethernet),
+            Some((EtherTypes::Ipv4, &ethernet2[14..]))
+        );
+    }
+}
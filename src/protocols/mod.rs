@@ -1,4 +1,26 @@
+pub mod ah;
+pub mod arp;
+pub mod cryptopan;
+pub mod dhcp;
+pub mod dns;
+pub mod esp;
+pub mod ethernet;
+pub mod frame;
+pub mod gre;
+pub mod gtp;
+pub mod icmpv6;
 pub mod ipv4;
+pub mod ipv6;
+pub mod link;
+pub mod mpls;
 pub mod packet;
+pub mod payload;
+pub mod quic;
+pub mod sctp;
 pub mod tcp;
+pub mod tcp_options;
+pub mod timing;
+pub mod tls;
 pub mod udp;
+pub mod util;
+pub mod vlan;
@@ -0,0 +1,204 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+use pnet::packet::ethernet::{EtherType, EtherTypes};
+
+/// Maximum number of MPLS label stack entries popped before giving up on a packet.
+pub(crate) const MAX_MPLS_DEPTH: usize = 8;
+
+/// Implementation of an MPLS label stack entry.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct MplsHeader {
+    /// A flat vector of parsed bit values, size of 32 bits as it's the length of a label stack entry
+    data: Vec<f32>,
+}
+
+impl Default for MplsHeader {
+    /// Returns an `MplsHeader` filled with 32 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for MplsHeader {
+    /// Number of bits extracted, the top entry of the label stack.
+    const WIDTH: usize = 32;
+
+    /// Constructs an `MplsHeader` from a raw bytes MPLS label stack.
+    ///
+    /// The first stack entry (label, traffic class, bottom-of-stack bit and TTL) is parsed bit by bit.
+    /// Returns `None` if the input is shorter than a stack entry.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing an MPLS label stack.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let entry = packet.get(..4)?;
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(entry, config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> MplsHeader {
+        MplsHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Mpls`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Mpls
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `mpls_label_0`, `mpls_label_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("mpls_label", 20),
+            ("mpls_tc", 3),
+            ("mpls_bos", 1),
+            ("mpls_ttl", 8),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in a label stack entry.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl MplsHeader {
+    /// Returns an `MplsHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Returns `true` if the ethertype announces an MPLS label stack, unicast or multicast.
+///
+/// # Arguments
+/// * `ethertype` - EtherType read before the stack.
+pub(crate) fn is_mpls(ethertype: EtherType) -> bool {
+    matches!(ethertype, EtherTypes::Mpls | EtherTypes::MplsMcast)
+}
+
+/// Pops the entries of an MPLS label stack until the bottom-of-stack bit.
+///
+/// MPLS doesn't announce its payload, IPv4 and IPv6 are recognized from their version field.
+/// Any other payload, or a stack deeper than `MAX_MPLS_DEPTH`, is returned as `EtherTypes::Mpls`.
+///
+/// # Arguments
+/// * `packet` - Raw bytes of the label stack.
+///
+/// # Returns
+/// The ethertype of the payload and its bytes, or `None` if the stack is truncated.
+pub(crate) fn pop_labels(mut packet: &[u8]) -> Option<(EtherType, &[u8])> {
+    for _ in 0..MAX_MPLS_DEPTH {
+        let bottom = packet.get(2)? & 0x01 != 0;
+        packet = packet.get(4..)?;
+        if bottom {
+            let ethertype = match packet.first().map(|byte| byte >> 4) {
+                Some(4) => EtherTypes::Ipv4,
+                Some(6) => EtherTypes::Ipv6,
+                _ => EtherTypes::Mpls,
+            };
+            return Some((ethertype, packet));
+        }
+    }
+    Some((EtherTypes::Mpls, packet))
+}
+
+#[cfg(test)]
+mod mpls_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    #[test]
+    fn test_mpls_header_creation() {
+        let raw_packet: Vec<u8> = vec![0x00, 0x01, 0x41, 0x40, 0x45];
+        let mpls_header = MplsHeader::new(&raw_packet);
+        let data = mpls_header.get_data();
+        assert_eq!(data.len(), 32, "Expected 32 bits in MplsHeader data.");
+        assert_eq!(
+            data[..24],
+            [
+                0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1., 0., 1., 0., 0., 0.,
+                0., 0., 1.
+            ],
+            "Expected label 20 at the bottom of the stack."
+        );
+        assert_eq!(data[24..], [0., 1., 0., 0., 0., 0., 0., 0.], "Wrong TTL.");
+    }
+
+    #[test]
+    fn test_pop_labels() {
+        let stack = [0x00, 0x01, 0x40, 0x40, 0x00, 0x02, 0x01, 0x40, 0x60, 0x00];
+        assert_eq!(
+            pop_labels(&stack),
+            Some((EtherTypes::Ipv6, &stack[8..])),
+            "Expected the IPv6 payload after two entries"
+        );
+        assert_eq!(pop_labels(&stack[..6]), None, "Expected a truncated stack");
+        let ethernet = [0x00, 0x01, 0x41, 0x40, 0x00, 0x00];
+        assert_eq!(
+            pop_labels(&ethernet),
+            Some((EtherTypes::Mpls, &ethernet[4..]))
+        );
+        let endless = [0x00; 4 * (MAX_MPLS_DEPTH + 1)];
+        assert_eq!(
+            pop_labels(&endless),
+            Some((EtherTypes::Mpls, &endless[4 * MAX_MPLS_DEPTH..])),
+            "Expected the walk to stop at the depth limit"
+        );
+        assert!(is_mpls(EtherTypes::MplsMcast));
+        assert!(!is_mpls(EtherTypes::Ipv4));
+    }
+
+    #[test]
+    fn test_mpls_header_get_headers() {
+        let headers = MplsHeader::get_headers();
+        assert_eq!(headers.len(), 32, "Expected 32 header names.");
+        assert_eq!(headers[0], "mpls_label_0");
+        assert_eq!(headers[20], "mpls_tc_0");
+        assert_eq!(headers[23], "mpls_bos_0");
+        assert_eq!(headers[31], "mpls_ttl_7");
+    }
+
+    #[test]
+    fn test_mpls_header_bad_header() {
+        let mpls_header = MplsHeader::new(&[0x0, 0x1]);
+        assert!(
+            mpls_header.bit_eq(&MplsHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            MplsHeader::new_with_config(&[0x0, 0x1], &config).bit_eq(&MplsHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+}
@@ -1,3 +1,7 @@
+use crate::config::{BitOrder, NprintConfig};
+#[cfg(test)]
+use crate::protocols::util::bit_eq;
+use crate::ProtocolType;
 use core::fmt::Debug;
 
 /// A trait to provide a generic handling of protocols
@@ -7,23 +11,299 @@ use core::fmt::Debug;
 /// float data, and accessing header metadata.
 ///
 pub(crate) trait PacketHeader: Debug {
-    /// Initializes a new instance, and return it.
+    /// Number of bits extracted with the default configuration, as many as the names of
+    /// `get_headers()`.
+    const WIDTH: usize;
+
+    /// Initializes a new instance with the default configuration, and return it.
     ///
     /// # Arguments
     /// * `data` - A byte slice containing the raw packet.
+    #[cfg(test)]
     fn new(data: &[u8]) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new_with_config(data, &NprintConfig::default())
+    }
+
+    /// Initializes a new instance following the given configuration, and return it.
+    ///
+    /// If the raw packet cannot be parsed, the absent header is returned instead.
+    ///
+    /// # Arguments
+    /// * `data` - A byte slice containing the raw packet.
+    /// * `config` - Configuration of the parsing.
+    #[cfg(test)]
+    fn new_with_config(data: &[u8], config: &NprintConfig) -> Self
+    where
+        Self: Sized,
+    {
+        let header = Self::parse(data, config).unwrap_or_else(|| Self::absent(config));
+        header.debug_assert_width(config);
+        header
+    }
+
+    /// Parses a raw packet following the given configuration.
+    ///
+    /// # Arguments
+    /// * `data` - A byte slice containing the raw packet.
+    /// * `config` - Configuration of the parsing.
+    ///
+    /// # Returns
+    /// The parsed header, or `None` if the raw packet is invalid or truncated.
+    fn parse(data: &[u8], config: &NprintConfig) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let mut header = Self::absent(config);
+        Self::parse_into(data, config, header.get_data_mut())?;
+        Some(header)
+    }
+
+    /// Parses a raw packet following the given configuration, writing its bits into `out`.
+    ///
+    /// # Arguments
+    /// * `data` - A byte slice containing the raw packet.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits, as wide as the header names.
+    ///
+    /// # Returns
+    /// `None` if the raw packet is invalid or truncated, `out` being then partially written.
+    fn parse_into(data: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()>
     where
         Self: Sized;
 
+    /// Returns the header of an absent or unparsable protocol, filled with the configured fill value.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration of the parsing.
+    fn absent(config: &NprintConfig) -> Self
+    where
+        Self: Sized;
+
+    /// Asserts in debug builds that the data is as wide as its protocol with the given
+    /// configuration, `WIDTH` with the default one.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration the header was parsed with.
+    #[cfg(test)]
+    fn debug_assert_width(&self, config: &NprintConfig)
+    where
+        Self: Sized,
+    {
+        debug_assert_eq!(
+            self.get_data().len(),
+            self.protocol_type().width_with_config(config),
+            "{:?} data doesn't match its width",
+            self.protocol_type()
+        );
+        debug_assert!(
+            *config != NprintConfig::default() || self.get_data().len() == Self::WIDTH,
+            "{:?} data doesn't match WIDTH",
+            self.protocol_type()
+        );
+    }
+
     /// Returns a reference to a vector of 32-bit floating-point numbers representing the
     /// parsed data content from the protocol if not possible, may return a default representation.
     fn get_data(&self) -> &Vec<f32>;
 
+    /// Returns a mutable view of the parsed data, used to post-process fields in place.
+    fn get_data_mut(&mut self) -> &mut [f32];
+
+    /// Returns the `ProtocolType` this header has been parsed as.
+    fn protocol_type(&self) -> ProtocolType;
+
     /// Returns the list of all field names of the protocols.
     fn get_headers() -> Vec<String>
     where
         Self: Sized;
 
+    /// Returns the list of all field names following the given configuration.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration of the parsing.
+    fn headers_with_config(_config: &NprintConfig) -> Vec<String>
+    where
+        Self: Sized,
+    {
+        Self::get_headers()
+    }
+
+    /// Compares the extracted data of two headers, see `bit_eq()`.
+    ///
+    /// Unlike `==` on the data, absent fields filled with `FillValue::NaN` are equal.
+    ///
+    /// # Arguments
+    /// * `other` - Header to compare with.
+    ///
+    /// # Returns
+    /// `true` if both headers hold the same bits.
+    #[cfg(test)]
+    fn bit_eq(&self, other: &Self) -> bool
+    where
+        Self: Sized,
+    {
+        bit_eq(self.get_data(), other.get_data())
+    }
+
+    /// Checks that every extracted bit has a name, i.e. that the field widths listed by
+    /// `headers_with_config()` add up to the length of `get_data()`.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration the header was parsed with.
+    ///
+    /// # Returns
+    /// `true` if the header names match the extracted data.
+    #[cfg(test)]
+    fn validate(&self, config: &NprintConfig) -> bool
+    where
+        Self: Sized,
+    {
+        Self::headers_with_config(config).len() == self.get_data().len()
+    }
+
     /// Remove the sensitive data
-    fn anonymize(&mut self);
+    #[cfg(test)]
+    fn anonymize(&mut self)
+    where
+        Self: Sized,
+    {
+        Self::anonymize_bits(self.get_data_mut());
+    }
+
+    /// Remove the sensitive data from the bits of a header.
+    ///
+    /// # Arguments
+    /// * `data` - Bits of a header of this protocol.
+    fn anonymize_bits(data: &mut [f32])
+    where
+        Self: Sized;
+
+    /// Reassembles the raw bytes of the header from the extracted bits.
+    ///
+    /// # Arguments
+    /// * `order` - Order of the bits extracted from every byte.
+    ///
+    /// # Returns
+    /// The header bytes, or `None` if a field is absent or the protocol doesn't support it.
+    #[cfg(test)]
+    fn to_bytes(&self, order: BitOrder) -> Option<Vec<u8>>
+    where
+        Self: Sized,
+    {
+        Self::reassemble(self.get_data(), order)
+    }
+
+    /// Reassembles the raw bytes of a header from its bits, see `to_bytes()`.
+    ///
+    /// # Arguments
+    /// * `data` - Bits of a header of this protocol.
+    /// * `order` - Order of the bits extracted from every byte.
+    fn reassemble(data: &[f32], order: BitOrder) -> Option<Vec<u8>>
+    where
+        Self: Sized,
+    {
+        let _ = (data, order);
+        None
+    }
+
+    /// Pseudonymize the sensitive data with a key, so that equal values keep matching.
+    ///
+    /// Headers without a keyed scheme are anonymized as with `anonymize()`.
+    ///
+    /// # Arguments
+    /// * `key` - Secret key of the pseudonymization.
+    /// * `order` - Order of the bits extracted from every byte.
+    #[cfg(test)]
+    fn anonymize_with_key(&mut self, key: &[u8; 16], order: BitOrder)
+    where
+        Self: Sized,
+    {
+        Self::anonymize_bits_with_key(self.get_data_mut(), key, order);
+    }
+
+    /// Pseudonymize the bits of a header with a key, see `anonymize_with_key()`.
+    ///
+    /// # Arguments
+    /// * `data` - Bits of a header of this protocol.
+    /// * `key` - Secret key of the pseudonymization.
+    /// * `order` - Order of the bits extracted from every byte.
+    fn anonymize_bits_with_key(data: &mut [f32], key: &[u8; 16], order: BitOrder)
+    where
+        Self: Sized,
+    {
+        let _ = (key, order);
+        Self::anonymize_bits(data);
+    }
+}
+
+#[cfg(test)]
+mod packet_header_tests {
+    use super::*;
+    use crate::protocols::ah::AhHeader;
+    use crate::protocols::arp::ArpHeader;
+    use crate::protocols::dhcp::DhcpHeader;
+    use crate::protocols::dns::DnsHeader;
+    use crate::protocols::esp::EspHeader;
+    use crate::protocols::ethernet::EthernetHeader;
+    use crate::protocols::frame::FrameMetaHeader;
+    use crate::protocols::gre::GreHeader;
+    use crate::protocols::gtp::GtpHeader;
+    use crate::protocols::icmpv6::Icmpv6Header;
+    use crate::protocols::ipv4::Ipv4Header;
+    use crate::protocols::mpls::MplsHeader;
+    use crate::protocols::payload::PayloadHeader;
+    use crate::protocols::quic::QuicHeader;
+    use crate::protocols::sctp::SctpHeader;
+    use crate::protocols::tcp::TcpHeader;
+    use crate::protocols::tcp_options::TcpOptionsHeader;
+    use crate::protocols::timing::TimingHeader;
+    use crate::protocols::tls::TlsClientHelloHeader;
+    use crate::protocols::udp::UdpHeader;
+    use crate::protocols::vlan::VlanHeader;
+
+    /// Asserts that the header names and the absent data of `H` are `WIDTH` long.
+    fn assert_width<H: PacketHeader>() {
+        let absent = H::absent(&NprintConfig::default());
+        assert_eq!(
+            H::get_headers().len(),
+            H::WIDTH,
+            "{:?} header names don't match WIDTH",
+            absent.protocol_type()
+        );
+        assert_eq!(
+            absent.get_data().len(),
+            H::WIDTH,
+            "{:?} data doesn't match WIDTH",
+            absent.protocol_type()
+        );
+        assert_eq!(absent.protocol_type().width(), H::WIDTH);
+    }
+
+    #[test]
+    fn test_packet_header_width() {
+        assert_width::<EthernetHeader>();
+        assert_width::<Ipv4Header>();
+        assert_width::<TcpHeader>();
+        assert_width::<UdpHeader>();
+        assert_width::<VlanHeader>();
+        assert_width::<DnsHeader>();
+        assert_width::<ArpHeader>();
+        assert_width::<SctpHeader>();
+        assert_width::<GreHeader>();
+        assert_width::<Icmpv6Header>();
+        assert_width::<PayloadHeader>();
+        assert_width::<QuicHeader>();
+        assert_width::<TcpOptionsHeader>();
+        assert_width::<TimingHeader>();
+        assert_width::<DhcpHeader>();
+        assert_width::<MplsHeader>();
+        assert_width::<TlsClientHelloHeader>();
+        assert_width::<FrameMetaHeader>();
+        assert_width::<GtpHeader>();
+        assert_width::<EspHeader>();
+        assert_width::<AhHeader>();
+    }
 }
@@ -0,0 +1,211 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+
+/// Number of payload bytes extracted by default, the maximum Ethernet frame length.
+pub(crate) const DEFAULT_PAYLOAD_BYTES: usize = 1514;
+
+/// Implementation of the transport payload.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct PayloadHeader {
+    /// A flat vector of parsed bit values, size of 8 bits per configured payload byte
+    data: Vec<f32>,
+}
+
+impl Default for PayloadHeader {
+    /// Returns a `PayloadHeader` filled with 1514 * 8 "-1"
+    fn default() -> Self {
+        Self::with_mtu(DEFAULT_PAYLOAD_BYTES)
+    }
+}
+
+impl PacketHeader for PayloadHeader {
+    /// Number of bits extracted with the default payload length.
+    const WIDTH: usize = DEFAULT_PAYLOAD_BYTES * 8;
+
+    /// Constructs a `PayloadHeader` from the raw bytes following the transport header.
+    ///
+    /// The first `payload_offset` bytes are skipped, then up to `max_payload_bytes` bytes
+    /// are parsed bit by bit. Missing bytes are padded with the fill value.
+    /// Returns `None` if the payload is longer than the configured `mtu`.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes of the payload.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        if config.mtu.is_some_and(|mtu| packet.len() > mtu) {
+            return None;
+        }
+        let bytes = config.payload_bytes();
+        let payload = packet.get(config.payload_offset..).unwrap_or_default();
+        let payload = &payload[..payload.len().min(bytes)];
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(payload, config.bit_order));
+        data.fill(config.fill_value.value());
+        Some(())
+    }
+
+    /// Returns the header of an absent payload, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> PayloadHeader {
+        PayloadHeader::with_fill(config.fill_value.value(), config.payload_bytes())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Payload`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Payload
+    }
+
+    /// Returns the name list of all bits of the default payload length, see `headers()`.
+    fn get_headers() -> Vec<String> {
+        Self::headers(Self::WIDTH / 8)
+    }
+
+    /// Returns the name list of all bits of the configured payload length.
+    fn headers_with_config(config: &NprintConfig) -> Vec<String> {
+        Self::headers(config.payload_bytes())
+    }
+
+    /// Nothing is removed from the payload, limit `max_payload_bytes` instead.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl PayloadHeader {
+    /// Returns a `PayloadHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    /// * `bytes` - Number of payload bytes.
+    pub fn with_fill(fill: f32, bytes: usize) -> Self {
+        Self {
+            data: vec![fill; bytes * 8],
+        }
+    }
+
+    /// Returns a `PayloadHeader` sized to the given MTU, filled with "-1".
+    ///
+    /// # Arguments
+    /// * `bytes` - Largest payload expected, 1514 by default.
+    pub fn with_mtu(bytes: usize) -> Self {
+        Self::with_fill(-1., bytes)
+    }
+
+    /// Returns the name list of the payload bits.
+    ///
+    /// Header names are suffixed with an index (e.g., `payload_0`, `payload_1`).
+    ///
+    /// # Arguments
+    /// * `bytes` - Number of payload bytes.
+    pub fn headers(bytes: usize) -> Vec<String> {
+        (0..bytes * 8).map(|i| format!("payload_{}", i)).collect()
+    }
+}
+
+#[cfg(test)]
+mod payload_header_tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_header_validate() {
+        let config = NprintConfig {
+            max_payload_bytes: Some(16),
+            ..Default::default()
+        };
+        assert!(PayloadHeader::new_with_config(&[0x80], &config).validate(&config));
+        assert!(!PayloadHeader::new(&[0x80]).validate(&config));
+        let default = NprintConfig::default();
+        assert!(PayloadHeader::default().validate(&default));
+        assert!(!PayloadHeader { data: vec![0.; 7] }.validate(&default));
+    }
+
+    #[test]
+    fn test_payload_header_creation() {
+        let payload_header = PayloadHeader::new(&[0x80, 0x01]);
+        let data = payload_header.get_data();
+        assert_eq!(
+            data.len(),
+            PayloadHeader::WIDTH,
+            "Expected 12112 bits in PayloadHeader data."
+        );
+        assert_eq!(
+            data[..16],
+            [1., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1.],
+            "Payload doesn't match expected."
+        );
+        assert!(
+            data[16..].iter().all(|bit| *bit == -1.),
+            "Expected padding."
+        );
+    }
+
+    #[test]
+    fn test_payload_header_limit_and_offset() {
+        let config = NprintConfig {
+            max_payload_bytes: Some(16),
+            payload_offset: 1,
+            ..Default::default()
+        };
+        let payload: Vec<u8> = (0..32).collect();
+        let payload_header = PayloadHeader::new_with_config(&payload, &config);
+        let data = payload_header.get_data();
+        assert_eq!(data.len(), 128, "Expected 128 bits with a 16 bytes limit.");
+        assert_eq!(
+            data[..8],
+            [0., 0., 0., 0., 0., 0., 0., 1.],
+            "Expected the first byte to be skipped."
+        );
+
+        let short = PayloadHeader::new_with_config(&payload[..4], &config);
+        assert_eq!(short.get_data().len(), 128);
+        assert!(short.get_data()[24..].iter().all(|bit| *bit == -1.));
+        let skipped = PayloadHeader::new_with_config(&[], &config);
+        assert_eq!(skipped, PayloadHeader::with_fill(-1., 16));
+    }
+
+    #[test]
+    fn test_payload_header_mtu() {
+        let config = NprintConfig {
+            mtu: Some(9000),
+            ..Default::default()
+        };
+        assert_eq!(config.payload_bytes(), 9000);
+        let payload = vec![0xff; 4000];
+        let payload_header = PayloadHeader::new_with_config(&payload, &config);
+        let data = payload_header.get_data();
+        assert_eq!(
+            data.len(),
+            9000 * 8,
+            "Expected the header sized to the MTU."
+        );
+        assert!(data[..4000 * 8].iter().all(|bit| *bit == 1.));
+        assert!(data[4000 * 8..].iter().all(|bit| *bit == -1.));
+
+        let jumbo = PayloadHeader::new_with_config(&vec![0xff; 9001], &config);
+        assert!(
+            jumbo.bit_eq(&PayloadHeader::with_mtu(9000)),
+            "Expected a payload exceeding the MTU to be absent."
+        );
+        assert!(PayloadHeader::default().bit_eq(&PayloadHeader::with_mtu(1514)));
+    }
+
+    #[test]
+    fn test_payload_header_get_headers() {
+        let headers = PayloadHeader::headers(16);
+        assert_eq!(headers.len(), 128, "Expected 128 header names.");
+        assert_eq!(headers[0], "payload_0");
+        assert_eq!(headers[127], "payload_127");
+        assert_eq!(PayloadHeader::get_headers().len(), PayloadHeader::WIDTH);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bit, bytes_to_bits, remove, BitWriter};
+use crate::ProtocolType;
+
+/// Maximum length in bytes of a QUIC connection ID.
+const MAX_CID_LEN: usize = 20;
+/// UDP port of QUIC short header packets, whose first byte alone isn't distinctive enough.
+const QUIC_PORT: u16 = 443;
+
+/// Implementation of QUIC header (RFC 9000), limited to its unencrypted bits.
+///
+/// Only the header form, fixed bit, long packet type, spin bit, version and connection IDs
+/// are extracted: the other bits of the first byte and the packet number are hidden by header
+/// protection, and the rest of the packet is encrypted.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct QuicHeader {
+    /// A flat vector of parsed bit values, size of 373 bits as it's the visible QUIC header with 20 bytes connection IDs
+    data: Vec<f32>,
+}
+
+impl Default for QuicHeader {
+    /// Returns a `QuicHeader` filled with 373 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for QuicHeader {
+    /// Number of bits extracted, the visible bits of a long header.
+    const WIDTH: usize = 373;
+
+    /// Constructs a `QuicHeader` from a raw bytes UDP payload.
+    ///
+    /// The visible bits of long header packets are all parsed. Short header packets only
+    /// expose their form, fixed and spin bits, other fields are filled.
+    /// Returns `None` if the payload isn't a valid QUIC header.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes of a UDP payload.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let fill = config.fill_value.value();
+        let first = *packet.first()?;
+        let order = config.bit_order;
+        let mut data = BitWriter::new(out);
+        data.extend([bit(first, 0, order), bit(first, 1, order)]);
+        if first & 0x80 == 0 {
+            data.extend([fill, fill, bit(first, 2, order)]);
+            data.fill(fill);
+            return Some(());
+        }
+        data.extend([bit(first, 2, order), bit(first, 3, order), fill]);
+
+        let (version, cids) = packet.get(1..5).zip(packet.get(5..))?;
+        data.extend(bytes_to_bits(version, order));
+        let mut cids = cids;
+        for _ in 0..2 {
+            let (&len, cid) = cids.split_first().filter(|(len, rest)| {
+                **len as usize <= MAX_CID_LEN && rest.len() >= **len as usize
+            })?;
+            let (cid, rest) = cid.split_at(len as usize);
+            data.extend(bytes_to_bits(&[len], order));
+            data.extend(bytes_to_bits(cid, order));
+            data.extend(std::iter::repeat_n(fill, (MAX_CID_LEN - cid.len()) * 8));
+            cids = rest;
+        }
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> QuicHeader {
+        QuicHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Quic`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Quic
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `quic_form_0`, `quic_form_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("quic_form", 1),
+            ("quic_fixed", 1),
+            ("quic_type", 2),
+            ("quic_spin", 1),
+            ("quic_version", 32),
+            ("quic_dcil", 8),
+            ("quic_dcid", 160),
+            ("quic_scil", 8),
+            ("quic_scid", 160),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Remove connection IDs, which identify the endpoints across migrations.
+    fn anonymize_bits(data: &mut [f32]) {
+        remove(data, 45, 204); // Destination connection ID
+        remove(data, 213, 372); // Source connection ID
+    }
+}
+
+impl QuicHeader {
+    /// Returns a `QuicHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Returns `true` if a UDP payload looks like a QUIC packet.
+///
+/// Long header packets are recognized by their first byte and connection ID lengths,
+/// short header packets by their fixed bit on port 443.
+///
+/// # Arguments
+/// * `src` - UDP source port.
+/// * `dst` - UDP destination port.
+/// * `payload` - Raw bytes of the UDP payload.
+pub(crate) fn is_quic(src: u16, dst: u16, payload: &[u8]) -> bool {
+    match payload.first() {
+        Some(first) if first & 0xc0 == 0xc0 => {
+            let Some(dcil) = payload.get(5).map(|len| *len as usize) else {
+                return false;
+            };
+            dcil <= MAX_CID_LEN
+                && payload
+                    .get(6 + dcil)
+                    .is_some_and(|scil| (*scil as usize) <= MAX_CID_LEN)
+        }
+        Some(first) if first & 0xc0 == 0x40 => src == QUIC_PORT || dst == QUIC_PORT,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod quic_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    // Initial packet, version 1, 8 bytes DCID, empty SCID
+    const PACKET: [u8; 20] = [
+        0xc3, 0x00, 0x00, 0x00, 0x01, 0x08, 0x83, 0x94, 0xc8, 0xf0, 0x3e, 0x51, 0x57, 0x08, 0x00,
+        0x00, 0x44, 0x9e, 0x7b, 0x9a,
+    ];
+
+    #[test]
+    fn test_quic_header_creation() {
+        let quic_header = QuicHeader::new(&PACKET);
+        let data = quic_header.get_data();
+        assert_eq!(data.len(), 373, "Expected 373 bits in QuicHeader data.");
+        assert_eq!(
+            data[..5],
+            [1., 1., 0., 0., -1.],
+            "First byte doesn't match expected."
+        );
+        assert_eq!(data[36], 1., "Version doesn't match expected.");
+        assert_eq!(
+            data[37..45],
+            [0., 0., 0., 0., 1., 0., 0., 0.],
+            "DCID length doesn't match expected."
+        );
+        assert_eq!(data[45], 1., "DCID doesn't match expected.");
+        assert!(
+            data[109..205].iter().all(|bit| *bit == -1.),
+            "Expected DCID padding."
+        );
+        assert!(
+            data[205..213].iter().all(|bit| *bit == 0.),
+            "Expected empty SCID."
+        );
+        assert!(
+            data[213..].iter().all(|bit| *bit == -1.),
+            "Expected SCID padding."
+        );
+    }
+
+    #[test]
+    fn test_quic_header_short() {
+        let quic_header = QuicHeader::new(&[0x63, 0x01, 0x02]);
+        let data = quic_header.get_data();
+        assert_eq!(data.len(), 373, "Expected 373 bits in QuicHeader data.");
+        assert_eq!(
+            data[..5],
+            [0., 1., -1., -1., 1.],
+            "First byte doesn't match expected."
+        );
+        assert!(
+            data[5..].iter().all(|bit| *bit == -1.),
+            "Expected hidden fields."
+        );
+    }
+
+    #[test]
+    fn test_quic_header_get_headers() {
+        let headers = QuicHeader::get_headers();
+        assert_eq!(headers.len(), 373, "Expected 373 header names.");
+        assert_eq!(headers[0], "quic_form_0");
+        assert_eq!(headers[4], "quic_spin_0");
+        assert_eq!(headers[5], "quic_version_0");
+        assert_eq!(headers[37], "quic_dcil_0");
+        assert_eq!(headers[45], "quic_dcid_0");
+        assert_eq!(headers[205], "quic_scil_0");
+        assert_eq!(headers[372], "quic_scid_159");
+    }
+
+    #[test]
+    fn test_quic_header_bad_header() {
+        let quic_header = QuicHeader::new(&PACKET[..8]);
+        assert!(
+            quic_header.bit_eq(&QuicHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            QuicHeader::new_with_config(&PACKET[..8], &config).bit_eq(&QuicHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+
+    #[test]
+    fn test_quic_header_anonymize() {
+        let mut quic_header = QuicHeader::new(&PACKET);
+        quic_header.anonymize();
+        let anon = quic_header.get_data();
+        assert!(
+            anon[45..205].iter().all(|bit| *bit == 0.),
+            "Expected DCID to be 0."
+        );
+        assert_eq!(anon[36], 1., "Expected version to be kept.");
+    }
+
+    #[test]
+    fn test_is_quic() {
+        assert!(is_quic(50000, 443, &PACKET));
+        assert!(is_quic(50000, 8443, &PACKET));
+        assert!(is_quic(443, 50000, &[0x41, 0x00]));
+        assert!(!is_quic(53, 50000, &[0x41, 0x00]));
+        assert!(!is_quic(50000, 443, &[0xc3, 0x00, 0x00, 0x00, 0x01, 0x30]));
+        assert!(!is_quic(50000, 443, &[]));
+    }
+}
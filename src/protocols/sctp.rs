@@ -0,0 +1,168 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, remove, BitWriter};
+use crate::ProtocolType;
+
+/// Length in bytes of the SCTP common header.
+const SCTP_HEADER_LEN: usize = 12;
+
+/// Implementation of SCTP common header.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct SctpHeader {
+    /// A flat vector of parsed bit values, size of 96 bits as it's the SCTP common header length
+    data: Vec<f32>,
+}
+
+impl Default for SctpHeader {
+    /// Returns an `SctpHeader` filled with 96 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for SctpHeader {
+    /// Number of bits extracted, the 12 bytes of the SCTP common header.
+    const WIDTH: usize = 96;
+
+    /// Constructs an `SctpHeader` from a raw bytes SCTP packet.
+    ///
+    /// If the input is long enough to hold the common header, its fields are parsed bit by bit.
+    /// Returns `None` if the packet is too short.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing an SCTP packet.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        if packet.len() < SCTP_HEADER_LEN {
+            return None;
+        }
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(&packet[..12], config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> SctpHeader {
+        SctpHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Sctp`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Sctp
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `sctp_sport_0`, `sctp_sport_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("sctp_sport", 16),
+            ("sctp_dport", 16),
+            ("sctp_vtag", 32),
+            ("sctp_cksum", 32),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    ///  Anonymize port source and destination
+    fn anonymize_bits(data: &mut [f32]) {
+        remove(data, 0, 15); // Port source
+        remove(data, 16, 31); // Port destination
+    }
+}
+
+impl SctpHeader {
+    /// Returns an `SctpHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+#[cfg(test)]
+mod sctp_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    const PACKET: [u8; 16] = [
+        0x0b, 0x59, 0x0b, 0x59, 0x12, 0x34, 0x56, 0x78, 0xde, 0xad, 0xbe, 0xef, 0x01, 0x00, 0x00,
+        0x04,
+    ];
+
+    #[test]
+    fn test_sctp_header_creation() {
+        let sctp_header = SctpHeader::new(&PACKET);
+        let data = sctp_header.get_data();
+        assert_eq!(data.len(), 96, "Expected 96 bits in SctpHeader data.");
+        assert_eq!(
+            data[..16],
+            [0., 0., 0., 0., 1., 0., 1., 1., 0., 1., 0., 1., 1., 0., 0., 1.],
+            "Source port doesn't match expected."
+        );
+        assert_eq!(
+            data[32..40],
+            [0., 0., 0., 1., 0., 0., 1., 0.],
+            "Verification tag doesn't match expected."
+        );
+        assert_eq!(data[95], 1., "Checksum doesn't match expected.");
+    }
+
+    #[test]
+    fn test_sctp_header_get_headers() {
+        let headers = SctpHeader::get_headers();
+        assert_eq!(headers.len(), 96, "Expected 96 header names.");
+        assert_eq!(headers[0], "sctp_sport_0");
+        assert_eq!(headers[16], "sctp_dport_0");
+        assert_eq!(headers[32], "sctp_vtag_0");
+        assert_eq!(headers[64], "sctp_cksum_0");
+        assert_eq!(headers[95], "sctp_cksum_31");
+    }
+
+    #[test]
+    fn test_sctp_header_bad_header() {
+        let sctp_header = SctpHeader::new(&PACKET[..8]);
+        assert!(
+            sctp_header.bit_eq(&SctpHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            SctpHeader::new_with_config(&PACKET[..8], &config).bit_eq(&SctpHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+
+    #[test]
+    fn test_sctp_header_anonymize() {
+        let mut sctp_header = SctpHeader::new(&PACKET);
+        sctp_header.anonymize();
+        let anon = sctp_header.get_data();
+        for port_bit in anon.iter().take(32) {
+            assert_eq!(*port_bit, 0., "Expected data bit 0-31 to be 0.");
+        }
+        assert_eq!(anon[95], 1., "Expected checksum to be kept.");
+    }
+}
@@ -1,6 +1,23 @@
+use crate::config::{BitOrder, NprintConfig};
 use crate::protocols::packet::PacketHeader;
-use pnet::packet::tcp::TcpPacket;
+use crate::protocols::util::{
+    bits_to_bytes, bits_to_value, bytes_to_bits, options_to_bits, remove, BitWriter,
+    DEFAULT_OPTIONS_BITS,
+};
+use crate::ProtocolType;
+use pnet::packet::tcp::{ipv4_checksum, ipv6_checksum, TcpPacket};
 use pnet::packet::Packet;
+use std::net::IpAddr;
+use std::ops::Range;
+
+/// Bit range of the checksum in the TCP data.
+pub(crate) const TCP_CHECKSUM: Range<usize> = 128..144;
+/// Bit range of the flags byte (`tcp_cwr` to `tcp_fin`) in the TCP data.
+pub(crate) const TCP_FLAGS: Range<usize> = 104..112;
+/// Bit range of the window size in the TCP data.
+pub(crate) const TCP_WINDOW: Range<usize> = 112..128;
+/// Number of bits of `tcp_wsize_scaled`, enough for a window shifted by the maximum scale (14).
+pub(crate) const SCALED_WINDOW_BITS: usize = 30;
 
 /// Implementation of TCP header.
 ///
@@ -13,40 +30,66 @@ pub(crate) struct TcpHeader {
 impl Default for TcpHeader {
     /// Returns an `TcpHeader` filled with 480 "-1"
     fn default() -> Self {
-        Self {
-            data: vec![-1.; 480],
-        }
+        Self::with_fill(-1.)
     }
 }
 
 impl PacketHeader for TcpHeader {
+    /// Number of bits extracted by default, the fixed fields followed by the padded options.
+    const WIDTH: usize = Self::FIXED_WIDTH + DEFAULT_OPTIONS_BITS;
+
     /// Constructs an `TcpHeader` from a raw bytes Tcp packet.
     ///
     /// If the input is a valid Tcp packet, its fields are parsed bit by bit.
-    /// If the packet is invalid or cannot be parsed, return Default.
+    /// Returns `None` if the packet is invalid or cannot be parsed, in particular if the
+    /// data offset is below 5 or announces more option bytes than the buffer holds.
     ///
     /// # Arguments
     /// * `packet` - Raw bytes representing an Tcp packet.
-    fn new(packet: &[u8]) -> TcpHeader {
-        if let Some(packet) = TcpPacket::new(packet) {
-            let option = packet.get_options_raw();
-            let mut data = Vec::with_capacity(480);
-            let packet = packet.packet();
-            data.extend((0..16).map(|i| ((packet[i / 8] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..16).map(|i| ((packet[2 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..32).map(|i| ((packet[4 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..32).map(|i| ((packet[8 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..4).rev().map(|i| ((packet[12] >> (4 + i)) & 1) as f32));
-            data.extend((0..4).rev().map(|i| ((packet[12] >> i) & 1) as f32));
-            data.extend((0..8).rev().map(|i| ((packet[13] >> i) & 1) as f32));
-            data.extend((0..16).map(|i| ((packet[14 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..16).map(|i| ((packet[16 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..16).map(|i| ((packet[18 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend(get_options_bits(option));
-            TcpHeader { data }
-        } else {
-            eprintln!("Not an TCP packet, returnin default...");
-            TcpHeader::default()
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let packet = TcpPacket::new(packet)?;
+        let options_len = (packet.get_data_offset() as usize * 4).checked_sub(20)?;
+        let option = packet.get_options_raw();
+        if option.len() < options_len {
+            return None;
+        }
+        let mut data = BitWriter::new(out);
+        let packet = packet.packet();
+        data.extend(bytes_to_bits(&packet[..2], config.bit_order));
+        data.extend(bytes_to_bits(&packet[2..4], config.bit_order));
+        data.extend(bytes_to_bits(&packet[4..8], config.bit_order));
+        data.extend(bytes_to_bits(&packet[8..12], config.bit_order));
+        data.extend(bytes_to_bits(&packet[12..14], config.bit_order)); // Data offset, reserved and flags
+        data.extend(bytes_to_bits(&packet[14..16], config.bit_order));
+        data.extend(bytes_to_bits(&packet[16..18], config.bit_order));
+        data.extend(bytes_to_bits(&packet[18..20], config.bit_order));
+        if config.options_indicator {
+            data.extend([f32::from(u8::from(options_len > 0))]);
+        }
+        data.extend(options_to_bits(
+            option,
+            config.tcp_options_width(),
+            config.fill_value.value(),
+            config.bit_order,
+        ));
+        if config.tcp_flags.has_value() {
+            let scale = if config.normalize_tcp_flags { 255. } else { 1. };
+            data.extend([packet[13] as f32 / scale]);
+        }
+        if config.scale_window {
+            // Unscaled until the flow layer knows the negotiated scale
+            data.extend(std::iter::repeat_n(0., SCALED_WINDOW_BITS - 16));
+            data.extend(bytes_to_bits(&packet[14..16], config.bit_order));
+        }
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> TcpHeader {
+        TcpHeader {
+            data: vec![config.fill_value.value(); TcpHeader::width(config)],
         }
     }
 
@@ -55,11 +98,114 @@ impl PacketHeader for TcpHeader {
         &self.data
     }
 
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Tcp`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Tcp
+    }
+
     /// Returns the name list of all field of the protocols.
     ///
     /// Header names are suffixed with an index (e.g., `tcp_sprt_0`, `tcp_sprt_1`).
     fn get_headers() -> Vec<String> {
-        let fields = vec![
+        Self::headers(DEFAULT_OPTIONS_BITS, false, false, false)
+    }
+
+    /// Returns the name list of all fields extracted following the given configuration.
+    fn headers_with_config(config: &NprintConfig) -> Vec<String> {
+        Self::headers(
+            config.tcp_options_width(),
+            config.options_indicator,
+            config.tcp_flags.has_value(),
+            config.scale_window,
+        )
+    }
+
+    ///  Anonymize port source and destination
+    fn anonymize_bits(data: &mut [f32]) {
+        remove(data, 0, 15); // Port source
+        remove(data, 16, 31); // Port destination
+    }
+
+    /// Reassembles the TCP header, options included up to the data offset.
+    ///
+    /// The options must directly follow the fixed fields, without `tcp_has_opt_0`.
+    /// Returns `None` if the extracted options are shorter than the data offset.
+    fn reassemble(data: &[f32], order: BitOrder) -> Option<Vec<u8>> {
+        let offset = bits_to_value(&data[96..104], order)?;
+        let header_len = ((offset >> 4) as usize * 4).max(20);
+        bits_to_bytes(data.get(..header_len * 8)?, order)
+    }
+}
+
+impl TcpHeader {
+    /// Number of bits of the fixed fields, preceding the options.
+    pub const FIXED_WIDTH: usize = 160;
+
+    /// Returns the number of bits extracted following the given configuration, the fixed
+    /// fields, `tcp_has_opt_0` if extracted and the configured options, followed by
+    /// `tcp_flags_value_0` and `tcp_wsize_scaled` if they are extracted.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration of the parsing, setting in particular the TCP flags encoding.
+    pub fn width(config: &NprintConfig) -> usize {
+        Self::options_range(config).end
+            + usize::from(config.tcp_flags.has_value())
+            + usize::from(config.scale_window) * SCALED_WINDOW_BITS
+    }
+
+    /// Returns the bit range of the options in the data extracted following the given
+    /// configuration, after the fixed fields and `tcp_has_opt_0`.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration of the parsing, setting in particular the options width.
+    pub fn options_range(config: &NprintConfig) -> Range<usize> {
+        let start = Self::FIXED_WIDTH + usize::from(config.options_indicator);
+        start..start + config.tcp_options_width()
+    }
+
+    /// Returns the window size decoded from the bits of a TCP header, unscaled.
+    ///
+    /// # Arguments
+    /// * `data` - Bits of the header.
+    /// * `order` - Order of the bits of every byte.
+    ///
+    /// # Returns
+    /// The window size, or `None` if its bits hold anything but 0 and 1, e.g. the fill value.
+    pub fn window(data: &[f32], order: BitOrder) -> Option<u16> {
+        bits_to_value(data.get(TCP_WINDOW)?, order).map(|value| value as u16)
+    }
+
+    /// Returns the flags byte (`tcp_cwr` to `tcp_fin`) decoded from the bits of a TCP header.
+    ///
+    /// # Arguments
+    /// * `data` - Bits of the header.
+    /// * `order` - Order of the bits of every byte.
+    ///
+    /// # Returns
+    /// The flags, or `None` if their bits hold anything but 0 and 1, e.g. the fill value.
+    pub fn flags(data: &[f32], order: BitOrder) -> Option<u8> {
+        bits_to_value(data.get(TCP_FLAGS)?, order).map(|value| value as u8)
+    }
+
+    /// Returns the name list of all fields, see `get_headers()`.
+    ///
+    /// # Arguments
+    /// * `options_bits` - Number of `tcp_opt` bits.
+    /// * `has_opt` - `true` to list `tcp_has_opt_0` before the options.
+    /// * `flags_value` - `true` to list `tcp_flags_value_0` after the options.
+    /// * `scaled_window` - `true` to end the list with `tcp_wsize_scaled`.
+    pub fn headers(
+        options_bits: usize,
+        has_opt: bool,
+        flags_value: bool,
+        scaled_window: bool,
+    ) -> Vec<String> {
+        let mut fields = vec![
             ("tcp_sprt", 16),
             ("tcp_dprt", 16),
             ("tcp_seq", 32),
@@ -78,53 +224,125 @@ impl PacketHeader for TcpHeader {
             ("tcp_wsize", 16),
             ("tcp_cksum", 16),
             ("tcp_urp", 16),
-            ("tcp_opt", 320),
         ];
 
-        fields
+        fields.push(("tcp_has_opt", usize::from(has_opt)));
+        fields.push(("tcp_opt", options_bits));
+        let mut headers: Vec<String> = fields
             .iter()
             .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
-            .collect()
-    }
-
-    ///  Anonymize port source and destination
-    fn anonymize(&mut self) {
-        self.remove(0, 15); // Port source
-        self.remove(16, 31); // Port destination
+            .collect();
+        if flags_value {
+            headers.push("tcp_flags_value_0".to_string());
+        }
+        if scaled_window {
+            headers.extend((0..SCALED_WINDOW_BITS).map(|i| format!("tcp_wsize_scaled_{}", i)));
+        }
+        headers
     }
-}
 
-impl TcpHeader {
-    /// Remove a given range.
+    /// Returns an `TcpHeader` filled with the given value.
     ///
     /// # Arguments
-    /// * `start` - Starting bit index (inclusive).
-    /// * `end` - Ending bit index (inclusive).
-    pub fn remove(&mut self, start: usize, end: usize) {
-        self.data[start..=end].fill(0.);
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
     }
 }
 
-/// Converts raw options bytes into a bit vector of 320 `f32`.
+/// Returns `true` if the checksum of a TCP segment is valid.
 ///
-/// Fill with `-1.0` all the fields not present.
+/// Truncated segments, and segments whose addresses mix IP versions, are reported invalid.
 ///
 /// # Arguments
-/// * `options` - Slice of bits from the option field of an Tcp header.
-fn get_options_bits(options: &[u8]) -> Vec<f32> {
-    let mut data = Vec::new();
-    for option in options {
-        data.extend((0..8).rev().map(|i| ((option >> i) & 1) as f32));
-    }
-    while data.len() < 320 {
-        data.push(-1.);
-    }
-    data
+/// * `segment` - Raw bytes of the TCP segment.
+/// * `source` - Source address of the IP pseudo-header.
+/// * `destination` - Destination address of the IP pseudo-header.
+pub(crate) fn tcp_checksum_valid(segment: &[u8], source: IpAddr, destination: IpAddr) -> bool {
+    let Some(tcp) = TcpPacket::new(segment) else {
+        return false;
+    };
+    let checksum = match (source, destination) {
+        (IpAddr::V4(source), IpAddr::V4(destination)) => ipv4_checksum(&tcp, &source, &destination),
+        (IpAddr::V6(source), IpAddr::V6(destination)) => ipv6_checksum(&tcp, &source, &destination),
+        _ => return false,
+    };
+    checksum == tcp.get_checksum()
 }
 
 #[cfg(test)]
 mod tcp_header_tests {
     use super::*;
+    use crate::config::{FillValue, TcpFlagsEncoding};
+
+    #[test]
+    fn test_tcp_window_and_flags() {
+        let segment = [
+            0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00, 0x00, 0x00, 0x50, 0x12,
+            0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        for order in [BitOrder::Msb0, BitOrder::Lsb0] {
+            let config = NprintConfig {
+                bit_order: order,
+                ..NprintConfig::default()
+            };
+            let mut data = vec![0.; TcpHeader::width(&config)];
+            assert!(TcpHeader::parse_into(&segment, &config, &mut data).is_some());
+            assert_eq!(TcpHeader::window(&data, order), Some(0x7210));
+            assert_eq!(
+                TcpHeader::flags(&data, order),
+                Some(0x12),
+                "Expected SYN-ACK"
+            );
+        }
+        let absent = TcpHeader::default();
+        assert_eq!(TcpHeader::window(absent.get_data(), BitOrder::Msb0), None);
+        assert_eq!(TcpHeader::flags(absent.get_data(), BitOrder::Msb0), None);
+    }
+
+    #[test]
+    fn test_tcp_header_bad_data_offset() {
+        let mut segment = [
+            0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x02,
+            0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        assert!(
+            TcpHeader::new(&segment).bit_eq(&TcpHeader::default()),
+            "Expected a 60-byte header in 20 bytes to be default."
+        );
+        segment[12] = 0x40;
+        assert!(
+            TcpHeader::new(&segment).bit_eq(&TcpHeader::default()),
+            "Expected a data offset below 5 to be default."
+        );
+        segment[12] = 0x50;
+        assert!(!TcpHeader::new(&segment).bit_eq(&TcpHeader::default()));
+    }
+
+    #[test]
+    fn test_tcp_checksum_valid() {
+        let mut raw_packet: Vec<u8> = vec![
+            0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8,
+            0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b,
+            0x00, 0x00, 0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04,
+            0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x03, 0x03, 0x07,
+        ];
+        let source = IpAddr::from([192, 168, 43, 37]);
+        let destination = IpAddr::from([198, 38, 120, 136]);
+        assert!(tcp_checksum_valid(&raw_packet[20..], source, destination));
+        raw_packet[59] ^= 0x01;
+        assert!(
+            !tcp_checksum_valid(&raw_packet[20..], source, destination),
+            "Expected the corrupted segment to be invalid"
+        );
+        assert!(
+            !tcp_checksum_valid(&raw_packet[20..30], source, destination),
+            "Expected the truncated segment to be invalid"
+        );
+    }
 
     #[test]
     fn test_tcp_header_creation() {
@@ -667,17 +885,26 @@ mod tcp_header_tests {
                 i
             );
         }
+        assert_eq!(headers.len(), TcpHeader::WIDTH);
+        assert_eq!(TcpHeader::default().get_data().len(), TcpHeader::WIDTH);
     }
 
     #[test]
     fn test_tcp_header_bad_header() {
         let raw_packet: Vec<u8> = vec![0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b];
         let tcp_header = TcpHeader::new(&raw_packet);
-        assert_eq!(
-            tcp_header,
-            TcpHeader::default(),
+        assert!(
+            tcp_header.bit_eq(&TcpHeader::default()),
             "Expected data to be default."
         );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            TcpHeader::new_with_config(&raw_packet, &config).bit_eq(&TcpHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
     }
 
     #[test]
@@ -694,4 +921,92 @@ mod tcp_header_tests {
             assert_eq!(*ip_bit, 0., "Expected data bit 0-31 to be 0.");
         }
     }
+
+    #[test]
+    fn test_tcp_header_to_bytes() {
+        let raw_packet: Vec<u8> = vec![
+            0xde, 0x92, 0x01, 0xbb, 0x72, 0x07, 0xf6, 0xa0, 0x00, 0x00, 0x00, 0x00, 0x80, 0x02,
+            0x20, 0x00, 0x05, 0x24, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4, 0x01, 0x03, 0x03, 0x02,
+            0x01, 0x01, 0x04, 0x02,
+        ];
+        let tcp_header = TcpHeader::new(&raw_packet);
+        assert_eq!(tcp_header.to_bytes(BitOrder::Msb0), Some(raw_packet));
+        assert_eq!(TcpHeader::default().to_bytes(BitOrder::Msb0), None);
+    }
+
+    #[test]
+    fn test_tcp_header_validate() {
+        let raw_packet: Vec<u8> = vec![
+            0xde, 0x92, 0x01, 0xbb, 0x72, 0x07, 0xf6, 0xa0, 0x00, 0x00, 0x00, 0x00, 0x50, 0x12,
+            0x20, 0x00, 0x05, 0x24, 0x00, 0x00,
+        ];
+        let default = NprintConfig::default();
+        let configs = [
+            NprintConfig {
+                tcp_flags: TcpFlagsEncoding::Value,
+                ..Default::default()
+            },
+            NprintConfig {
+                tcp_flags: TcpFlagsEncoding::BitsAndValue,
+                scale_window: true,
+                ..Default::default()
+            },
+            NprintConfig {
+                tcp_options_bits: Some(31),
+                scale_window: true,
+                ..Default::default()
+            },
+            NprintConfig {
+                options_indicator: true,
+                tcp_flags: TcpFlagsEncoding::Value,
+                ..Default::default()
+            },
+            NprintConfig {
+                options_indicator: true,
+                ..Default::default()
+            },
+        ];
+        assert!(TcpHeader::new(&raw_packet).validate(&default));
+        for config in &configs {
+            let tcp_header = TcpHeader::new_with_config(&raw_packet, config);
+            assert!(tcp_header.validate(config), "Invalid with {:?}", config);
+            assert!(
+                !tcp_header.validate(&default),
+                "Expected the widths of {:?} to be checked",
+                config
+            );
+        }
+    }
+
+    #[test]
+    fn test_tcp_header_flags_value() {
+        let raw_packet: Vec<u8> = vec![
+            0xde, 0x92, 0x01, 0xbb, 0x72, 0x07, 0xf6, 0xa0, 0x00, 0x00, 0x00, 0x00, 0x50, 0x12,
+            0x20, 0x00, 0x05, 0x24, 0x00, 0x00,
+        ];
+        let mut config = NprintConfig {
+            tcp_flags: TcpFlagsEncoding::BitsAndValue,
+            ..Default::default()
+        };
+        let tcp_header = TcpHeader::new_with_config(&raw_packet, &config);
+        let data = tcp_header.get_data();
+        assert_eq!(data.len(), TcpHeader::WIDTH + 1);
+        assert!(tcp_header.validate(&config));
+        assert_eq!(data[TcpHeader::WIDTH], 18., "Expected SYN-ACK");
+        assert_eq!(
+            TcpHeader::headers(DEFAULT_OPTIONS_BITS, false, true, false)
+                .last()
+                .unwrap(),
+            "tcp_flags_value_0"
+        );
+
+        config.normalize_tcp_flags = true;
+        let tcp_header = TcpHeader::new_with_config(&raw_packet, &config);
+        assert_eq!(tcp_header.get_data()[TcpHeader::WIDTH], 18. / 255.);
+        assert!(TcpHeader::new_with_config(&[0x0], &config).bit_eq(&TcpHeader::absent(&config)));
+        assert_eq!(
+            TcpHeader::absent(&config).get_data().len(),
+            TcpHeader::WIDTH + 1
+        );
+    }
 }
@@ -0,0 +1,292 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::write_bits;
+use crate::ProtocolType;
+use pnet::packet::tcp::TcpPacket;
+
+/// End of option list.
+const EOL: u8 = 0;
+/// No operation, used as padding.
+const NOP: u8 = 1;
+/// Maximum segment size.
+const MSS: u8 = 2;
+/// Window scale.
+const WSCALE: u8 = 3;
+/// SACK permitted.
+const SACK_PERMITTED: u8 = 4;
+/// Timestamps.
+const TIMESTAMPS: u8 = 8;
+
+/// Implementation of decoded TCP options: MSS, window scale, SACK permitted and timestamps.
+///
+/// An alternative to the raw `tcp_opt` region of `TcpHeader`, whose meaning depends on the
+/// position of each option.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct TcpOptionsHeader {
+    /// A flat vector of parsed bit values, size of 89 bits as it's the length of the decoded option values
+    data: Vec<f32>,
+}
+
+impl Default for TcpOptionsHeader {
+    /// Returns a `TcpOptionsHeader` filled with 89 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for TcpOptionsHeader {
+    /// Number of bits extracted, MSS, window scale, SACK permitted and timestamps.
+    const WIDTH: usize = 89;
+
+    /// Constructs a `TcpOptionsHeader` from a raw bytes TCP packet.
+    ///
+    /// The options are walked as type-length-value, stopping at the end of list or at the first
+    /// malformed length. Absent options are filled, except SACK permitted which is a flag.
+    /// Returns `None` if the packet is invalid or cannot be parsed.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing a TCP packet.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let fill = config.fill_value.value();
+        let tcp_packet = TcpPacket::new(packet)?;
+        let header_len = (tcp_packet.get_data_offset() as usize * 4).clamp(20, packet.len());
+
+        let data = out;
+        data.fill(fill);
+        data[24] = 0.;
+        for (kind, value) in options(&packet[20..header_len]) {
+            match (kind, value.len()) {
+                (MSS, 2) => write_bits(&mut data[0..16], value, config.bit_order),
+                (WSCALE, 1) => write_bits(&mut data[16..24], value, config.bit_order),
+                (SACK_PERMITTED, 0) => data[24] = 1.,
+                (TIMESTAMPS, 8) => write_bits(&mut data[25..89], value, config.bit_order),
+                _ => {}
+            }
+        }
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> TcpOptionsHeader {
+        TcpOptionsHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::TcpOptions`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::TcpOptions
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `tcp_mss_0`, `tcp_mss_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("tcp_mss", 16),
+            ("tcp_wscale", 8),
+            ("tcp_sackperm", 1),
+            ("tcp_ts_val", 32),
+            ("tcp_ts_ecr", 32),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in the decoded options.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl TcpOptionsHeader {
+    /// Returns a `TcpOptionsHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Walks raw TCP options as type-length-value, skipping the padding.
+///
+/// The walk stops at the end of list or at the first malformed length.
+///
+/// # Arguments
+/// * `options` - Raw bytes of the options, following the fixed TCP header.
+///
+/// # Returns
+/// An iterator over the kind and value of each option.
+pub(crate) fn options(mut options: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    std::iter::from_fn(move || loop {
+        let (&kind, rest) = options.split_first()?;
+        match kind {
+            EOL => return None,
+            NOP => {
+                options = rest;
+                continue;
+            }
+            _ => {}
+        }
+        let len = *rest.first()? as usize;
+        if len < 2 || len > options.len() {
+            return None;
+        }
+        let value = &options[2..len];
+        options = &options[len..];
+        return Some((kind, value));
+    })
+}
+
+/// Returns the shift count of the window scale option, if present.
+///
+/// # Arguments
+/// * `raw` - Raw bytes of the options, following the fixed TCP header.
+pub(crate) fn window_scale(raw: &[u8]) -> Option<u8> {
+    options(raw).find_map(|(kind, value)| match (kind, value) {
+        (WSCALE, [shift]) => Some(*shift),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tcp_options_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    /// Returns a TCP SYN carrying the given options.
+    fn syn(options: &[u8]) -> Vec<u8> {
+        let mut packet = vec![
+            0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+            0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        packet[12] = (((20 + options.len()) / 4) << 4) as u8;
+        packet.extend(options);
+        packet
+    }
+
+    #[test]
+    fn test_tcp_options_header_creation() {
+        let packet = syn(&[
+            0x02, 0x04, 0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00,
+            0x00, 0x01, 0x01, 0x03, 0x03, 0x07,
+        ]);
+        let tcp_options_header = TcpOptionsHeader::new(&packet);
+        let data = tcp_options_header.get_data();
+        assert_eq!(data.len(), 89, "Expected 89 bits in TcpOptionsHeader data.");
+        assert_eq!(
+            data[..16],
+            [0., 0., 0., 0., 0., 1., 0., 1., 1., 0., 1., 1., 0., 1., 0., 0.],
+            "MSS doesn't match expected."
+        );
+        assert_eq!(
+            data[16..24],
+            [0., 0., 0., 0., 0., 1., 1., 1.],
+            "Window scale doesn't match expected."
+        );
+        assert_eq!(data[24], 1., "Expected SACK permitted.");
+        assert_eq!(
+            data[25..29],
+            [1., 1., 1., 0.],
+            "TSval doesn't match expected."
+        );
+        assert_eq!(data[88], 1., "TSecr doesn't match expected.");
+    }
+
+    #[test]
+    fn test_tcp_options_header_absent() {
+        let tcp_options_header = TcpOptionsHeader::new(&syn(&[0x01, 0x01, 0x00, 0x02]));
+        let data = tcp_options_header.get_data();
+        assert!(
+            data[..24].iter().all(|bit| *bit == -1.),
+            "Expected absent options."
+        );
+        assert_eq!(data[24], 0., "Expected no SACK permitted.");
+        assert!(
+            data[25..].iter().all(|bit| *bit == -1.),
+            "Expected absent timestamps."
+        );
+    }
+
+    #[test]
+    fn test_tcp_options_header_malformed() {
+        for options in [
+            [0x02, 0x00, 0x05, 0xb4],
+            [0x02, 0x08, 0x05, 0xb4],
+            [0xfe, 0x01, 0x02, 0x04],
+            [0x03, 0x04, 0x07, 0x00],
+        ] {
+            let tcp_options_header = TcpOptionsHeader::new(&syn(&options));
+            assert!(
+                tcp_options_header.get_data()[..24]
+                    .iter()
+                    .all(|bit| *bit == -1.),
+                "Expected malformed options to be ignored."
+            );
+        }
+    }
+
+    #[test]
+    fn test_tcp_options_header_get_headers() {
+        let headers = TcpOptionsHeader::get_headers();
+        assert_eq!(headers.len(), 89, "Expected 89 header names.");
+        assert_eq!(headers[0], "tcp_mss_0");
+        assert_eq!(headers[16], "tcp_wscale_0");
+        assert_eq!(headers[24], "tcp_sackperm_0");
+        assert_eq!(headers[25], "tcp_ts_val_0");
+        assert_eq!(headers[57], "tcp_ts_ecr_0");
+        assert_eq!(headers[88], "tcp_ts_ecr_31");
+    }
+
+    #[test]
+    fn test_tcp_options_header_bad_header() {
+        let tcp_options_header = TcpOptionsHeader::new(&[0x97, 0xa4]);
+        assert!(
+            tcp_options_header.bit_eq(&TcpOptionsHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            TcpOptionsHeader::new_with_config(&[0x97, 0xa4], &config)
+                .bit_eq(&TcpOptionsHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+
+    #[test]
+    fn test_window_scale() {
+        assert_eq!(
+            window_scale(&[0x02, 0x04, 0x05, 0xb4, 0x01, 0x03, 0x03, 0x07]),
+            Some(7),
+            "Window scale not found after the NOP"
+        );
+        assert_eq!(
+            window_scale(&[0x02, 0x04, 0x05, 0xb4, 0x00, 0x03, 0x03, 0x07]),
+            None,
+            "Window scale read after the end of option list"
+        );
+        assert_eq!(
+            window_scale(&[0x03, 0x04, 0x07]),
+            None,
+            "Window scale read from a malformed option"
+        );
+    }
+}
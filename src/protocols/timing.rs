@@ -0,0 +1,153 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+use std::time::Duration;
+
+/// Implementation of the inter-arrival time, a pseudo-protocol computed from the capture timestamps.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct TimingHeader {
+    /// A flat vector of parsed bit values, size of 32 bits as it's the length of the delta in microseconds
+    data: Vec<f32>,
+}
+
+impl Default for TimingHeader {
+    /// Returns a `TimingHeader` filled with 32 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for TimingHeader {
+    /// Number of bits extracted, the 4 bytes of the encoded delta.
+    const WIDTH: usize = 32;
+
+    /// Constructs a `TimingHeader` from an encoded inter-arrival delta, see `encode_delta()`.
+    ///
+    /// The 4 bytes of the delta are parsed bit by bit.
+    /// Returns `None` if the input is shorter than 4 bytes.
+    ///
+    /// # Arguments
+    /// * `packet` - Big-endian delta in microseconds.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let delta = packet.get(..4)?;
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(delta, config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> TimingHeader {
+        TimingHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Timing`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Timing
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `timing_delta_0`, `timing_delta_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [("timing_delta", 32)];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in an inter-arrival time.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl TimingHeader {
+    /// Returns a `TimingHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Encodes an inter-arrival delta as the input of `TimingHeader::parse()`.
+///
+/// Deltas are counted in microseconds, saturating after about 71 minutes.
+///
+/// # Arguments
+/// * `delta` - Time elapsed since the previous packet.
+pub(crate) fn encode_delta(delta: Duration) -> [u8; 4] {
+    u32::try_from(delta.as_micros())
+        .unwrap_or(u32::MAX)
+        .to_be_bytes()
+}
+
+#[cfg(test)]
+mod timing_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    #[test]
+    fn test_timing_header_creation() {
+        let timing_header = TimingHeader::new(&encode_delta(Duration::from_micros(0x0102)));
+        let data = timing_header.get_data();
+        assert_eq!(data.len(), 32, "Expected 32 bits in TimingHeader data.");
+        assert_eq!(
+            data[16..],
+            [0., 0., 0., 0., 0., 0., 0., 1., 0., 0., 0., 0., 0., 0., 1., 0.],
+            "Timing header doesn't match expected."
+        );
+    }
+
+    #[test]
+    fn test_encode_delta_saturates() {
+        assert_eq!(encode_delta(Duration::ZERO), [0; 4]);
+        assert_eq!(
+            encode_delta(Duration::from_secs(1)),
+            1_000_000u32.to_be_bytes()
+        );
+        assert_eq!(encode_delta(Duration::from_secs(86400)), [0xff; 4]);
+    }
+
+    #[test]
+    fn test_timing_header_get_headers() {
+        let headers = TimingHeader::get_headers();
+        assert_eq!(headers.len(), 32, "Expected 32 header names.");
+        assert_eq!(headers[0], "timing_delta_0");
+        assert_eq!(headers[31], "timing_delta_31");
+    }
+
+    #[test]
+    fn test_timing_header_bad_header() {
+        let timing_header = TimingHeader::new(&[0x0]);
+        assert!(
+            timing_header.bit_eq(&TimingHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            TimingHeader::new_with_config(&[0x0], &config).bit_eq(&TimingHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+
+/// TLS record content type of handshake messages.
+const HANDSHAKE: u8 = 22;
+/// TLS handshake type of a ClientHello.
+const CLIENT_HELLO: u8 = 1;
+/// Length in bytes of the TLS record header.
+const RECORD_HEADER_LEN: usize = 5;
+/// Length in bytes of the handshake header, its type and length.
+const HANDSHAKE_HEADER_LEN: usize = 4;
+/// Length in bytes of the ClientHello random.
+const RANDOM_LEN: usize = 32;
+/// Maximum number of cipher suites extracted, the following ones are dropped.
+pub(crate) const MAX_CIPHER_SUITES: usize = 32;
+
+/// Implementation of a TLS ClientHello, limited to the fields used by JA3-like fingerprints.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct TlsClientHelloHeader {
+    /// A flat vector of parsed bit values, size of 560 bits as it's the versions, the cipher suites length and up to 32 cipher suites
+    data: Vec<f32>,
+}
+
+impl Default for TlsClientHelloHeader {
+    /// Returns a `TlsClientHelloHeader` filled with 560 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for TlsClientHelloHeader {
+    /// Number of bits extracted, the versions, the cipher suites length and the first
+    /// `MAX_CIPHER_SUITES` cipher suites.
+    const WIDTH: usize = 560;
+
+    /// Constructs a `TlsClientHelloHeader` from a raw bytes TCP payload.
+    ///
+    /// The record version, handshake version, cipher suites length and the first
+    /// `MAX_CIPHER_SUITES` cipher suites are parsed bit by bit, missing suites are filled.
+    /// Returns `None` if the payload isn't a ClientHello record, or is truncated before the
+    /// cipher suites length.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes of a TCP payload.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        if !is_client_hello(packet) {
+            return None;
+        }
+        let hello = packet.get(RECORD_HEADER_LEN + HANDSHAKE_HEADER_LEN..)?;
+        let version = hello.get(..2)?;
+        let session_id_len = *hello.get(2 + RANDOM_LEN)? as usize;
+        let suites = hello.get(3 + RANDOM_LEN + session_id_len..)?;
+        let suites_len = suites.get(..2)?;
+        let len = u16::from_be_bytes([suites_len[0], suites_len[1]]) as usize;
+        let list = &suites[2..];
+        let list = &list[..list.len().min(len).min(MAX_CIPHER_SUITES * 2) / 2 * 2];
+
+        let mut data = BitWriter::new(out);
+        data.extend(bytes_to_bits(&packet[1..3], config.bit_order));
+        data.extend(bytes_to_bits(version, config.bit_order));
+        data.extend(bytes_to_bits(suites_len, config.bit_order));
+        data.extend(bytes_to_bits(list, config.bit_order));
+        data.fill(config.fill_value.value());
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> TlsClientHelloHeader {
+        TlsClientHelloHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::TlsClientHello`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::TlsClientHello
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `tls_rec_ver_0`, `tls_rec_ver_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [
+            ("tls_rec_ver", 16),
+            ("tls_hs_ver", 16),
+            ("tls_cs_len", 16),
+            ("tls_cs", 512),
+        ];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in the versions and cipher suites.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl TlsClientHelloHeader {
+    /// Returns a `TlsClientHelloHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Returns `true` if a TCP payload starts with a TLS handshake record carrying a ClientHello.
+///
+/// # Arguments
+/// * `payload` - Raw bytes of a TCP payload.
+pub(crate) fn is_client_hello(payload: &[u8]) -> bool {
+    payload.first() == Some(&HANDSHAKE) && payload.get(RECORD_HEADER_LEN) == Some(&CLIENT_HELLO)
+}
+
+#[cfg(test)]
+mod tls_client_hello_header_tests {
+    use super::*;
+    use crate::config::BitOrder;
+    use crate::protocols::util::bits_to_bytes;
+
+    /// Returns a ClientHello record offering the given cipher suites, without extensions.
+    fn client_hello(suites: &[u16]) -> Vec<u8> {
+        let mut hello = vec![0x03, 0x03];
+        hello.extend([0xaa; RANDOM_LEN]);
+        hello.extend([0x02, 0xbb, 0xbb]);
+        hello.extend(((suites.len() * 2) as u16).to_be_bytes());
+        hello.extend(suites.iter().flat_map(|suite| suite.to_be_bytes()));
+        hello.extend([0x01, 0x00]);
+
+        let mut record = vec![HANDSHAKE, 0x03, 0x01];
+        record.extend(((hello.len() + HANDSHAKE_HEADER_LEN) as u16).to_be_bytes());
+        record.extend([CLIENT_HELLO, 0x00]);
+        record.extend((hello.len() as u16).to_be_bytes());
+        record.extend(hello);
+        record
+    }
+
+    #[test]
+    fn test_tls_client_hello_header_creation() {
+        let header = TlsClientHelloHeader::new(&client_hello(&[0x1301, 0xc02f]));
+        let data = header.get_data();
+        assert_eq!(
+            data.len(),
+            560,
+            "Expected 560 bits in TlsClientHelloHeader data."
+        );
+        assert_eq!(
+            bits_to_bytes(&data[..80], BitOrder::Msb0),
+            Some(vec![
+                0x03, 0x01, 0x03, 0x03, 0x00, 0x04, 0x13, 0x01, 0xc0, 0x2f
+            ]),
+            "Wrong versions or cipher suites."
+        );
+        assert!(
+            data[80..].iter().all(|bit| *bit == -1.),
+            "Expected the missing cipher suites to be filled."
+        );
+    }
+
+    #[test]
+    fn test_tls_client_hello_header_bounded() {
+        let suites: Vec<u16> = (0..40).collect();
+        let header = TlsClientHelloHeader::new(&client_hello(&suites));
+        let data = header.get_data();
+        assert_eq!(
+            bits_to_bytes(&data[32..48], BitOrder::Msb0),
+            Some(vec![0x00, 80]),
+            "Expected the full cipher suites length."
+        );
+        assert_eq!(
+            bits_to_bytes(&data[544..], BitOrder::Msb0),
+            Some(vec![0x00, 31]),
+            "Expected the list to stop at the 32nd suite."
+        );
+
+        let truncated = client_hello(&[0x1301, 0x1302]);
+        let header = TlsClientHelloHeader::new(&truncated[..truncated.len() - 3]);
+        let data = header.get_data();
+        assert_eq!(
+            bits_to_bytes(&data[48..64], BitOrder::Msb0),
+            Some(vec![0x13, 0x01]),
+            "Expected the complete cipher suites of a truncated list."
+        );
+        assert!(data[64..].iter().all(|bit| *bit == -1.));
+    }
+
+    #[test]
+    fn test_tls_client_hello_header_get_headers() {
+        let headers = TlsClientHelloHeader::get_headers();
+        assert_eq!(headers.len(), 560, "Expected 560 header names.");
+        assert_eq!(headers[0], "tls_rec_ver_0");
+        assert_eq!(headers[16], "tls_hs_ver_0");
+        assert_eq!(headers[32], "tls_cs_len_0");
+        assert_eq!(headers[559], "tls_cs_511");
+    }
+
+    #[test]
+    fn test_tls_client_hello_header_bad_header() {
+        let mut server_hello = client_hello(&[0x1301]);
+        server_hello[RECORD_HEADER_LEN] = 2;
+        let mut application_data = client_hello(&[0x1301]);
+        application_data[0] = 23;
+        for payload in [
+            &server_hello[..],
+            &application_data,
+            &client_hello(&[])[..20],
+        ] {
+            assert!(
+                TlsClientHelloHeader::new(payload).bit_eq(&TlsClientHelloHeader::default()),
+                "Expected data to be default."
+            );
+        }
+        assert!(is_client_hello(&client_hello(&[])));
+        assert!(!is_client_hello(&server_hello));
+    }
+}
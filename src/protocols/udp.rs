@@ -1,6 +1,15 @@
+use crate::config::{BitOrder, NprintConfig};
 use crate::protocols::packet::PacketHeader;
-use pnet::packet::udp::UdpPacket;
+use crate::protocols::util::{bits_to_bytes, bytes_to_bits, remove, BitWriter};
+use crate::ProtocolType;
+use pnet::packet::udp::{ipv4_checksum, ipv6_checksum, UdpPacket};
 use pnet::packet::Packet;
+use pnet::util::checksum;
+use std::net::IpAddr;
+use std::ops::Range;
+
+/// Bit range of the checksum in the UDP data.
+pub(crate) const UDP_CHECKSUM: Range<usize> = 48..64;
 
 /// Implementation of Udp header.
 ///
@@ -13,33 +22,44 @@ pub(crate) struct UdpHeader {
 impl Default for UdpHeader {
     /// Returns an `UdpHeader` filled with 64 "-1"
     fn default() -> Self {
-        Self {
-            data: vec![-1.; 64],
-        }
+        Self::with_fill(-1.)
     }
 }
 
 impl PacketHeader for UdpHeader {
+    /// Number of bits extracted, the length of the UDP header.
+    const WIDTH: usize = 64;
+
     /// Constructs an `UdpHeader` from a raw bytes UDP packet.
     ///
     /// If the input is a valid Udp packet, its fields are parsed bit by bit.
-    /// If the packet is invalid or cannot be parsed, return Default.
+    /// Returns `None` if the packet is invalid or cannot be parsed, in particular if its
+    /// length is below the 8 bytes of the header. A length of 0 is kept for IPv6 jumbograms
+    /// (RFC 2675), and a datagram shorter than its length is parsed, see `udp_truncated()`.
+    ///
+    /// UDP-Lite headers are parsed the same way, their checksum coverage taking the place of the length.
     ///
     /// # Arguments
     /// * `packet` - Raw bytes representing an Udp packet.
-    fn new(packet: &[u8]) -> UdpHeader {
-        if let Some(packet) = UdpPacket::new(packet) {
-            let mut data = Vec::with_capacity(64);
-            let packet = packet.packet();
-            data.extend((0..16).map(|i| ((packet[i / 8] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..16).map(|i| ((packet[2 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..16).map(|i| ((packet[4 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            data.extend((0..16).map(|i| ((packet[6 + (i / 8)] >> (7 - (i % 8))) & 1) as f32));
-            UdpHeader { data }
-        } else {
-            eprintln!("Not an UDP packet, returnin default...");
-            UdpHeader::default()
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let packet = UdpPacket::new(packet)?;
+        if (1..8).contains(&packet.get_length()) {
+            return None;
         }
+        let mut data = BitWriter::new(out);
+        let packet = packet.packet();
+        data.extend(bytes_to_bits(&packet[..2], config.bit_order));
+        data.extend(bytes_to_bits(&packet[2..4], config.bit_order));
+        data.extend(bytes_to_bits(&packet[4..6], config.bit_order));
+        data.extend(bytes_to_bits(&packet[6..8], config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> UdpHeader {
+        UdpHeader::with_fill(config.fill_value.value())
     }
 
     /// Returns a reference to the extracted data, or the default header if the extraction failed.
@@ -47,6 +67,16 @@ impl PacketHeader for UdpHeader {
         &self.data
     }
 
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Udp`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Udp
+    }
+
     /// Returns the name list of all field of the protocols.
     ///
     /// Header names are suffixed with an index (e.g., `udp_sport_0`, `udp_sport_1`).
@@ -65,26 +95,216 @@ impl PacketHeader for UdpHeader {
     }
 
     ///  Anonymize port source and destination
-    fn anonymize(&mut self) {
-        self.remove(0, 15); // Port source
-        self.remove(16, 31); // Port destination
+    fn anonymize_bits(data: &mut [f32]) {
+        remove(data, 0, 15); // Port source
+        remove(data, 16, 31); // Port destination
+    }
+
+    /// Reassembles the 8 bytes of the UDP header.
+    fn reassemble(data: &[f32], order: BitOrder) -> Option<Vec<u8>> {
+        bits_to_bytes(data, order)
     }
 }
 
 impl UdpHeader {
-    /// Remove a given range.
+    /// Returns an `UdpHeader` filled with the given value.
     ///
     /// # Arguments
-    /// * `start` - Starting bit index (inclusive).
-    /// * `end` - Ending bit index (inclusive).
-    pub fn remove(&mut self, start: usize, end: usize) {
-        self.data[start..=end].fill(0.);
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
     }
 }
 
+/// Returns `true` if the checksum of a UDP datagram is valid.
+///
+/// A null checksum, meaning that none was computed, is valid over IPv4 only. Truncated
+/// datagrams, and datagrams whose addresses mix IP versions, are reported invalid.
+///
+/// # Arguments
+/// * `datagram` - Raw bytes of the UDP datagram.
+/// * `source` - Source address of the IP pseudo-header.
+/// * `destination` - Destination address of the IP pseudo-header.
+pub(crate) fn udp_checksum_valid(datagram: &[u8], source: IpAddr, destination: IpAddr) -> bool {
+    let Some(udp) = UdpPacket::new(datagram) else {
+        return false;
+    };
+    let checksum = match (source, destination) {
+        (IpAddr::V4(_), IpAddr::V4(_)) if udp.get_checksum() == 0 => return true,
+        (IpAddr::V4(source), IpAddr::V4(destination)) => ipv4_checksum(&udp, &source, &destination),
+        (IpAddr::V6(source), IpAddr::V6(destination)) => ipv6_checksum(&udp, &source, &destination),
+        _ => return false,
+    };
+    checksum == udp.get_checksum()
+}
+
+/// Returns `true` if the checksum of a UDP-Lite datagram is valid.
+///
+/// Only the bytes announced by the checksum coverage are covered, the whole datagram for
+/// a null coverage. Truncated datagrams, invalid coverages, and datagrams whose addresses
+/// mix IP versions are reported invalid.
+///
+/// # Arguments
+/// * `datagram` - Raw bytes of the UDP-Lite datagram.
+/// * `source` - Source address of the IP pseudo-header.
+/// * `destination` - Destination address of the IP pseudo-header.
+pub(crate) fn udp_lite_checksum_valid(
+    datagram: &[u8],
+    source: IpAddr,
+    destination: IpAddr,
+) -> bool {
+    let Some(udp) = UdpPacket::new(datagram) else {
+        return false;
+    };
+    let covered = match udp.get_length() as usize {
+        0 => datagram.len(),
+        coverage if (8..=datagram.len()).contains(&coverage) => coverage,
+        _ => return false,
+    };
+    let Ok(len) = u16::try_from(datagram.len()) else {
+        return false;
+    };
+    let mut pseudo = match (source, destination) {
+        (IpAddr::V4(source), IpAddr::V4(destination)) => [
+            &source.octets()[..],
+            &destination.octets(),
+            &[0, 136],
+            &len.to_be_bytes(),
+        ]
+        .concat(),
+        (IpAddr::V6(source), IpAddr::V6(destination)) => [
+            &source.octets()[..],
+            &destination.octets(),
+            &u32::from(len).to_be_bytes(),
+            &[0, 0, 0, 136],
+        ]
+        .concat(),
+        _ => return false,
+    };
+    let skipword = pseudo.len() / 2 + 3;
+    pseudo.extend_from_slice(&datagram[..covered]);
+    checksum(&pseudo, skipword) == udp.get_checksum()
+}
+
+/// Returns `true` if a UDP datagram is shorter than the length announced by its header,
+/// e.g. when captured with a snap length.
+///
+/// # Arguments
+/// * `datagram` - Raw bytes of the UDP datagram.
+pub(crate) fn udp_truncated(datagram: &[u8]) -> bool {
+    UdpPacket::new(datagram).is_some_and(|packet| datagram.len() < usize::from(packet.get_length()))
+}
+
 #[cfg(test)]
 mod udp_header_tests {
     use super::*;
+    use crate::config::FillValue;
+
+    #[test]
+    fn test_udp_header_exact_length() {
+        let raw_packet: Vec<u8> = vec![0x30, 0x39, 0x00, 0x35, 0x00, 0x08, 0x00, 0x00];
+        let udp_header = UdpHeader::new(&raw_packet);
+        assert!(
+            !udp_header.bit_eq(&UdpHeader::default()),
+            "Expected a parsed header."
+        );
+        assert_eq!(
+            udp_header.get_data()[40..48],
+            [0., 0., 0., 0., 1., 0., 0., 0.]
+        );
+        assert!(
+            UdpHeader::new(&raw_packet[..7]).bit_eq(&UdpHeader::default()),
+            "Expected a truncated header."
+        );
+    }
+
+    #[test]
+    fn test_udp_header_length_mismatch() {
+        let mut raw_packet: Vec<u8> = vec![0x30, 0x39, 0x00, 0x35, 0x00, 0x04, 0x00, 0x00];
+        assert!(
+            UdpHeader::new(&raw_packet).bit_eq(&UdpHeader::default()),
+            "Expected a length below 8 bytes to be invalid."
+        );
+        raw_packet[4..6].copy_from_slice(&[0x00, 0x00]);
+        assert!(
+            !UdpHeader::new(&raw_packet).bit_eq(&UdpHeader::default()),
+            "Expected a jumbogram length to be parsed."
+        );
+        assert!(!udp_truncated(&raw_packet));
+
+        // 1000 bytes announced over a 20 bytes capture
+        raw_packet[4..6].copy_from_slice(&1000u16.to_be_bytes());
+        raw_packet.extend([0x0; 12]);
+        assert!(!UdpHeader::new(&raw_packet).bit_eq(&UdpHeader::default()));
+        assert!(udp_truncated(&raw_packet), "Expected a truncated datagram.");
+        raw_packet[4..6].copy_from_slice(&20u16.to_be_bytes());
+        assert!(!udp_truncated(&raw_packet));
+        assert!(!udp_truncated(&raw_packet[..6]), "Expected no header.");
+    }
+
+    #[test]
+    fn test_udp_lite_checksum_valid() {
+        let source = IpAddr::from([10, 0, 0, 1]);
+        let destination = IpAddr::from([10, 0, 0, 2]);
+        let mut partial: Vec<u8> = vec![0x30, 0x39, 0x1f, 0x90, 0x00, 0x08, 0x9b, 0x97];
+        partial.extend(b"abcd");
+        assert!(udp_lite_checksum_valid(&partial, source, destination));
+        partial[11] = b'z';
+        assert!(
+            udp_lite_checksum_valid(&partial, source, destination),
+            "Expected uncovered bytes to be ignored"
+        );
+        partial[0] = 0x31;
+        assert!(!udp_lite_checksum_valid(&partial, source, destination));
+
+        let mut full: Vec<u8> = vec![0x30, 0x39, 0x1f, 0x90, 0x00, 0x00, 0xd6, 0xd8];
+        full.extend(b"abcd");
+        assert!(udp_lite_checksum_valid(&full, source, destination));
+        full[4..6].copy_from_slice(&[0x00, 0x04]);
+        assert!(
+            !udp_lite_checksum_valid(&full, source, destination),
+            "Expected a coverage below the header length to be invalid"
+        );
+    }
+
+    #[test]
+    fn test_udp_header_validate() {
+        let raw_packet: Vec<u8> = vec![0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00];
+        let config = NprintConfig::default();
+        assert!(UdpHeader::new(&raw_packet).validate(&config));
+        assert!(UdpHeader::default().validate(&config));
+        let mut invalid = UdpHeader::default();
+        invalid.data.pop();
+        assert!(
+            !invalid.validate(&config),
+            "Expected a missing bit to be detected"
+        );
+    }
+
+    #[test]
+    fn test_udp_checksum_valid() {
+        let mut raw_packet: Vec<u8> = vec![
+            0x45, 0x00, 0x00, 0x1e, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00, 0x0a, 0x00,
+            0x00, 0x01, 0x0a, 0x00, 0x00, 0x02, 0x30, 0x39, 0x00, 0x35, 0x00, 0x0a, 0x00, 0x00,
+            0x61, 0x62,
+        ];
+        let source = IpAddr::from([10, 0, 0, 1]);
+        let destination = IpAddr::from([10, 0, 0, 2]);
+        assert!(
+            udp_checksum_valid(&raw_packet[20..], source, destination),
+            "Expected a null checksum to be valid"
+        );
+        raw_packet[26..28].copy_from_slice(&[0x12, 0x34]);
+        assert!(!udp_checksum_valid(&raw_packet[20..], source, destination));
+        let checksum = {
+            let udp = UdpPacket::new(&raw_packet[20..]).unwrap();
+            ipv4_checksum(&udp, &[10, 0, 0, 1].into(), &[10, 0, 0, 2].into())
+        };
+        raw_packet[26..28].copy_from_slice(&checksum.to_be_bytes());
+        assert!(udp_checksum_valid(&raw_packet[20..], source, destination));
+    }
 
     #[test]
     fn test_udp_header_creation() {
@@ -184,17 +404,26 @@ mod udp_header_tests {
                 i
             );
         }
+        assert_eq!(headers.len(), UdpHeader::WIDTH);
+        assert_eq!(UdpHeader::default().get_data().len(), UdpHeader::WIDTH);
     }
 
     #[test]
     fn test_udp_header_bad_header() {
         let raw_packet: Vec<u8> = vec![0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b];
         let udp_header = UdpHeader::new(&raw_packet);
-        assert_eq!(
-            udp_header,
-            UdpHeader::default(),
+        assert!(
+            udp_header.bit_eq(&UdpHeader::default()),
             "Expected data to be default."
         );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            UdpHeader::new_with_config(&raw_packet, &config).bit_eq(&UdpHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
     }
 
     #[test]
@@ -207,4 +436,12 @@ mod udp_header_tests {
             assert_eq!(*ip_bit, 0., "Expected data bit 0-31 to be 0.");
         }
     }
+
+    #[test]
+    fn test_udp_header_to_bytes() {
+        let raw_packet: Vec<u8> = vec![0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00];
+        let udp_header = UdpHeader::new(&raw_packet);
+        assert_eq!(udp_header.to_bytes(BitOrder::Msb0), Some(raw_packet));
+        assert_eq!(UdpHeader::default().to_bytes(BitOrder::Msb0), None);
+    }
 }
@@ -0,0 +1,365 @@
+//! Helpers shared by the protocol parsers.
+use crate::config::BitOrder;
+use std::ops::Range;
+
+/// Returns a bit of a byte.
+///
+/// # Arguments
+/// * `byte` - Byte to read.
+/// * `i` - Index of the bit, from 0 to 7, counted in the given order.
+/// * `order` - Order of the bits.
+pub(crate) fn bit(byte: u8, i: usize, order: BitOrder) -> f32 {
+    let shift = match order {
+        BitOrder::Msb0 => 7 - i,
+        BitOrder::Lsb0 => i,
+    };
+    ((byte >> shift) & 1) as f32
+}
+
+/// Returns the bits of a byte slice, 8 bits per byte.
+///
+/// # Arguments
+/// * `bytes` - Bytes to read.
+/// * `order` - Order of the bits of every byte.
+pub(crate) fn bytes_to_bits(bytes: &[u8], order: BitOrder) -> impl Iterator<Item = f32> + '_ {
+    bytes
+        .iter()
+        .flat_map(move |byte| (0..8).map(move |i| bit(*byte, i, order)))
+}
+
+/// Number of option bits extracted by default for IPv4 and TCP, the 40 bytes of a header
+/// length of 15 words as in the reference nPrint.
+pub(crate) const DEFAULT_OPTIONS_BITS: usize = 320;
+
+/// Converts raw options bytes into exactly `target_bits` bit values.
+///
+/// Options shorter than `target_bits` are padded with `fill`, longer ones are truncated.
+///
+/// # Arguments
+/// * `options` - Raw bytes of the options.
+/// * `target_bits` - Number of bits returned, whatever the length of the options.
+/// * `fill` - Value of the bits beyond the options.
+/// * `order` - Order of the bits of every byte.
+pub(crate) fn options_to_bits(
+    options: &[u8],
+    target_bits: usize,
+    fill: f32,
+    order: BitOrder,
+) -> impl Iterator<Item = f32> + '_ {
+    let options = &options[..options.len().min(target_bits.div_ceil(8))];
+    bytes_to_bits(options, order)
+        .chain(std::iter::repeat(fill))
+        .take(target_bits)
+}
+
+/// Returns the position of the `i`-th most significant bit of a range of `len` bits.
+///
+/// With `Lsb0`, the range is expected to start on a byte boundary.
+fn position(i: usize, len: usize, order: BitOrder) -> usize {
+    match order {
+        BitOrder::Msb0 => i,
+        BitOrder::Lsb0 => {
+            let start = i / 8 * 8;
+            (start + 8).min(len) - 1 - (i - start)
+        }
+    }
+}
+
+/// Reads an unsigned integer from a range of bits.
+///
+/// # Arguments
+/// * `bits` - Slice of bit values, each one expected to be `0.` or `1.`.
+/// * `order` - Order of the bits of every byte.
+///
+/// # Returns
+/// The value, or `None` if a bit is neither `0.` nor `1.` (e.g. absent field).
+pub(crate) fn bits_to_value(bits: &[f32], order: BitOrder) -> Option<u64> {
+    (0..bits.len()).try_fold(0u64, |value, i| {
+        match bits[position(i, bits.len(), order)] {
+            0. => Some(value << 1),
+            1. => Some((value << 1) | 1),
+            _ => None,
+        }
+    })
+}
+
+/// Writes an unsigned integer into a range of bits.
+///
+/// # Arguments
+/// * `bits` - Slice of bit values to overwrite.
+/// * `value` - Value to write, truncated to `bits.len()` bits.
+/// * `order` - Order of the bits of every byte.
+pub(crate) fn value_to_bits(bits: &mut [f32], value: u64, order: BitOrder) {
+    let len = bits.len();
+    for i in 0..len {
+        bits[position(i, len, order)] = ((value >> (len - 1 - i)) & 1) as f32;
+    }
+}
+
+/// Reassembles bytes from their bits.
+///
+/// # Arguments
+/// * `bits` - Slice of bit values, 8 per byte.
+/// * `order` - Order of the bits of every byte.
+///
+/// # Returns
+/// The bytes, or `None` if a bit is neither `0.` nor `1.` or a byte is incomplete.
+pub(crate) fn bits_to_bytes(bits: &[f32], order: BitOrder) -> Option<Vec<u8>> {
+    if !bits.len().is_multiple_of(8) {
+        return None;
+    }
+    bits.chunks(8)
+        .map(|byte| bits_to_value(byte, order).map(|value| value as u8))
+        .collect()
+}
+
+/// Writes bytes bit by bit into a slice of bit values.
+///
+/// # Arguments
+/// * `bits` - Destination, of 8 bits per byte.
+/// * `bytes` - Raw bytes to write.
+/// * `order` - Order of the bits of every byte.
+pub(crate) fn write_bits(bits: &mut [f32], bytes: &[u8], order: BitOrder) {
+    for (bit, value) in bits.iter_mut().zip(bytes_to_bits(bytes, order)) {
+        *bit = value;
+    }
+}
+
+/// Returns the bit range of a field in a table of fields, from the widths of the fields before it.
+///
+/// Evaluated at compile time for the constant ranges, an unknown field failing the build.
+///
+/// # Arguments
+/// * `fields` - Field names and their number of bits, in header order.
+/// * `name` - Name of the field, as in the table.
+pub(crate) const fn field_range(fields: &[(&str, usize)], name: &str) -> Range<usize> {
+    let mut start = 0;
+    let mut i = 0;
+    while i < fields.len() {
+        let (field, bits) = fields[i];
+        if field.len() == name.len() {
+            let (field, name) = (field.as_bytes(), name.as_bytes());
+            let mut j = 0;
+            while j < field.len() && field[j] == name[j] {
+                j += 1;
+            }
+            if j == field.len() {
+                return start..start + bits;
+            }
+        }
+        start += bits;
+        i += 1;
+    }
+    panic!("unknown field");
+}
+
+/// Zeroes a range of bits, used to remove sensitive fields.
+///
+/// # Arguments
+/// * `bits` - Bit values of a header.
+/// * `start` - Starting bit index (inclusive).
+/// * `end` - Ending bit index (inclusive).
+pub(crate) fn remove(bits: &mut [f32], start: usize, end: usize) {
+    bits[start..=end].fill(0.);
+}
+
+/// Compares two slices of bit values, NaN values at the same position being equal.
+///
+/// Needed with `FillValue::NaN`, as absent fields would otherwise never be equal.
+///
+/// # Arguments
+/// * `left` - Bit values of a header.
+/// * `right` - Bit values of another header.
+#[cfg(test)]
+pub(crate) fn bit_eq(left: &[f32], right: &[f32]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .all(|(l, r)| l == r || (l.is_nan() && r.is_nan()))
+}
+
+/// Updates an internet checksum after some of the bytes it covers changed (RFC 1624).
+///
+/// # Arguments
+/// * `checksum` - Checksum covering the original bytes.
+/// * `original` - Original bytes, aligned on 16-bit words of the checksummed data.
+/// * `updated` - Same bytes after modification, of the same length.
+///
+/// # Returns
+/// The checksum covering the updated bytes.
+pub(crate) fn update_checksum(checksum: u16, original: &[u8], updated: &[u8]) -> u16 {
+    let word = |bytes: &[u8]| u16::from_be_bytes([bytes[0], bytes.get(1).copied().unwrap_or(0)]);
+    let mut sum = u32::from(!checksum);
+    for (old, new) in original.chunks(2).zip(updated.chunks(2)) {
+        sum += u32::from(!word(old)) + u32::from(word(new));
+    }
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// Sequential writer of bit values into the slice of a header.
+///
+/// Writing past the end of the slice panics, as it means a header is wider than its names.
+pub(crate) struct BitWriter<'a> {
+    /// Destination of the bit values.
+    bits: &'a mut [f32],
+    /// Index of the next bit to write.
+    position: usize,
+}
+
+impl<'a> BitWriter<'a> {
+    /// Returns a writer starting at the first bit of `bits`.
+    ///
+    /// # Arguments
+    /// * `bits` - Destination of the bit values.
+    pub fn new(bits: &'a mut [f32]) -> Self {
+        Self { bits, position: 0 }
+    }
+
+    /// Fills the bits that haven't been written yet with the given value.
+    ///
+    /// # Arguments
+    /// * `value` - Value of the remaining bits.
+    pub fn fill(&mut self, value: f32) {
+        self.bits[self.position..].fill(value);
+        self.position = self.bits.len();
+    }
+
+    /// Returns `true` once every bit has been written.
+    pub fn is_full(&self) -> bool {
+        self.position == self.bits.len()
+    }
+}
+
+impl Extend<f32> for BitWriter<'_> {
+    /// Writes the values after the last written bit.
+    fn extend<I: IntoIterator<Item = f32>>(&mut self, values: I) {
+        for value in values {
+            self.bits[self.position] = value;
+            self.position += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod util_tests {
+    use super::*;
+
+    #[test]
+    fn test_field_range() {
+        let fields = [("ver", 4), ("hl", 4), ("tl", 16)];
+        assert_eq!(field_range(&fields, "ver"), 0..4);
+        assert_eq!(field_range(&fields, "tl"), 8..24);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown field")]
+    fn test_field_range_unknown() {
+        field_range(&[("ver", 4)], "ve");
+    }
+
+    #[test]
+    fn test_options_to_bits_empty() {
+        let bits: Vec<f32> = options_to_bits(&[], 320, -1., BitOrder::Msb0).collect();
+        assert_eq!(bits, vec![-1.; 320], "Expected only fill bits");
+    }
+
+    #[test]
+    fn test_options_to_bits_partial() {
+        let bits: Vec<f32> = options_to_bits(&[0x01, 0x01], 320, -1., BitOrder::Msb0).collect();
+        assert_eq!(bits.len(), 320, "Expected exactly 320 options bits");
+        assert_eq!(bits[..8], [0., 0., 0., 0., 0., 0., 0., 1.]);
+        assert!(bits[..16].iter().all(|bit| *bit != -1.));
+        assert!(bits[16..].iter().all(|bit| *bit == -1.));
+    }
+
+    #[test]
+    fn test_options_to_bits_full() {
+        let bits: Vec<f32> = options_to_bits(&[0xff; 40], 320, -1., BitOrder::Msb0).collect();
+        assert_eq!(bits, vec![1.; 320], "Expected the options without fill");
+    }
+
+    #[test]
+    fn test_options_to_bits_overflow() {
+        let bits: Vec<f32> = options_to_bits(&[0xff; 44], 320, -1., BitOrder::Msb0).collect();
+        assert_eq!(bits, vec![1.; 320], "Expected the first 40 bytes");
+        let bits: Vec<f32> = options_to_bits(&[0xff; 2], 12, 0., BitOrder::Msb0).collect();
+        assert_eq!(bits, vec![1.; 12], "Expected a cap within a byte");
+    }
+
+    #[test]
+    fn test_bits_to_value() {
+        assert_eq!(bits_to_value(&[1., 0., 1., 1.], BitOrder::Msb0), Some(11));
+        assert_eq!(bits_to_value(&[], BitOrder::Msb0), Some(0));
+        assert_eq!(bits_to_value(&[1., -1., 1.], BitOrder::Msb0), None);
+    }
+
+    #[test]
+    fn test_value_to_bits() {
+        let mut bits = [-1.; 8];
+        value_to_bits(&mut bits, 0xa5, BitOrder::Msb0);
+        assert_eq!(bits, [1., 0., 1., 0., 0., 1., 0., 1.]);
+        value_to_bits(&mut bits[..4], 0x1f, BitOrder::Msb0);
+        assert_eq!(bits[..4], [1., 1., 1., 1.], "Expected truncation to 4 bits");
+    }
+
+    #[test]
+    fn test_bit_order() {
+        assert_eq!(
+            bytes_to_bits(&[0x01, 0x80], BitOrder::Lsb0).collect::<Vec<_>>(),
+            [1., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1.]
+        );
+        let mut bits = [-1.; 16];
+        value_to_bits(&mut bits, 0x0180, BitOrder::Lsb0);
+        assert_eq!(
+            bits,
+            [1., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1.]
+        );
+        assert_eq!(bits_to_value(&bits, BitOrder::Lsb0), Some(0x0180));
+        assert_eq!(bits_to_value(&bits, BitOrder::Msb0), Some(0x8001));
+    }
+
+    #[test]
+    fn test_bit_writer() {
+        let mut bits = [-1.; 12];
+        let mut writer = BitWriter::new(&mut bits);
+        writer.extend(bytes_to_bits(&[0xa5], BitOrder::Msb0));
+        writer.extend([1.]);
+        writer.fill(0.);
+        assert_eq!(bits, [1., 0., 1., 0., 0., 1., 0., 1., 1., 0., 0., 0.]);
+
+        remove(&mut bits, 0, 3);
+        assert_eq!(bits[..5], [0., 0., 0., 0., 0.]);
+    }
+
+    #[test]
+    fn test_bit_eq() {
+        assert!(bit_eq(&[f32::NAN, 1.], &[f32::NAN, 1.]));
+        assert!(!bit_eq(&[f32::NAN, 1.], &[0., 1.]));
+        assert!(!bit_eq(&[f32::NAN], &[f32::NAN, 1.]));
+        assert!(bit_eq(&[-1.; 4], &[-1.; 4]));
+    }
+
+    #[test]
+    fn test_update_checksum() {
+        let original = [0x45, 0x00, 0x00, 0x3c, 0xc0, 0xa8, 0x2b, 0x25];
+        let updated = [0x45, 0x00, 0x00, 0x3c, 0x00, 0x00, 0x00, 0x00];
+        let checksum = pnet::util::checksum(&original, usize::MAX);
+        assert_eq!(
+            update_checksum(checksum, &original[4..], &updated[4..]),
+            pnet::util::checksum(&updated, usize::MAX)
+        );
+        assert_eq!(update_checksum(checksum, &original, &original), checksum);
+    }
+
+    #[test]
+    fn test_bits_to_bytes() {
+        let bits: Vec<f32> = bytes_to_bits(&[0xa5, 0x01], BitOrder::Lsb0).collect();
+        assert_eq!(bits_to_bytes(&bits, BitOrder::Lsb0), Some(vec![0xa5, 0x01]));
+        assert_eq!(bits_to_bytes(&bits[..12], BitOrder::Msb0), None);
+        assert_eq!(bits_to_bytes(&[-1.; 8], BitOrder::Msb0), None);
+    }
+}
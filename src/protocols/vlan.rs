@@ -0,0 +1,162 @@
+use crate::config::NprintConfig;
+use crate::protocols::packet::PacketHeader;
+use crate::protocols::util::{bytes_to_bits, BitWriter};
+use crate::ProtocolType;
+use pnet::packet::ethernet::{EtherType, EtherTypes};
+use pnet::packet::vlan::VlanPacket;
+use pnet::packet::Packet;
+
+/// Maximum number of stacked VLAN tags popped before giving up on a frame.
+pub(crate) const MAX_VLAN_DEPTH: usize = 4;
+
+/// Implementation of 802.1Q VLAN tag.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct VlanHeader {
+    /// A flat vector of parsed bit values, size of 16 bits as it's the Tag Control Information length
+    data: Vec<f32>,
+}
+
+impl Default for VlanHeader {
+    /// Returns a `VlanHeader` filled with 16 "-1"
+    fn default() -> Self {
+        Self::with_fill(-1.)
+    }
+}
+
+impl PacketHeader for VlanHeader {
+    /// Number of bits extracted, the Tag Control Information.
+    const WIDTH: usize = 16;
+
+    /// Constructs a `VlanHeader` from a raw bytes VLAN tag.
+    ///
+    /// If the input is a valid VLAN tag, its Tag Control Information is parsed bit by bit.
+    /// Returns `None` if the tag is invalid or cannot be parsed.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes representing a VLAN tag, starting after the TPID.
+    /// * `config` - Configuration of the parsing.
+    /// * `out` - Destination of the bits.
+    fn parse_into(packet: &[u8], config: &NprintConfig, out: &mut [f32]) -> Option<()> {
+        let packet = VlanPacket::new(packet)?;
+        let mut data = BitWriter::new(out);
+        let packet = packet.packet();
+        data.extend(bytes_to_bits(&packet[..2], config.bit_order));
+        Some(())
+    }
+
+    /// Returns the header of an absent protocol, filled with the configured fill value.
+    fn absent(config: &NprintConfig) -> VlanHeader {
+        VlanHeader::with_fill(config.fill_value.value())
+    }
+
+    /// Returns a reference to the extracted data, or the default header if the extraction failed.
+    fn get_data(&self) -> &Vec<f32> {
+        &self.data
+    }
+
+    /// Returns a mutable view of the extracted data.
+    fn get_data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    /// Returns `ProtocolType::Vlan`.
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Vlan
+    }
+
+    /// Returns the name list of all field of the protocols.
+    ///
+    /// Header names are suffixed with an index (e.g., `vlan_vid_0`, `vlan_vid_1`).
+    fn get_headers() -> Vec<String> {
+        let fields = [("vlan_pcp", 3), ("vlan_dei", 1), ("vlan_vid", 12)];
+
+        fields
+            .iter()
+            .flat_map(|(name, bits)| (0..*bits).map(move |i| format!("{}_{}", name, i)))
+            .collect()
+    }
+
+    /// Nothing to anonymize in a VLAN tag.
+    fn anonymize_bits(_data: &mut [f32]) {}
+}
+
+impl VlanHeader {
+    /// Returns a `VlanHeader` filled with the given value.
+    ///
+    /// # Arguments
+    /// * `fill` - Value of every bit.
+    pub fn with_fill(fill: f32) -> Self {
+        Self {
+            data: vec![fill; Self::WIDTH],
+        }
+    }
+}
+
+/// Returns `true` if the ethertype announces a VLAN tag (802.1Q, 802.1ad or legacy QinQ).
+///
+/// # Arguments
+/// * `ethertype` - EtherType read before the tag.
+pub(crate) fn is_vlan(ethertype: EtherType) -> bool {
+    matches!(
+        ethertype,
+        EtherTypes::Vlan | EtherTypes::PBridge | EtherTypes::QinQ
+    )
+}
+
+#[cfg(test)]
+mod vlan_header_tests {
+    use super::*;
+    use crate::config::FillValue;
+
+    #[test]
+    fn test_vlan_header_creation() {
+        let raw_packet: Vec<u8> = vec![0x20, 0x45, 0x08, 0x00];
+        let vlan_header = VlanHeader::new(&raw_packet);
+        let vlan_header_test = [
+            0., 0., 1., 0., 0., 0., 0., 0., 0., 1., 0., 0., 0., 1., 0., 1.,
+        ];
+        assert_eq!(
+            *vlan_header.get_data(),
+            vlan_header_test,
+            "VLAN header doesn't match expected."
+        );
+    }
+
+    #[test]
+    fn test_vlan_header_get_headers() {
+        let headers = VlanHeader::get_headers();
+        assert_eq!(headers.len(), 16, "Expected 16 header names.");
+        assert_eq!(headers[0], "vlan_pcp_0");
+        assert_eq!(headers[2], "vlan_pcp_2");
+        assert_eq!(headers[3], "vlan_dei_0");
+        assert_eq!(headers[4], "vlan_vid_0");
+        assert_eq!(headers[15], "vlan_vid_11");
+    }
+
+    #[test]
+    fn test_is_vlan() {
+        assert!(is_vlan(EtherTypes::Vlan));
+        assert!(is_vlan(EtherTypes::PBridge));
+        assert!(is_vlan(EtherTypes::QinQ));
+        assert!(!is_vlan(EtherTypes::Ipv4));
+    }
+
+    #[test]
+    fn test_vlan_header_bad_header() {
+        let raw_packet: Vec<u8> = vec![0x20];
+        let vlan_header = VlanHeader::new(&raw_packet);
+        assert!(
+            vlan_header.bit_eq(&VlanHeader::default()),
+            "Expected data to be default."
+        );
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        assert!(
+            VlanHeader::new_with_config(&raw_packet, &config).bit_eq(&VlanHeader::absent(&config)),
+            "Expected data to be absent with the NaN fill."
+        );
+    }
+}
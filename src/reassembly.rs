@@ -0,0 +1,249 @@
+//! Reassembly of fragmented IPv4 datagrams, so that transport headers are parsed on complete packets.
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use pnet::packet::ipv4::{checksum, Ipv4Flags, Ipv4Packet, MutableIpv4Packet};
+use pnet::packet::Packet;
+
+/// Time after which an incomplete datagram is dropped, same default as Linux `ipfrag_time`.
+pub(crate) const FRAGMENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Identification of the datagram a fragment belongs to: source, destination, id and protocol.
+type FragmentKey = (Ipv4Addr, Ipv4Addr, u16, u8);
+
+/// Fragments received so far for one datagram.
+#[derive(Debug)]
+struct Fragments {
+    /// Timestamp of the first received fragment.
+    first_seen: Duration,
+    /// Bytes preceding the IP payload of the first fragment (link layer and IP header).
+    head: Option<Vec<u8>>,
+    /// Payload of every fragment, indexed by its offset in bytes.
+    parts: BTreeMap<usize, Vec<u8>>,
+    /// Length of the full payload, known once the last fragment is received.
+    total_len: Option<usize>,
+}
+
+/// Buffer reconstructing IPv4 datagrams from their fragments.
+#[derive(Debug, Default)]
+pub(crate) struct Reassembler {
+    /// Datagrams being reassembled.
+    pending: HashMap<FragmentKey, Fragments>,
+}
+
+impl Reassembler {
+    /// Processes a packet and returns the packet to parse, if any.
+    ///
+    /// Unfragmented packets are returned as-is. Fragments are buffered until their
+    /// datagram is complete, then the reassembled packet is returned. Fragments may be
+    /// received out of order or duplicated. Incomplete datagrams older than
+    /// `FRAGMENT_TIMEOUT` are dropped. A first fragment shorter than its header length is
+    /// returned as-is, for the parser to reject it.
+    ///
+    /// # Arguments
+    /// * `packet` - Raw bytes of the packet.
+    /// * `ip_offset` - Offset of the IPv4 header in `packet`.
+    /// * `timestamp` - Capture time of the packet.
+    ///
+    /// # Returns
+    /// The packet to parse, or `None` while its datagram is incomplete.
+    pub fn process<'a>(
+        &mut self,
+        packet: &'a [u8],
+        ip_offset: usize,
+        timestamp: Duration,
+    ) -> Option<Cow<'a, [u8]>> {
+        self.pending.retain(|_, fragments| {
+            timestamp.saturating_sub(fragments.first_seen) <= FRAGMENT_TIMEOUT
+        });
+
+        let Some(ipv4) = packet.get(ip_offset..).and_then(Ipv4Packet::new) else {
+            return Some(Cow::Borrowed(packet));
+        };
+        let more_fragments = ipv4.get_flags() & Ipv4Flags::MoreFragments != 0;
+        let offset = ipv4.get_fragment_offset() as usize * 8;
+        if !more_fragments && offset == 0 {
+            return Some(Cow::Borrowed(packet));
+        }
+
+        let header_len = ipv4.get_header_length() as usize * 4;
+        let payload_len = (ipv4.get_total_length() as usize)
+            .saturating_sub(header_len)
+            .min(ipv4.payload().len());
+        let payload = &ipv4.payload()[..payload_len];
+        let head = match offset {
+            0 => match packet.get(..ip_offset + header_len) {
+                Some(head) => Some(head.to_vec()),
+                None => return Some(Cow::Borrowed(packet)),
+            },
+            _ => None,
+        };
+        let key = (
+            ipv4.get_source(),
+            ipv4.get_destination(),
+            ipv4.get_identification(),
+            ipv4.get_next_level_protocol().0,
+        );
+        let fragments = self.pending.entry(key).or_insert_with(|| Fragments {
+            first_seen: timestamp,
+            head: None,
+            parts: BTreeMap::new(),
+            total_len: None,
+        });
+        if head.is_some() {
+            fragments.head = head;
+        }
+        if !more_fragments {
+            fragments.total_len = Some(offset + payload_len);
+        }
+        fragments.parts.insert(offset, payload.to_vec());
+
+        let payload = fragments.assemble()?;
+        let mut head = self.pending.remove(&key)?.head?;
+        head.extend(payload);
+        finalize(&mut head[ip_offset..]);
+        Some(Cow::Owned(head))
+    }
+}
+
+impl Fragments {
+    /// Returns the full payload if every byte has been received.
+    ///
+    /// Parts starting past the end of the datagram are ignored.
+    fn assemble(&self) -> Option<Vec<u8>> {
+        let total_len = self.total_len?;
+        self.head.as_ref()?;
+        let mut payload = Vec::with_capacity(total_len);
+        for (offset, part) in self.parts.range(..total_len) {
+            if *offset > payload.len() {
+                return None;
+            }
+            let end = (offset + part.len()).min(total_len);
+            if end > payload.len() {
+                payload.extend_from_slice(&part[payload.len() - offset..end - offset]);
+            }
+        }
+        (payload.len() == total_len).then_some(payload)
+    }
+}
+
+/// Updates the IPv4 header of a reassembled datagram: length, fragmentation fields and checksum.
+///
+/// # Arguments
+/// * `datagram` - Raw bytes of the reassembled IPv4 datagram.
+fn finalize(datagram: &mut [u8]) {
+    let total_len = datagram.len().min(u16::MAX as usize) as u16;
+    if let Some(mut ipv4) = MutableIpv4Packet::new(datagram) {
+        ipv4.set_total_length(total_len);
+        ipv4.set_flags(ipv4.get_flags() & !Ipv4Flags::MoreFragments);
+        ipv4.set_fragment_offset(0);
+        ipv4.set_checksum(checksum(&ipv4.to_immutable()));
+    }
+}
+
+#[cfg(test)]
+mod reassembly_tests {
+    use super::*;
+
+    /// Builds an Ethernet frame carrying an IPv4 fragment of a UDP datagram.
+    fn fragment(id: u16, offset: usize, more: bool, payload: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0; 14];
+        packet[12..14].copy_from_slice(&[0x08, 0x00]);
+        let total_len = (20 + payload.len()) as u16;
+        let flags_offset = ((more as u16) << 13) | (offset / 8) as u16;
+        packet.extend([0x45, 0x00]);
+        packet.extend(total_len.to_be_bytes());
+        packet.extend(id.to_be_bytes());
+        packet.extend(flags_offset.to_be_bytes());
+        packet.extend([0x40, 0x11, 0x00, 0x00, 10, 0, 0, 1, 10, 0, 0, 2]);
+        packet.extend(payload);
+        packet
+    }
+
+    /// Returns a UDP datagram of 24 bytes split in three fragments of 8 bytes.
+    fn fragments() -> Vec<Vec<u8>> {
+        let datagram: Vec<u8> = [0x00, 0x35, 0x00, 0x35, 0x00, 0x18, 0x00, 0x00]
+            .into_iter()
+            .chain(0..16)
+            .collect();
+        (0..3)
+            .map(|i| fragment(7, i * 8, i < 2, &datagram[i * 8..(i + 1) * 8]))
+            .collect()
+    }
+
+    #[test]
+    fn test_reassembly_unfragmented() {
+        let mut reassembler = Reassembler::default();
+        let packet = fragment(1, 0, false, &[0; 8]);
+        assert_eq!(
+            reassembler.process(&packet, 14, Duration::ZERO),
+            Some(Cow::Borrowed(&packet[..]))
+        );
+    }
+
+    #[test]
+    fn test_reassembly_out_of_order_and_duplicate() {
+        let mut reassembler = Reassembler::default();
+        let fragments = fragments();
+        assert_eq!(reassembler.process(&fragments[2], 14, Duration::ZERO), None);
+        assert_eq!(reassembler.process(&fragments[0], 14, Duration::ZERO), None);
+        assert_eq!(reassembler.process(&fragments[2], 14, Duration::ZERO), None);
+        let packet = reassembler
+            .process(&fragments[1], 14, Duration::ZERO)
+            .expect("Expected a complete datagram");
+        assert_eq!(packet.len(), 14 + 20 + 24, "Wrong reassembled length");
+        let ipv4 = Ipv4Packet::new(&packet[14..]).unwrap();
+        assert_eq!(ipv4.get_total_length(), 44);
+        assert_eq!(ipv4.get_fragment_offset(), 0);
+        assert_eq!(ipv4.get_flags() & Ipv4Flags::MoreFragments, 0);
+        assert_eq!(ipv4.get_checksum(), checksum(&ipv4), "Wrong checksum");
+        assert_eq!(ipv4.payload()[..2], [0x00, 0x35]);
+        assert_eq!(ipv4.payload()[8..], (0..16).collect::<Vec<u8>>());
+        assert!(reassembler.pending.is_empty());
+    }
+
+    #[test]
+    fn test_reassembly_timeout() {
+        let mut reassembler = Reassembler::default();
+        let fragments = fragments();
+        reassembler.process(&fragments[0], 14, Duration::ZERO);
+        reassembler.process(&fragments[1], 14, Duration::ZERO);
+        let late = FRAGMENT_TIMEOUT + Duration::from_secs(1);
+        assert_eq!(
+            reassembler.process(&fragments[2], 14, late),
+            None,
+            "Expected the incomplete datagram to be dropped"
+        );
+        assert_eq!(reassembler.pending.len(), 1);
+    }
+
+    #[test]
+    fn test_reassembly_truncated_header() {
+        let mut reassembler = Reassembler::default();
+        // First fragment announcing 60 header bytes, holding only 20
+        let mut packet = fragment(3, 0, true, &[]);
+        packet[14] = 0x4f;
+        assert_eq!(
+            reassembler.process(&packet, 14, Duration::ZERO),
+            Some(Cow::Borrowed(&packet[..])),
+            "Expected the fragment to be left to the parser"
+        );
+        assert!(reassembler.pending.is_empty());
+    }
+
+    #[test]
+    fn test_reassembly_stray_fragment() {
+        let mut reassembler = Reassembler::default();
+        let fragments = fragments();
+        let stray = fragment(7, 64, true, &[0; 8]);
+        assert_eq!(reassembler.process(&stray, 14, Duration::ZERO), None);
+        assert_eq!(reassembler.process(&fragments[0], 14, Duration::ZERO), None);
+        assert_eq!(reassembler.process(&fragments[1], 14, Duration::ZERO), None);
+        let packet = reassembler
+            .process(&fragments[2], 14, Duration::ZERO)
+            .expect("Expected the fragment past the end to be ignored");
+        assert_eq!(packet.len(), 14 + 20 + 24, "Wrong reassembled length");
+    }
+}
@@ -0,0 +1,91 @@
+//! Conversion to Apache Arrow, available with the `arrow` feature.
+use crate::Nprint;
+use arrow::array::{ArrayRef, Float32Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Schema metadata key holding `Nprint::schema_version()`.
+pub const SCHEMA_VERSION_KEY: &str = "nprint.schema_version";
+/// Schema metadata key holding `Nprint::schema_hash()`, in hexadecimal.
+pub const SCHEMA_HASH_KEY: &str = "nprint.schema_hash";
+
+impl Nprint {
+    /// Returns the nprint values as a `RecordBatch`, one row per packet.
+    ///
+    /// Every bit is a non-nullable `Float32` column, named after the header names of a packet row.
+    /// Absent fields hold the configured fill value. The schema metadata holds the schema
+    /// version and hash, under `SCHEMA_VERSION_KEY` and `SCHEMA_HASH_KEY`.
+    pub fn to_record_batch(&self) -> RecordBatch {
+        let rows: Vec<Vec<f32>> = self.iter_packets().collect();
+        let fields: Vec<Field> = self
+            .packet_headers()
+            .iter()
+            .map(|name| Field::new(name, DataType::Float32, false))
+            .collect();
+        let columns = (0..fields.len())
+            .map(|i| {
+                Arc::new(Float32Array::from_iter_values(
+                    rows.iter().map(|row| row[i]),
+                )) as ArrayRef
+            })
+            .collect();
+        let metadata = HashMap::from([
+            (
+                SCHEMA_VERSION_KEY.to_string(),
+                Nprint::schema_version().to_string(),
+            ),
+            (
+                SCHEMA_HASH_KEY.to_string(),
+                format!("{:016x}", self.schema_hash()),
+            ),
+        ]);
+        let schema = Schema::new_with_metadata(fields, metadata);
+        let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+        RecordBatch::try_new_with_options(Arc::new(schema), columns, &options)
+            .expect("every column holds one value per packet")
+    }
+}
+
+#[cfg(test)]
+mod record_batch_tests {
+    use super::{SCHEMA_HASH_KEY, SCHEMA_VERSION_KEY};
+    use crate::{Nprint, ProtocolType};
+    use arrow::array::{Array, Float32Array};
+
+    #[test]
+    fn test_to_record_batch() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::Ipv4, ProtocolType::Udp]);
+        nprint.add(&raw_packet);
+        let batch = nprint.to_record_batch();
+        let names: Vec<String> = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect();
+        assert_eq!(names, nprint.get_headers(), "Wrong column names");
+        assert_eq!(batch.num_rows(), nprint.count(), "Wrong row count");
+        assert_eq!(
+            batch.schema().metadata()[SCHEMA_HASH_KEY],
+            format!("{:016x}", nprint.schema_hash())
+        );
+        assert_eq!(batch.schema().metadata()[SCHEMA_VERSION_KEY], "1");
+        let version = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .unwrap();
+        assert_eq!(version.values()[..], [1., 1.], "Expected IPv4 version bit");
+
+        let empty = Nprint::new(&raw_packet, vec![]).to_record_batch();
+        assert_eq!(empty.num_columns(), 0);
+        assert_eq!(empty.num_rows(), 1);
+    }
+}
@@ -0,0 +1,51 @@
+//! Rewriting of TCP sequence and acknowledgment numbers relative to each direction's first one.
+use crate::config::BitOrder;
+use crate::protocols::util::{bits_to_value, value_to_bits};
+
+/// Bit range of the source port in the TCP data.
+const SPORT: std::ops::Range<usize> = 0..16;
+/// Bit range of the sequence number in the TCP data.
+const SEQ: std::ops::Range<usize> = 32..64;
+/// Bit range of the acknowledgment number in the TCP data.
+const ACKN: std::ops::Range<usize> = 64..96;
+
+/// Tracks the first sequence number seen in each direction of a TCP flow.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct SequenceTracker {
+    /// Source port of the first TCP packet, defining the forward direction.
+    first_sport: Option<u64>,
+    /// First sequence number of the forward and backward direction.
+    bases: [Option<u64>; 2],
+}
+
+impl SequenceTracker {
+    /// Rewrites in place the sequence and acknowledgment numbers of a TCP header.
+    ///
+    /// The sequence number becomes an offset from the first one of its direction, and the
+    /// acknowledgment number an offset from the first one of the opposite direction (0 while
+    /// that direction hasn't been seen). Absent headers are left untouched.
+    ///
+    /// # Arguments
+    /// * `tcp` - Bits of a TCP header.
+    /// * `order` - Order of the bits extracted from every byte.
+    pub fn apply(&mut self, tcp: &mut [f32], order: BitOrder) {
+        let (Some(sport), Some(seq), Some(ackn)) = (
+            bits_to_value(&tcp[SPORT], order),
+            bits_to_value(&tcp[SEQ], order),
+            bits_to_value(&tcp[ACKN], order),
+        ) else {
+            return;
+        };
+        let direction = usize::from(*self.first_sport.get_or_insert(sport) != sport);
+        let base = *self.bases[direction].get_or_insert(seq);
+        let relative_ackn = self.bases[1 - direction].map_or(0, |other| wrapping(ackn, other));
+        value_to_bits(&mut tcp[SEQ], wrapping(seq, base), order);
+        value_to_bits(&mut tcp[ACKN], relative_ackn, order);
+    }
+}
+
+/// Returns `value - base` modulo 2^32.
+fn wrapping(value: u64, base: u64) -> u64 {
+    (value as u32).wrapping_sub(base as u32) as u64
+}
@@ -0,0 +1,170 @@
+//! Streaming export, writing every packet as soon as it's parsed.
+use crate::{write_csv_row, Nprint, NprintConfig, ParseStats, ProtocolType};
+use std::io::Write;
+use std::time::Duration;
+
+/// Writer exporting packets as CSV rows one at a time, without retaining them.
+///
+/// The header row is written on creation, then every pushed packet is parsed and written
+/// right away, so that memory stays flat regardless of the flow length. The rows are the
+/// same as the ones of `Nprint::to_csv()`.
+///
+/// # Example
+///
+/// ```
+/// use nprint_rs::{NprintSink, ProtocolType};
+///
+/// let mut sink = NprintSink::new(vec![ProtocolType::Udp], Vec::new()).unwrap();
+/// sink.push(&[0x0]).unwrap();
+/// let csv = String::from_utf8(sink.into_inner()).unwrap();
+/// assert_eq!(csv.lines().count(), 2);
+/// ```
+#[derive(Debug)]
+pub struct NprintSink<W: Write> {
+    /// Parsing state shared by the packets, holding no packet between two pushes.
+    nprint: Nprint,
+    /// Destination of the rows.
+    writer: W,
+}
+
+impl<W: Write> NprintSink<W> {
+    /// Creates a new `NprintSink` and writes the header row.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
+    /// * `writer` - Any destination implementing `std::io::Write`.
+    ///
+    /// # Returns
+    ///
+    /// The sink, or the error raised by the writer.
+    pub fn new(protocols: Vec<ProtocolType>, writer: W) -> std::io::Result<NprintSink<W>> {
+        NprintSink::new_with_config(protocols, NprintConfig::default(), writer)
+    }
+
+    /// Creates a new `NprintSink` like `new()`, parsing the packets following the given configuration.
+    ///
+    /// Once `max_packets` rows are written, the next packets are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocols` - A vector of `ProtocolType` specifying the protocol stack to parse.
+    /// * `config` - A `NprintConfig` applied to every pushed packet.
+    /// * `writer` - Any destination implementing `std::io::Write`.
+    ///
+    /// # Returns
+    ///
+    /// The sink, or the error raised by the writer.
+    pub fn new_with_config(
+        protocols: Vec<ProtocolType>,
+        config: NprintConfig,
+        mut writer: W,
+    ) -> std::io::Result<NprintSink<W>> {
+        let nprint = Nprint::empty_with_config(protocols, config);
+        writeln!(writer, "{}", nprint.packet_headers().join(","))?;
+        Ok(NprintSink { nprint, writer })
+    }
+
+    /// Parses a packet and writes its row.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet data.
+    ///
+    /// # Returns
+    ///
+    /// An `std::io::Result` reporting any error raised by the writer.
+    pub fn push(&mut self, packet: &[u8]) -> std::io::Result<()> {
+        self.nprint.add(packet);
+        self.write_pending()
+    }
+
+    /// Parses a packet captured at the given time and writes its row, see `Nprint::add_with_timestamp()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A byte slice representing the raw packet data.
+    /// * `timestamp` - Capture time of the packet, from any fixed origin.
+    ///
+    /// # Returns
+    ///
+    /// An `std::io::Result` reporting any error raised by the writer.
+    pub fn push_with_timestamp(
+        &mut self,
+        packet: &[u8],
+        timestamp: Duration,
+    ) -> std::io::Result<()> {
+        self.nprint.add_with_timestamp(packet, timestamp);
+        self.write_pending()
+    }
+
+    /// Returns the number of rows written, header excluded.
+    pub fn count(&self) -> usize {
+        self.nprint.count()
+    }
+
+    /// Returns the statistics of the parsing of every pushed packet.
+    pub fn stats(&self) -> &ParseStats {
+        self.nprint.stats()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Writes and drops the packets parsed but not written yet.
+    fn write_pending(&mut self) -> std::io::Result<()> {
+        self.nprint.directions.clear();
+        while let Some(headers) = self.nprint.data.pop_front() {
+            write_csv_row(&mut self.writer, &self.nprint.select(&headers))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod sink_tests {
+    use super::*;
+
+    const PACKET: [u8; 42] = [
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00, 0x00,
+        0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b, 0xac, 0x10,
+        0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00,
+    ];
+
+    #[test]
+    fn test_sink_matches_to_csv() {
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Udp];
+        let mut sink = NprintSink::new(protocols.clone(), Vec::new()).unwrap();
+        let mut nprint = Nprint::new(&PACKET, protocols);
+        sink.push(&PACKET).unwrap();
+        for packet in [&[0x0][..], &PACKET] {
+            sink.push(packet).unwrap();
+            nprint.add(packet);
+        }
+        assert_eq!(sink.count(), 3);
+        assert!(sink.nprint.data.is_empty(), "Expected no retained packet");
+        let csv = String::from_utf8(sink.into_inner()).unwrap();
+        assert_eq!(csv, nprint.to_csv_string());
+    }
+
+    #[test]
+    fn test_sink_max_packets() {
+        let config = NprintConfig {
+            max_packets: Some(1),
+            ..Default::default()
+        };
+        let mut sink =
+            NprintSink::new_with_config(vec![ProtocolType::Udp], config, Vec::new()).unwrap();
+        sink.push(&PACKET).unwrap();
+        sink.push(&PACKET).unwrap();
+        assert_eq!(sink.count(), 1);
+        let csv = String::from_utf8(sink.into_inner()).unwrap();
+        assert_eq!(
+            csv.lines().count(),
+            2,
+            "Expected the header and a single row"
+        );
+    }
+}
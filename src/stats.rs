@@ -0,0 +1,53 @@
+//! Counters of the parsing outcomes, to monitor the quality of the parsed traffic.
+
+/// Statistics accumulated while parsing the packets of an `Nprint`.
+///
+/// Every header that cannot be parsed is counted, and replaced by the fill value in the output.
+/// Nothing is ever written to stdout or stderr, the failures are only reported here.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseStats {
+    /// Number of packets parsed.
+    pub packets: u64,
+    /// Number of packets whose every encountered header was successfully parsed.
+    pub parsed: u64,
+    /// Number of packets too short for their link layer, e.g. not an Ethernet frame.
+    pub non_ethernet: u64,
+    /// Number of frames whose ethertype, after VLAN tags and GRE tunnels, isn't IPv4, IPv6 or ARP.
+    pub unsupported_ethertype: u64,
+    /// Number of truncated IPv4 headers.
+    pub truncated_ipv4: u64,
+    /// Number of truncated TCP headers.
+    pub truncated_tcp: u64,
+    /// Number of truncated UDP headers.
+    pub truncated_udp: u64,
+    /// Number of invalid or truncated headers of any other protocol.
+    pub truncated_other: u64,
+    /// Number of UDP datagrams shorter than their announced length, their header being
+    /// still parsed. Not counted as a failure.
+    pub truncated_udp_payload: u64,
+}
+
+impl ParseStats {
+    /// Returns the number of failures of every kind.
+    pub fn failures(&self) -> u64 {
+        self.non_ethernet
+            + self.unsupported_ethertype
+            + self.truncated_ipv4
+            + self.truncated_tcp
+            + self.truncated_udp
+            + self.truncated_other
+    }
+}
+
+/// Increments `counter` if the header couldn't be parsed, and returns the header.
+///
+/// # Arguments
+/// * `header` - Outcome of a header parsing.
+/// * `counter` - Counter of the failures of this protocol.
+pub(crate) fn counted<T>(header: Option<T>, counter: &mut u64) -> Option<T> {
+    if header.is_none() {
+        *counter += 1;
+    }
+    header
+}
@@ -0,0 +1,77 @@
+//! Scaling of the TCP window size by the window scale negotiated in each direction's SYN.
+use crate::config::{BitOrder, NprintConfig};
+use crate::protocols::tcp::{TcpHeader, SCALED_WINDOW_BITS, TCP_WINDOW};
+use crate::protocols::tcp_options::window_scale;
+use crate::protocols::util::{bits_to_bytes, bits_to_value, value_to_bits};
+
+/// Bit range of the source port in the TCP data.
+const SPORT: std::ops::Range<usize> = 0..16;
+/// Bit range of the data offset in the TCP data.
+const DOFF: std::ops::Range<usize> = 96..100;
+/// Bit range of the flags byte (`tcp_cwr` to `tcp_fin`) in the TCP data.
+const FLAGS: std::ops::Range<usize> = 104..112;
+/// SYN bit of the flags byte.
+const SYN: u64 = 0x02;
+/// Largest shift count allowed by RFC 7323.
+const MAX_SHIFT: u8 = 14;
+
+/// Tracks the window scale announced in the SYN of each direction of a TCP flow.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct WindowScaleTracker {
+    /// Source port of the first TCP packet, defining the forward direction.
+    first_sport: Option<u64>,
+    /// Window scale of the SYN of the forward and backward direction, `Some(None)` for a SYN
+    /// without the option.
+    scales: [Option<Option<u8>>; 2],
+}
+
+impl WindowScaleTracker {
+    /// Writes the effective window of a TCP header into its `tcp_wsize_scaled` bits.
+    ///
+    /// Windows are shifted by the scale of their direction once both directions announced
+    /// one in their SYN (RFC 7323), and kept as is otherwise, as well as in SYN segments.
+    /// Absent headers are left untouched.
+    ///
+    /// # Arguments
+    /// * `tcp` - Bits of a TCP header, ending with `tcp_wsize_scaled`.
+    /// * `config` - Configuration of the parsing, setting the bit order and options width.
+    pub fn apply(&mut self, tcp: &mut [f32], config: &NprintConfig) {
+        let order = config.bit_order;
+        let (Some(sport), Some(flags), Some(window)) = (
+            bits_to_value(&tcp[SPORT], order),
+            bits_to_value(&tcp[FLAGS], order),
+            bits_to_value(&tcp[TCP_WINDOW], order),
+        ) else {
+            return;
+        };
+        let direction = usize::from(*self.first_sport.get_or_insert(sport) != sport);
+        let shift = if flags & SYN != 0 {
+            let options = &tcp[TcpHeader::options_range(config)];
+            self.scales[direction] = Some(syn_scale(options, tcp, order));
+            0
+        } else {
+            match self.scales {
+                [Some(Some(forward)), Some(Some(backward))] => {
+                    [forward, backward][direction].min(MAX_SHIFT)
+                }
+                _ => 0,
+            }
+        };
+        let scaled = tcp.len() - SCALED_WINDOW_BITS;
+        value_to_bits(&mut tcp[scaled..], window << shift, order);
+    }
+}
+
+/// Returns the window scale announced in the options of a SYN, if any.
+///
+/// # Arguments
+/// * `options` - Bits of the options of the TCP header.
+/// * `tcp` - Bits of the TCP header.
+/// * `order` - Order of the bits extracted from every byte.
+fn syn_scale(options: &[f32], tcp: &[f32], order: BitOrder) -> Option<u8> {
+    let header_len = bits_to_value(&tcp[DOFF], order)? as usize * 4;
+    let options_len = (header_len * 8).saturating_sub(TcpHeader::FIXED_WIDTH);
+    let options = &options[..options_len.min(options.len()) / 8 * 8];
+    window_scale(&bits_to_bytes(options, order)?)
+}
@@ -1,7 +1,12 @@
 #[cfg(test)]
 mod nprint_tests {
-    use nprint_rs::Nprint;
     use nprint_rs::ProtocolType;
+    use nprint_rs::{
+        AnonField, BitOrder, Direction, FillValue, FlowKey, Headers, Layout, LinkLayer, Nprint,
+        NprintBuilder, NprintConfig, NprintError, PayloadSource, TcpFlagsEncoding,
+        TruncationPolicy,
+    };
+    use std::time::Duration;
 
     #[test]
     fn test_nprint_creation_ipv4() {
@@ -271,6 +276,2736 @@ mod nprint_tests {
         );
     }
 
+    #[test]
+    fn test_nprint_to_csv() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00, 0x53, 0x70,
+            0x6f, 0x74, 0x55, 0x64, 0x70, 0x30, 0x9e, 0x61, 0x42, 0x3d, 0x11, 0x99, 0x99, 0xee,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::Ipv4, ProtocolType::Udp]);
+        nprint.add(&raw_packet);
+        let csv = nprint.to_csv_string();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines.len(),
+            3,
+            "Expected a header row and one row per packet"
+        );
+        assert_eq!(
+            lines[0],
+            [&HEADER_IP[..], &HEADER_UDP[..]].concat().join(","),
+            "Header row mismatch"
+        );
+        for row in &lines[1..] {
+            let values: Vec<f32> = row.split(',').map(|v| v.parse().unwrap()).collect();
+            assert_eq!(
+                values.len(),
+                nprint.get_headers().len(),
+                "Row width mismatch"
+            );
+            assert_eq!(
+                values,
+                nprint.print()[..values.len()],
+                "Row values mismatch"
+            );
+        }
+        assert!(
+            lines[1].contains(",-1,"),
+            "Absent fields should be written as -1"
+        );
+    }
+
+    #[test]
+    fn test_nprint_fill_value() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let config = NprintConfig {
+            fill_value: FillValue::Custom(0.5),
+            ..Default::default()
+        };
+        let nprint = Nprint::new_with_config(
+            &raw_packet,
+            vec![ProtocolType::Ipv4, ProtocolType::Udp],
+            config,
+        );
+        let output = nprint.print();
+        assert_eq!(output.len(), 544, "Wrong output width");
+        assert!(
+            output[..160].iter().all(|bit| *bit == 0. || *bit == 1.),
+            "Expected IPv4 fixed fields to be parsed"
+        );
+        assert!(
+            output[160..].iter().all(|bit| *bit == 0.5),
+            "Expected options padding and absent UDP to use the fill value"
+        );
+        assert!(
+            Nprint::new(&raw_packet, vec![ProtocolType::Udp])
+                .print()
+                .iter()
+                .all(|bit| *bit == -1.),
+            "Expected -1 when no config is passed"
+        );
+
+        let config = NprintConfig {
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        let nprint = Nprint::new_with_config(&raw_packet, vec![ProtocolType::Udp], config);
+        assert!(
+            nprint.print().iter().all(|bit| bit.is_nan()),
+            "Expected NaN for the absent UDP header"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_nprint_serde_round_trip() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let mut nprint = Nprint::new(
+            &raw_packet,
+            vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp],
+        );
+        nprint.add(&raw_packet);
+        let json = serde_json::to_string(&nprint).unwrap();
+        let restored: Nprint = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.print(), nprint.print(), "Round trip changed data");
+        assert_eq!(restored.count(), nprint.count(), "Wrong number of packet!");
+        assert_eq!(
+            restored.get_headers(),
+            nprint.get_headers(),
+            "Headers names mismatch"
+        );
+
+        // Packets added after a reload continue the flow
+        let restore = |nprint: &Nprint| -> Nprint {
+            serde_json::from_str(&serde_json::to_string(nprint).unwrap()).unwrap()
+        };
+        let mut next = raw_packet.clone();
+        next[38..42].copy_from_slice(&(0x962e_5e0b_u32 + 1460).to_be_bytes());
+        let mut relative = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Tcp])
+            .relative_seq(true)
+            .build_from(&raw_packet);
+        let mut restored = restore(&relative);
+        relative.add(&next);
+        restored.add(&next);
+        assert_eq!(
+            restored.print(),
+            relative.print(),
+            "Expected the first sequence number to be kept"
+        );
+
+        // SYN of the server, announcing a window scale too
+        let mut reply = raw_packet.clone();
+        reply[26..30].copy_from_slice(&raw_packet[30..34]);
+        reply[30..34].copy_from_slice(&raw_packet[26..30]);
+        reply[34..36].copy_from_slice(&raw_packet[36..38]);
+        reply[36..38].copy_from_slice(&raw_packet[34..36]);
+        reply[47] = 0x12;
+        let mut ack = raw_packet.clone();
+        ack[47] = 0x10;
+        let mut scaled = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Tcp])
+            .scale_window(true)
+            .build_from(&raw_packet);
+        scaled.add(&reply);
+        let mut restored = restore(&scaled);
+        scaled.add(&ack);
+        restored.add(&ack);
+        assert_eq!(
+            restored.print(),
+            scaled.print(),
+            "Expected the window scales to be kept"
+        );
+        let ack_row = &scaled.print()[2 * 510..];
+        assert_ne!(
+            ack_row[510 - 16..],
+            ack_row[112..128],
+            "Expected a scaled window"
+        );
+
+        let mut timed = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Timing])
+            .build();
+        timed.add_with_timestamp(&raw_packet, Duration::from_secs(1));
+        let mut restored = restore(&timed);
+        timed.add_with_timestamp(&raw_packet, Duration::from_secs(3));
+        restored.add_with_timestamp(&raw_packet, Duration::from_secs(3));
+        assert_eq!(
+            restored.print(),
+            timed.print(),
+            "Expected the inter-arrival time from the last packet"
+        );
+    }
+
+    #[test]
+    fn test_nprint_vlan() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x81, 0x00, 0x20, 0x45,
+            0x08, 0x00, 0x45, 0x00, 0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d,
+            0xac, 0x10, 0x0c, 0x9b, 0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34,
+            0x85, 0x00,
+        ];
+        let nprint = Nprint::new(&raw_packet, vec![ProtocolType::Vlan, ProtocolType::Udp]);
+        let output = nprint.print();
+        assert_eq!(
+            output[..16],
+            [0., 0., 1., 0., 0., 0., 0., 0., 0., 1., 0., 0., 0., 1., 0., 1.],
+            "VLAN tag doesn't match expected."
+        );
+        assert_eq!(
+            output[16..32],
+            [1., 1., 1., 0., 0., 0., 0., 1., 0., 0., 0., 1., 0., 1., 0., 1.],
+            "Expected the inner UDP to still be parsed."
+        );
+
+        let untagged = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00,
+        ];
+        let nprint = Nprint::new(&untagged, vec![ProtocolType::Vlan]);
+        assert_eq!(nprint.print(), [-1.; 16], "Expected default VLAN tag");
+    }
+
+    #[test]
+    fn test_nprint_qinq() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x88, 0xa8, 0x00, 0x64,
+            0x81, 0x00, 0x20, 0x45, 0x08, 0x00, 0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00,
+            0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4,
+            0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00, 0x00, 0x00, 0xa0, 0x02, 0x72, 0x10,
+            0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2,
+            0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x03, 0x07,
+        ];
+        let nprint = Nprint::new(&raw_packet, vec![ProtocolType::Vlan, ProtocolType::Tcp]);
+        let output = nprint.print();
+        assert_eq!(
+            output[..16],
+            [0., 0., 0., 0., 0., 0., 0., 0., 0., 1., 1., 0., 0., 1., 0., 0.],
+            "Expected the outer service tag."
+        );
+        assert_eq!(
+            output[16..48],
+            [
+                1., 0., 0., 1., 0., 1., 1., 1., 1., 0., 1., 0., 0., 1., 0., 0., 0., 0., 0., 0., 0.,
+                0., 0., 1., 1., 0., 1., 1., 1., 0., 1., 1.
+            ],
+            "Expected the inner TCP ports to be parsed."
+        );
+    }
+
+    #[test]
+    fn test_nprint_ethernet_anonymize() {
+        let raw_packet = vec![
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0xff, 0xee, 0xdd, 0xcc, 0xbb, 0xaa, 0x81, 0x00,
+            0x20, 0x45, 0x08, 0x00, 0x45, 0x00, 0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11,
+            0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b, 0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15,
+            0x00, 0x34, 0x85, 0x00,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::Ethernet]);
+        let output = nprint.print();
+        assert_eq!(output.len(), 112, "Wrong output width");
+        assert_eq!(
+            output[96..],
+            [1., 0., 0., 0., 0., 0., 0., 1., 0., 0., 0., 0., 0., 0., 0., 0.],
+            "Expected the ethertype before VLAN popping"
+        );
+        nprint.anonymize();
+        let output = nprint.print();
+        assert!(
+            output[..96].iter().all(|bit| *bit == 0.),
+            "MACs not anonymized"
+        );
+        assert_eq!(output[96], 1., "Ethertype should be kept");
+    }
+
+    #[test]
+    fn test_nprint_packet_data() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::Ipv4, ProtocolType::Udp]);
+        nprint.add(&[0x0]);
+        let output = nprint.print();
+        assert_eq!(
+            nprint.packet_data(0).unwrap(),
+            output[..544],
+            "First packet mismatch"
+        );
+        assert_eq!(
+            nprint.packet_data(1).unwrap(),
+            output[544..],
+            "Second packet mismatch"
+        );
+        assert_eq!(nprint.packet_data(2), None, "Expected None out of range");
+        let packets: Vec<Vec<f32>> = nprint.iter_packets().collect();
+        assert_eq!(packets.len(), 2, "Wrong number of packet!");
+        assert_eq!(packets.concat(), output, "Iterator doesn't match print()");
+    }
+
+    #[test]
+    fn test_nprint_dns() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x30, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0x08, 0x08, 0x08, 0x08, 0xe1, 0x15, 0x00, 0x35, 0x00, 0x1c, 0x85, 0x00, 0xab, 0xcd,
+            0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x65, 0x78, 0x61,
+        ];
+        let nprint = Nprint::new(&raw_packet, vec![ProtocolType::Dns]);
+        let output = nprint.print();
+        assert_eq!(output.len(), 96, "Wrong output width");
+        assert_eq!(
+            output[..16],
+            [1., 0., 1., 0., 1., 0., 1., 1., 1., 1., 0., 0., 1., 1., 0., 1.],
+            "DNS id doesn't match expected."
+        );
+        assert_eq!(output[23], 1., "Expected RD flag to be set");
+
+        let not_dns = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00, 0x53, 0x70,
+            0x6f, 0x74, 0x55, 0x64, 0x70, 0x30, 0x9e, 0x61, 0x42, 0x3d, 0x11, 0x99, 0x99, 0xee,
+        ];
+        let nprint = Nprint::new(&not_dns, vec![ProtocolType::Dns]);
+        assert_eq!(nprint.print(), [-1.; 96], "Expected default DNS header");
+    }
+
+    #[test]
+    fn test_nprint_builder() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00,
+        ];
+        let mut nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Ipv4, ProtocolType::Udp])
+            .fill_value(FillValue::Zero)
+            .max_packets(2)
+            .anonymize(true)
+            .build_from(&raw_packet);
+        nprint.add(&raw_packet);
+        nprint.add(&raw_packet);
+        assert_eq!(
+            nprint.count(),
+            2,
+            "Expected the packets beyond the cap to be ignored"
+        );
+        for packet in nprint.iter_packets() {
+            assert!(
+                packet[96..160].iter().all(|bit| *bit == 0.),
+                "IPs not anonymized"
+            );
+            assert!(
+                packet[480..512].iter().all(|bit| *bit == 0.),
+                "Ports not anonymized"
+            );
+            assert!(
+                packet[160..480].iter().all(|bit| *bit == 0.),
+                "Expected zero padding"
+            );
+        }
+    }
+
+    #[test]
+    fn test_nprint_print_matrix() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::Udp]);
+        nprint.add(&raw_packet);
+        let matrix = nprint.print_matrix(3);
+        assert_eq!(matrix.len(), 3, "Wrong number of rows");
+        assert_eq!(
+            matrix[..2].concat(),
+            nprint.print(),
+            "Rows don't match print()"
+        );
+        assert_eq!(matrix[2], [-1.; 64], "Expected a padding row");
+        assert_eq!(nprint.print_matrix(1).len(), 1, "Expected truncation");
+    }
+
+    #[test]
+    fn test_nprint_arp() {
+        let raw_packet = vec![
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x08, 0x06,
+            0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55,
+            0xc0, 0xa8, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0xa8, 0x01, 0x02,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::Arp, ProtocolType::Ipv4]);
+        let output = nprint.print();
+        assert_eq!(output.len(), 704, "Wrong output width");
+        assert_eq!(output[15], 1., "Expected ARP hardware type");
+        assert_eq!(output[63], 1., "Expected ARP request operation");
+        assert!(
+            output[224..].iter().all(|bit| *bit == -1.),
+            "Expected default IPv4"
+        );
+        nprint.anonymize();
+        assert!(
+            nprint.print()[64..224].iter().all(|bit| *bit == 0.),
+            "ARP addresses not anonymized"
+        );
+    }
+
+    #[test]
+    fn test_nprint_relative_seq() {
+        let first = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        // Same direction, 1460 bytes further
+        let mut second = first.clone();
+        second[38..42].copy_from_slice(&(0x962e5e0bu32 + 1460).to_be_bytes());
+        let mut nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Tcp])
+            .relative_seq(true)
+            .build_from(&first);
+        nprint.add(&second);
+        let seq_offsets: Vec<u32> = nprint
+            .iter_packets()
+            .map(|packet| {
+                packet[32..64]
+                    .iter()
+                    .fold(0, |value, bit| (value << 1) | *bit as u32)
+            })
+            .collect();
+        assert_eq!(
+            seq_offsets,
+            vec![0, 1460],
+            "Wrong relative sequence numbers"
+        );
+    }
+
+    #[test]
+    fn test_nprint_sctp() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x30, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x84, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0x0b, 0x59, 0x0b, 0x59, 0x12, 0x34, 0x56, 0x78, 0xde, 0xad,
+            0xbe, 0xef, 0x01, 0x00, 0x00, 0x04,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::Sctp, ProtocolType::Udp]);
+        let output = nprint.print();
+        assert_eq!(output.len(), 160, "Wrong output width");
+        assert_eq!(
+            output[..16],
+            [0., 0., 0., 0., 1., 0., 1., 1., 0., 1., 0., 1., 1., 0., 0., 1.],
+            "SCTP source port doesn't match expected."
+        );
+        assert!(
+            output[96..].iter().all(|bit| *bit == -1.),
+            "Expected default UDP"
+        );
+        nprint.anonymize();
+        assert!(
+            nprint.print()[..32].iter().all(|bit| *bit == 0.),
+            "SCTP ports not anonymized"
+        );
+    }
+
+    #[test]
+    fn test_nprint_bits_per_packet() {
+        let protocols = vec![
+            ProtocolType::Ethernet,
+            ProtocolType::Vlan,
+            ProtocolType::Arp,
+            ProtocolType::Ipv4,
+            ProtocolType::Tcp,
+            ProtocolType::Udp,
+            ProtocolType::Sctp,
+            ProtocolType::Dns,
+            ProtocolType::Gre,
+            ProtocolType::Icmpv6,
+            ProtocolType::Payload,
+            ProtocolType::Quic,
+            ProtocolType::TcpOptions,
+            ProtocolType::Timing,
+            ProtocolType::Dhcp,
+            ProtocolType::Mpls,
+            ProtocolType::TlsClientHello,
+        ];
+        for proto in &protocols {
+            let nprint = Nprint::new(&[0x0], vec![*proto]);
+            assert_eq!(
+                proto.width(),
+                nprint.get_headers().len(),
+                "Wrong width for {:?}",
+                proto
+            );
+        }
+        let mut nprint = Nprint::new(&[0x0], protocols);
+        nprint.add(&[0x0]);
+        assert_eq!(nprint.bits_per_packet(), 3102 + 1514 * 8);
+        assert_eq!(
+            nprint.print().len(),
+            nprint.bits_per_packet() * nprint.count()
+        );
+    }
+
+    #[test]
+    fn test_nprint_gre() {
+        let inner = [
+            0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8,
+            0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b,
+            0x00, 0x00, 0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04,
+            0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x03, 0x03, 0x07,
+        ];
+        let mut plain = vec![0x0; 12];
+        plain.extend([0x08, 0x00]);
+        plain.extend(inner);
+        // Ethernet, outer IPv4 carrying GRE, GRE with a key, then the inner packet
+        let mut tunneled = vec![0x0; 12];
+        tunneled.extend([0x08, 0x00]);
+        tunneled.extend([
+            0x45, 0x00, 0x00, 0x58, 0x00, 0x01, 0x00, 0x00, 0x40, 0x2f, 0x00, 0x00, 0x0a, 0x00,
+            0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+        ]);
+        tunneled.extend([0x20, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x2a]);
+        tunneled.extend(inner);
+
+        let protocols = vec![ProtocolType::Gre, ProtocolType::Ipv4, ProtocolType::Tcp];
+        let nprint = Nprint::new(&tunneled, protocols);
+        let output = nprint.print();
+        assert_eq!(output[2], 1., "Expected the GRE key bit");
+        assert_eq!(
+            output[32..],
+            Nprint::new(&plain, vec![ProtocolType::Ipv4, ProtocolType::Tcp]).print(),
+            "Expected the inner IPv4 and TCP headers"
+        );
+    }
+
+    #[test]
+    fn test_nprint_truncation_drop_oldest() {
+        let packets: Vec<Vec<u8>> = (0..4u8)
+            .map(|i| {
+                vec![
+                    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45,
+                    0x00, 0x00, 0x1c, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10,
+                    0x0c, 0x9b, 0xac, 0x10, 0x1f, 0xff, 0xe1, i, 0xe1, 0x15, 0x00, 0x08, 0x85,
+                    0x00,
+                ]
+            })
+            .collect();
+        let mut nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Udp])
+            .max_packets(2)
+            .truncation(TruncationPolicy::DropOldest)
+            .build_from(&packets[0]);
+        for packet in &packets[1..] {
+            nprint.add(packet);
+        }
+        assert_eq!(nprint.count(), 2, "Wrong number of packet!");
+        assert_eq!(
+            nprint.print(),
+            Nprint::new(&packets[2], vec![ProtocolType::Udp])
+                .print()
+                .into_iter()
+                .chain(Nprint::new(&packets[3], vec![ProtocolType::Udp]).print())
+                .collect::<Vec<f32>>(),
+            "Expected the last two packets"
+        );
+
+        let empty = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Udp])
+            .max_packets(0)
+            .truncation(TruncationPolicy::DropOldest)
+            .build_from(&packets[0]);
+        assert_eq!(empty.count(), 0, "Expected no packet with a cap of 0");
+    }
+
+    #[test]
+    fn test_nprint_icmpv6() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x86, 0xdd, 0x60, 0x00,
+            0x00, 0x00, 0x00, 0x10, 0x3a, 0xff, 0xfe, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0xff, 0x02, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x86, 0x00, 0x12, 0x34,
+            0x40, 0x00, 0x07, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let nprint = Nprint::new(&raw_packet, vec![ProtocolType::Icmpv6, ProtocolType::Ipv4]);
+        let output = nprint.print();
+        assert_eq!(output.len(), 544, "Wrong output width");
+        assert_eq!(
+            output[..8],
+            [1., 0., 0., 0., 0., 1., 1., 0.],
+            "Expected a router advertisement"
+        );
+        assert!(
+            output[64..].iter().all(|bit| *bit == -1.),
+            "Expected default IPv4"
+        );
+    }
+
+    #[test]
+    fn test_nprint_anonymize_with_key() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x1c, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x08, 0x85, 0x00,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Udp];
+        let mut first = Nprint::new(&raw_packet, protocols.clone());
+        let mut second = Nprint::new(&raw_packet, protocols);
+        first.anonymize_with_key(&[42; 16]);
+        second.anonymize_with_key(&[42; 16]);
+        let output = first.print();
+        assert_eq!(output, second.print(), "Expected the same pseudonyms");
+        assert_eq!(
+            output[96..112],
+            output[128..144],
+            "Expected the shared /16 prefix to be kept"
+        );
+        assert_ne!(output[112..128], output[144..160]);
+        assert!(
+            output[480..512].iter().all(|bit| *bit == 0.),
+            "Ports not anonymized"
+        );
+    }
+
+    #[test]
+    fn test_nprint_select_fields() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Ipv4, ProtocolType::Tcp])
+            .select_fields(&["tcp_syn", "tcp_wsize", "ipv4_ttl"])
+            .build_from(&raw_packet);
+        let headers = nprint.get_headers();
+        assert_eq!(nprint.bits_per_packet(), 25, "Wrong selected width");
+        assert_eq!(headers.len(), 25);
+        assert_eq!(headers[0], "ipv4_ttl_0");
+        assert_eq!(headers[8], "tcp_syn_0");
+        assert_eq!(headers[24], "tcp_wsize_15");
+
+        let full = Nprint::new(&raw_packet, vec![ProtocolType::Ipv4, ProtocolType::Tcp]);
+        let full_output = full.print();
+        let expected: Vec<f32> = full
+            .get_headers()
+            .iter()
+            .zip(full_output)
+            .filter(|(name, _)| headers.contains(name))
+            .map(|(_, value)| value)
+            .collect();
+        assert_eq!(nprint.print(), expected, "Selected values don't match");
+        assert_eq!(nprint.print()[8], 1., "Expected SYN flag");
+    }
+
+    #[test]
+    fn test_nprint_payload() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00, 0x53, 0x70,
+            0x6f, 0x74, 0x55, 0x64, 0x70, 0x30, 0x9e, 0x61, 0x42, 0x3d, 0x11, 0x99, 0x99, 0xee,
+        ];
+        let nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Udp, ProtocolType::Payload])
+            .max_payload_bytes(16)
+            .payload_offset(2)
+            .build_from(&raw_packet);
+        let output = nprint.print();
+        assert_eq!(output.len(), 64 + 128, "Expected a 128-bit payload");
+        assert_eq!(nprint.bits_per_packet(), output.len());
+        assert_eq!(nprint.get_headers().len(), output.len());
+        assert_eq!(nprint.get_headers()[64], "payload_0");
+        assert_eq!(
+            output[64..72],
+            [0., 1., 1., 0., 1., 1., 1., 1.],
+            "Expected the payload to start after the offset"
+        );
+        assert!(
+            output[64 + 112..].iter().all(|bit| *bit == -1.),
+            "Expected padding after the 14 remaining bytes"
+        );
+    }
+
+    #[test]
+    fn test_nprint_quic() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x30, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0x08, 0x08, 0x08, 0x08, 0xe1, 0x15, 0x01, 0xbb, 0x00, 0x1c, 0x85, 0x00, 0xc3, 0x00,
+            0x00, 0x00, 0x01, 0x08, 0x83, 0x94, 0xc8, 0xf0, 0x3e, 0x51, 0x57, 0x08, 0x00, 0x00,
+        ];
+        let nprint = Nprint::new(&raw_packet, vec![ProtocolType::Quic, ProtocolType::Dns]);
+        let output = nprint.print();
+        assert_eq!(output.len(), 373 + 96, "Wrong output width");
+        assert_eq!(output[..2], [1., 1.], "Expected a long header");
+        assert_eq!(output[36], 1., "Expected QUIC version 1");
+        assert!(
+            output[373..].iter().all(|bit| *bit == -1.),
+            "Expected default DNS"
+        );
+    }
+
+    #[test]
+    fn test_nprint_tcp_options() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let nprint = Nprint::new(
+            &raw_packet,
+            vec![ProtocolType::Tcp, ProtocolType::TcpOptions],
+        );
+        let output = nprint.print();
+        let headers = nprint.get_headers();
+        assert_eq!(output.len(), 480 + 89, "Wrong output width");
+        assert_eq!(headers[480], "tcp_mss_0");
+        assert_eq!(
+            output[480..496],
+            [0., 0., 0., 0., 0., 1., 0., 1., 1., 0., 1., 1., 0., 1., 0., 0.],
+            "Expected a MSS of 1460"
+        );
+        assert_eq!(output[480 + 23], 1., "Expected a window scale of 7");
+        assert_eq!(output[480 + 24], 1., "Expected SACK permitted");
+        assert!(
+            output[480 + 57..].iter().all(|bit| *bit == 0.),
+            "Expected a null TSecr"
+        );
+    }
+
+    #[test]
+    fn test_nprint_stats() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x60, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+        ];
+        let mut lldp = vec![0x0; 14];
+        lldp[12..14].copy_from_slice(&[0x88, 0xcc]);
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::Tcp]);
+        nprint.add(&raw_packet[..40]);
+        nprint.add(&[0x0]);
+        nprint.add(&lldp);
+        let stats = nprint.stats();
+        assert_eq!(stats.packets, 4);
+        assert_eq!(stats.parsed, 1, "Expected a single clean packet");
+        assert_eq!(stats.truncated_tcp, 1);
+        assert_eq!(stats.non_ethernet, 1);
+        assert_eq!(stats.unsupported_ethertype, 1);
+        assert_eq!(stats.failures(), 3);
+        assert!(
+            nprint.print()[480..].iter().all(|bit| *bit == -1.),
+            "Expected the truncated TCP header to be filled"
+        );
+    }
+
+    #[test]
+    fn test_nprint_bit_order() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let msb = Nprint::new(&raw_packet, vec![ProtocolType::Ipv4, ProtocolType::Tcp]);
+        let lsb = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Ipv4, ProtocolType::Tcp])
+            .bit_order(BitOrder::Lsb0)
+            .build_from(&raw_packet);
+        assert_eq!(
+            lsb.print()[..8],
+            [1., 0., 1., 0., 0., 0., 1., 0.],
+            "Expected the IHL bits first"
+        );
+        let reversed: Vec<f32> = msb.print()[..160]
+            .chunks(8)
+            .flat_map(|byte| byte.iter().rev().copied())
+            .collect();
+        assert_eq!(lsb.print()[..160], reversed, "Expected reversed bytes");
+
+        let relative = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Tcp])
+            .bit_order(BitOrder::Lsb0)
+            .relative_seq(true)
+            .build_from(&raw_packet);
+        assert!(
+            relative.print()[32..64].iter().all(|bit| *bit == 0.),
+            "Expected a relative sequence number of 0"
+        );
+    }
+
+    #[test]
+    fn test_nprint_timing() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let mut nprint = Nprint::new_with_timestamp(
+            &raw_packet,
+            vec![ProtocolType::Timing],
+            NprintConfig::default(),
+            Duration::from_secs(10),
+        );
+        nprint.add_with_timestamp(&raw_packet, Duration::from_micros(10_000_005));
+        nprint.add(&raw_packet);
+        let matrix = nprint.print_matrix(3);
+        assert_eq!(matrix[0], vec![0.; 32], "Expected a null first delta");
+        assert_eq!(
+            matrix[1][29..],
+            [1., 0., 1.],
+            "Expected a delta of 5 microseconds"
+        );
+        assert!(matrix[1][..29].iter().all(|bit| *bit == 0.));
+        assert_eq!(matrix[2], vec![-1.; 32], "Expected an unknown delta");
+        assert_eq!(nprint.get_headers()[0], "timing_delta_0");
+    }
+
+    #[test]
+    fn test_nprint_header_bytes() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp];
+        let nprint = Nprint::new(&raw_packet, protocols.clone());
+        assert_eq!(
+            nprint.header_bytes(0, ProtocolType::Ipv4).as_deref(),
+            Some(&raw_packet[14..34])
+        );
+        assert_eq!(
+            nprint.header_bytes(0, ProtocolType::Tcp).as_deref(),
+            Some(&raw_packet[34..])
+        );
+        assert_eq!(nprint.header_bytes(0, ProtocolType::Udp), None);
+        assert_eq!(nprint.header_bytes(1, ProtocolType::Tcp), None);
+
+        let anonymized = NprintBuilder::new()
+            .protocols(protocols)
+            .anonymize(true)
+            .bit_order(BitOrder::Lsb0)
+            .build_from(&raw_packet);
+        let mut expected = raw_packet[34..].to_vec();
+        expected[..4].fill(0);
+        assert_eq!(
+            anonymized.header_bytes(0, ProtocolType::Tcp),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn test_nprint_link_layer() {
+        let raw_ip = vec![
+            0x45, 0x00, 0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8,
+            0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b,
+            0x00, 0x00, 0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let mut ethernet = vec![0x0; 12];
+        ethernet.extend([0x08, 0x00]);
+        ethernet.extend(&raw_ip);
+        let mut sll = vec![0x0; 14];
+        sll.extend([0x08, 0x00]);
+        sll.extend(&raw_ip);
+        let mut sll2 = vec![0x08, 0x00];
+        sll2.extend([0x0; 18]);
+        sll2.extend(&raw_ip);
+        let protocols = vec![
+            ProtocolType::Ethernet,
+            ProtocolType::Ipv4,
+            ProtocolType::Tcp,
+        ];
+        let expected = Nprint::new(&ethernet, protocols.clone()).print()[112..].to_vec();
+
+        for (link_layer, packet) in [
+            (LinkLayer::RawIp, &raw_ip),
+            (LinkLayer::LinuxSll, &sll),
+            (LinkLayer::LinuxSll2, &sll2),
+            (LinkLayer::Auto, &raw_ip),
+        ] {
+            let nprint = NprintBuilder::new()
+                .protocols(protocols.clone())
+                .link_layer(link_layer)
+                .build_from(packet);
+            let output = nprint.print();
+            assert!(
+                output[..112].iter().all(|bit| *bit == -1.),
+                "Expected no Ethernet header for {:?}",
+                link_layer
+            );
+            assert_eq!(
+                output[112..],
+                expected,
+                "Wrong headers for {:?}",
+                link_layer
+            );
+            assert_eq!(nprint.stats().parsed, 1);
+        }
+
+        let auto = NprintBuilder::new()
+            .protocols(protocols)
+            .link_layer(LinkLayer::Auto)
+            .build_from(&ethernet);
+        assert_eq!(
+            auto.print()[112..],
+            expected,
+            "Expected Ethernet to be detected"
+        );
+    }
+
+    #[test]
+    fn test_nprint_dhcp() {
+        let mut raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x01, 0x10, 0x00, 0x00, 0x00, 0x00, 0x40, 0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0xff, 0x00, 0x44, 0x00, 0x43, 0x00, 0xfc, 0x00, 0x00,
+        ];
+        raw_packet.extend([0x01, 0x01, 0x06, 0x00, 0x39, 0x03, 0xf3, 0x26]);
+        raw_packet.extend([0; 20]);
+        raw_packet.extend([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+        raw_packet.extend([0; 10 + 192]);
+        raw_packet.extend([0x63, 0x82, 0x53, 0x63, 0x35, 0x01, 0x01, 0xff]);
+        let nprint = Nprint::new(&raw_packet, vec![ProtocolType::Dhcp, ProtocolType::Dns]);
+        let output = nprint.print();
+        assert_eq!(output.len(), 352 + 96, "Wrong output width");
+        assert_eq!(output[7], 1., "Expected a BOOTREQUEST");
+        assert_eq!(output[230], 1., "Expected the client hardware address");
+        assert!(
+            output[352..].iter().all(|bit| *bit == -1.),
+            "Expected default DNS"
+        );
+
+        let anonymized = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Dhcp])
+            .anonymize(true)
+            .build_from(&raw_packet);
+        assert!(anonymized.print()[96..].iter().all(|bit| *bit == 0.));
+    }
+
+    #[test]
+    fn test_nprint_checksum_validity() {
+        let mut raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp];
+        let builder = NprintBuilder::new()
+            .protocols(protocols.clone())
+            .checksum_validity(true);
+        let mut nprint = builder.build_from(&raw_packet);
+        raw_packet[73] ^= 0x01;
+        nprint.add(&raw_packet);
+
+        let headers = nprint.get_headers();
+        assert_eq!(nprint.bits_per_packet(), 480 + 480 + 64 - 3 * 15);
+        assert_eq!(headers.len(), nprint.bits_per_packet());
+        assert!(!headers.iter().any(|name| name.starts_with("tcp_cksum_1")));
+        let ipv4 = headers
+            .iter()
+            .position(|h| h == "ipv4_cksum_valid")
+            .unwrap();
+        let tcp = headers.iter().position(|h| h == "tcp_cksum_valid").unwrap();
+        let udp = headers.iter().position(|h| h == "udp_cksum_valid").unwrap();
+        let valid = nprint.packet_data(0).unwrap();
+        assert_eq!((valid[ipv4], valid[tcp], valid[udp]), (1., 1., -1.));
+        let corrupted = nprint.packet_data(1).unwrap();
+        assert_eq!(
+            (corrupted[ipv4], corrupted[tcp]),
+            (1., 0.),
+            "Expected the corrupted segment to be invalid"
+        );
+
+        let selected = NprintBuilder::new()
+            .protocols(protocols)
+            .checksum_validity(true)
+            .select_fields(&["tcp_cksum"])
+            .build_from(&raw_packet);
+        assert_eq!(selected.get_headers(), vec!["tcp_cksum_valid"]);
+        assert_eq!(*selected.print(), vec![0.]);
+    }
+
+    #[test]
+    fn test_nprint_ipv6_extension_headers() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x86, 0xdd, 0x60, 0x00,
+            0x00, 0x00, 0x00, 0x10, 0x00, 0x40, 0xfe, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0xfe, 0x80, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x11, 0x00, 0x01, 0x04,
+            0x00, 0x00, 0x00, 0x00, 0x30, 0x39, 0x1f, 0x90, 0x00, 0x08, 0x00, 0x00,
+        ];
+        let nprint = Nprint::new(&raw_packet, vec![ProtocolType::Udp]);
+        let output = nprint.print();
+        assert_eq!(
+            output[..32],
+            [
+                0., 0., 1., 1., 0., 0., 0., 0., 0., 0., 1., 1., 1., 0., 0., 1., 0., 0., 0., 1., 1.,
+                1., 1., 1., 1., 0., 0., 1., 0., 0., 0., 0.
+            ],
+            "Expected the UDP ports after the Hop-by-Hop header"
+        );
+        assert_eq!(nprint.stats().parsed, 1);
+
+        let truncated = Nprint::new(&raw_packet[..60], vec![ProtocolType::Udp]);
+        assert!(truncated.print().iter().all(|bit| *bit == -1.));
+        assert_eq!(truncated.stats().truncated_other, 1);
+    }
+
+    #[test]
+    fn test_nprint_validate() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let protocols = vec![
+            ProtocolType::Ethernet,
+            ProtocolType::Ipv4,
+            ProtocolType::Tcp,
+            ProtocolType::Udp,
+            ProtocolType::Payload,
+            ProtocolType::TcpOptions,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, protocols.clone());
+        nprint.add(&raw_packet[..30]);
+        assert_eq!(nprint.validate(), Ok(()));
+
+        let selected = NprintBuilder::new()
+            .protocols(protocols)
+            .max_payload_bytes(4)
+            .checksum_validity(true)
+            .select_fields(&["tcp_cksum", "payload", "ipv4_ttl"])
+            .build_from(&raw_packet);
+        assert_eq!(selected.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_nprint_mpls() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x88, 0x47, 0x00, 0x01,
+            0x40, 0x40, 0x00, 0x02, 0x01, 0x3f, 0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00,
+            0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4,
+            0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00, 0x00, 0x00, 0x50, 0x02, 0x72, 0x10,
+            0x25, 0xd4, 0x00, 0x00,
+        ];
+        let nprint = Nprint::new(
+            &raw_packet,
+            vec![ProtocolType::Mpls, ProtocolType::Ipv4, ProtocolType::Tcp],
+        );
+        let output = nprint.print();
+        assert_eq!(
+            output[..24],
+            [
+                0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1., 0., 1., 0., 0., 0.,
+                0., 0., 0.
+            ],
+            "Expected the top label"
+        );
+        assert_eq!(output[32..36], [0., 1., 0., 0.], "Expected the inner IPv4");
+        assert_eq!(
+            output[32 + 480..32 + 496],
+            [1., 0., 0., 1., 0., 1., 1., 1., 1., 0., 1., 0., 0., 1., 0., 0.]
+        );
+        assert_eq!(nprint.stats().parsed, 1);
+
+        let truncated = Nprint::new(&raw_packet[..16], vec![ProtocolType::Mpls]);
+        assert!(truncated.print().iter().all(|bit| *bit == -1.));
+        assert_eq!(truncated.stats().truncated_other, 1);
+    }
+
+    #[test]
+    fn test_nprint_udp_lite_and_empty_udp() {
+        let mut udp_lite = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x20, 0x00, 0x00, 0x40, 0x00, 0x40, 0x88, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x01,
+            0x0a, 0x00, 0x00, 0x02, 0x30, 0x39, 0x1f, 0x90, 0x00, 0x08, 0x9b, 0x97,
+        ];
+        udp_lite.extend(b"abcd");
+        let nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Udp, ProtocolType::Payload])
+            .max_payload_bytes(4)
+            .checksum_validity(true)
+            .build_from(&udp_lite);
+        let headers = nprint.get_headers();
+        let output = nprint.print();
+        assert_eq!(
+            output[..16],
+            [0., 0., 1., 1., 0., 0., 0., 0., 0., 0., 1., 1., 1., 0., 0., 1.],
+            "Expected the UDP-Lite source port"
+        );
+        let valid = headers.iter().position(|h| h == "udp_cksum_valid").unwrap();
+        assert_eq!(output[valid], 1., "Expected a valid UDP-Lite checksum");
+        assert_eq!(
+            output[valid + 1..valid + 9],
+            [0., 1., 1., 0., 0., 0., 0., 1.]
+        );
+
+        let empty = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x1c, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x01,
+            0x0a, 0x00, 0x00, 0x02, 0x30, 0x39, 0x1f, 0x90, 0x00, 0x08, 0x00, 0x00,
+        ];
+        let nprint = Nprint::new(&empty, vec![ProtocolType::Udp, ProtocolType::Payload]);
+        let output = nprint.print();
+        assert_eq!(
+            output[40..48],
+            [0., 0., 0., 0., 1., 0., 0., 0.],
+            "Expected length 8"
+        );
+        assert!(
+            output[64..].iter().all(|bit| *bit == -1.),
+            "Expected no payload"
+        );
+        assert_eq!(nprint.stats().parsed, 1);
+    }
+
+    #[test]
+    fn test_nprint_flow_key_and_directions() {
+        let forward = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x1c, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x14, 0x00, 0x35, 0x00, 0x08, 0x85, 0x00,
+        ];
+        let mut backward = forward.clone();
+        backward[26..34].rotate_left(4);
+        backward[34..38].rotate_left(2);
+
+        let mut nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Udp])
+            .max_packets(3)
+            .truncation(TruncationPolicy::DropOldest)
+            .build_from(&[0x0]);
+        assert_eq!(nprint.flow_key(), None);
+        for packet in [&forward, &backward, &forward, &backward] {
+            nprint.add(packet);
+        }
+
+        let key = nprint
+            .flow_key()
+            .expect("Expected the key of the first IPv4 packet");
+        assert_eq!(
+            key.src_ip,
+            "172.16.12.155".parse::<std::net::IpAddr>().unwrap()
+        );
+        assert_eq!((key.src_port, key.dst_port, key.proto), (57620, 53, 17));
+        assert_eq!(
+            nprint.directions(),
+            vec![Direction::Backward, Direction::Forward, Direction::Backward],
+            "Expected the directions of the last three packets"
+        );
+        assert_eq!(nprint.directions().len(), nprint.count());
+        assert_eq!(nprint.direction_of(0), Some(Direction::Backward));
+        assert_eq!(nprint.direction_of(1), Some(Direction::Forward));
+        assert_eq!(nprint.direction_of(3), None);
+    }
+
+    #[test]
+    fn test_nprint_protocol_major_layout() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let mut second = raw_packet.clone();
+        second[22] = 0x01;
+        let builder = || {
+            NprintBuilder::new()
+                .protocols(vec![ProtocolType::Ipv4, ProtocolType::Tcp])
+                .select_fields(&["ipv4_ttl", "tcp_sprt"])
+        };
+        let mut packet_major = builder().build_from(&raw_packet);
+        packet_major.add(&second);
+        let mut protocol_major = builder()
+            .layout(Layout::ProtocolMajor)
+            .build_from(&raw_packet);
+        protocol_major.add(&second);
+
+        let rows: Vec<Vec<f32>> = packet_major.iter_packets().collect();
+        let expected: Vec<f32> =
+            [&rows[0][..8], &rows[1][..8], &rows[0][8..], &rows[1][8..]].concat();
+        assert_eq!(protocol_major.print(), expected, "Expected IPv4 bits first");
+        assert_eq!(
+            protocol_major.print()[8..16],
+            [0., 0., 0., 0., 0., 0., 0., 1.]
+        );
+
+        let headers = protocol_major.get_headers();
+        assert_eq!(headers.len(), protocol_major.print().len());
+        assert_eq!(headers[0], "pkt0_ipv4_ttl_0");
+        assert_eq!(headers[8], "pkt1_ipv4_ttl_0");
+        assert_eq!(headers[16], "pkt0_tcp_sprt_0");
+        assert_eq!(headers[47], "pkt1_tcp_sprt_15");
+        assert_eq!(protocol_major.validate(), Ok(()));
+        assert_eq!(
+            protocol_major.to_csv_string(),
+            packet_major.to_csv_string(),
+            "Expected CSV rows to stay per packet"
+        );
+        protocol_major.add(&raw_packet);
+        assert_eq!(
+            protocol_major.get_headers().len(),
+            protocol_major.print().len(),
+            "Expected the names of the new packet"
+        );
+    }
+
+    #[test]
+    fn test_nprint_get_headers_cached() {
+        let mut nprint = Nprint::new(&[0x0], vec![ProtocolType::Ipv4, ProtocolType::Udp]);
+        let first = nprint.get_headers().as_ptr();
+        assert!(
+            std::ptr::eq(first, nprint.get_headers().as_ptr()),
+            "Expected the cached names"
+        );
+        nprint.add(&[0x0]);
+        assert!(std::ptr::eq(first, nprint.get_headers().as_ptr()));
+        assert_eq!(nprint.get_headers().len(), 480 + 64);
+    }
+
+    #[test]
+    fn test_nprint_failed_header_refilled() {
+        // QUIC long header truncated in its source connection ID
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x23, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0x08, 0x08, 0x08, 0x08, 0xe1, 0x15, 0x01, 0xbb, 0x00, 0x0f, 0x85, 0x00, 0xc3, 0x00,
+            0x00, 0x00, 0x01, 0x00, 0x04,
+        ];
+        let protocols = vec![ProtocolType::Udp, ProtocolType::Quic, ProtocolType::Timing];
+        let nprint = NprintBuilder::new()
+            .protocols(protocols)
+            .build_from(&raw_packet);
+        let output = nprint.print();
+        assert_eq!(output.len(), 64 + 373 + 32, "Wrong output width");
+        assert!(
+            output[..64].iter().all(|bit| *bit != -1.),
+            "Expected a parsed UDP header"
+        );
+        assert!(
+            output[64..].iter().all(|bit| *bit == -1.),
+            "Expected the partially written QUIC header to be filled back"
+        );
+        assert_eq!(nprint.stats().truncated_other, 1);
+        assert_eq!(nprint.packet_data(0), Some(output));
+    }
+
+    #[test]
+    fn test_nprint_tls_client_hello() {
+        let mut client_hello = vec![
+            0x16, 0x03, 0x01, 0x00, 0x2f, 0x01, 0x00, 0x00, 0x2b, 0x03, 0x03,
+        ];
+        client_hello.extend([0xaa; 32]);
+        client_hello.extend([0x00, 0x00, 0x04, 0x13, 0x01, 0xc0, 0x2f, 0x01, 0x00]);
+        let mut raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x18, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        raw_packet[17] += client_hello.len() as u8;
+        raw_packet.extend(&client_hello);
+
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::TlsClientHello]);
+        let output = nprint.print();
+        assert_eq!(output.len(), 560, "Wrong output width");
+        let bytes: Vec<u8> = output[..80]
+            .chunks(8)
+            .map(|bits| bits.iter().fold(0, |byte, bit| byte << 1 | *bit as u8))
+            .collect();
+        assert_eq!(
+            bytes,
+            [0x03, 0x01, 0x03, 0x03, 0x00, 0x04, 0x13, 0x01, 0xc0, 0x2f],
+            "Expected the versions and both cipher suites"
+        );
+        assert!(output[80..].iter().all(|bit| *bit == -1.));
+
+        // The same segment carrying application data
+        raw_packet[54] = 0x17;
+        nprint.add(&raw_packet);
+        assert!(
+            nprint.packet_data(1).unwrap().iter().all(|bit| *bit == -1.),
+            "Expected default TLS ClientHello for application data"
+        );
+        assert_eq!(nprint.stats().truncated_other, 0);
+    }
+
+    #[test]
+    fn test_nprint_jumbo_frame() {
+        let udp_packet = |payload_len: usize| {
+            let mut raw_packet = vec![
+                0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+                0x00, 0x00, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+                0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x00, 0x85, 0x00,
+            ];
+            raw_packet[16..18].copy_from_slice(&((payload_len + 28) as u16).to_be_bytes());
+            raw_packet[38..40].copy_from_slice(&((payload_len + 8) as u16).to_be_bytes());
+            raw_packet.extend(vec![0xff; payload_len]);
+            raw_packet
+        };
+        let mut nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Udp, ProtocolType::Payload])
+            .mtu(9000)
+            .build_from(&udp_packet(4000));
+        assert_eq!(nprint.bits_per_packet(), 64 + 9000 * 8);
+        assert_eq!(nprint.get_headers().len(), nprint.bits_per_packet());
+        let output = nprint.print();
+        assert!(
+            output[64..64 + 4000 * 8].iter().all(|bit| *bit == 1.),
+            "Expected the 4000 payload bytes"
+        );
+        assert!(output[64 + 4000 * 8..].iter().all(|bit| *bit == -1.));
+
+        nprint.add(&udp_packet(9001));
+        let output = nprint.packet_data(1).unwrap();
+        assert!(output[..64].iter().all(|bit| *bit != -1.));
+        assert!(
+            output[64..].iter().all(|bit| *bit == -1.),
+            "Expected a payload exceeding the MTU to be absent"
+        );
+    }
+
+    #[test]
+    fn test_nprint_diff() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp];
+        let mut original = Nprint::new(&raw_packet, protocols.clone());
+        original.add(&raw_packet);
+        let mut anonymized = Nprint::new(&raw_packet, protocols.clone());
+        anonymized.add(&raw_packet);
+        assert_eq!(original.diff(&anonymized), Some(vec![]));
+
+        anonymized.anonymize();
+        let diff = original.diff(&anonymized).unwrap();
+        assert!(!diff.is_empty(), "Expected anonymized bits");
+        let width = original.bits_per_packet();
+        for (index, before, after) in diff {
+            let bit = index % width;
+            assert!(
+                (96..160).contains(&bit) || (480..512).contains(&bit),
+                "Unexpected change of bit {}",
+                bit
+            );
+            assert_eq!((before, after), (1., 0.), "Expected a removed bit");
+        }
+
+        assert_eq!(
+            original.diff(&Nprint::new(&raw_packet, protocols)),
+            None,
+            "Expected mismatched packet counts"
+        );
+        assert_eq!(
+            original.diff(&Nprint::new(&raw_packet, vec![ProtocolType::Ipv4])),
+            None,
+            "Expected mismatched protocols"
+        );
+    }
+
+    #[test]
+    fn test_nprint_recompute_checksums() {
+        use pnet::packet::ipv4::{checksum, Ipv4Packet};
+        use pnet::packet::tcp::{ipv4_checksum, TcpPacket};
+
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp];
+        let assert_checksums = |nprint: &Nprint, valid: bool| {
+            let ip = nprint.header_bytes(0, ProtocolType::Ipv4).unwrap();
+            let tcp = nprint.header_bytes(0, ProtocolType::Tcp).unwrap();
+            let ip = Ipv4Packet::new(&ip).unwrap();
+            let tcp = TcpPacket::new(&tcp).unwrap();
+            assert_eq!(ip.get_checksum() == checksum(&ip), valid);
+            let expected = ipv4_checksum(&tcp, &ip.get_source(), &ip.get_destination());
+            assert_eq!(tcp.get_checksum() == expected, valid);
+        };
+
+        let mut nprint = NprintBuilder::new()
+            .protocols(protocols.clone())
+            .recompute_checksums(true)
+            .build_from(&raw_packet);
+        assert_checksums(&nprint, true);
+        nprint.anonymize();
+        assert_checksums(&nprint, true);
+        nprint.anonymize_with_key(&[7; 16]);
+        assert_checksums(&nprint, true);
+
+        let mut untouched = NprintBuilder::new()
+            .protocols(protocols)
+            .build_from(&raw_packet);
+        untouched.anonymize();
+        assert_checksums(&untouched, false);
+    }
+
+    #[test]
+    fn test_nprint_from_packets() {
+        let syn = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let mut ack = syn.clone();
+        ack[47] = 0x10;
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp];
+        let packets: Vec<&[u8]> = vec![&syn, &ack, &syn];
+
+        let nprint = Nprint::from_packets(&packets, protocols.clone()).unwrap();
+        let mut expected = Nprint::new(&syn, protocols.clone());
+        expected.add(&ack);
+        expected.add(&syn);
+        assert_eq!(nprint.count(), 3);
+        assert_eq!(nprint.get_headers(), expected.get_headers());
+        assert_eq!(nprint.diff(&expected), Some(vec![]));
+
+        assert!(Nprint::from_packets(&[], protocols).is_none());
+    }
+
+    #[test]
+    fn test_nprint_headers_with_protocols() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp];
+        let mut nprint = Nprint::new(&raw_packet, protocols.clone());
+        nprint.add(&raw_packet);
+
+        let headers: Vec<(&ProtocolType, &[f32])> = nprint.headers_with_protocols().collect();
+        assert_eq!(headers.len(), 2 * protocols.len());
+        let tags: Vec<&ProtocolType> = headers.iter().map(|(protocol, _)| *protocol).collect();
+        assert_eq!(tags, protocols.iter().chain(&protocols).collect::<Vec<_>>());
+        for (index, packet) in nprint.iter_packets().enumerate() {
+            let row: Vec<f32> = headers[index * 3..(index + 1) * 3]
+                .iter()
+                .flat_map(|(_, values)| values.iter().copied())
+                .collect();
+            assert_eq!(row, packet);
+        }
+        let tcp = headers[1].1;
+        assert_eq!(tcp.len(), 480);
+        assert_eq!(&tcp[106..112], &[0., 0., 0., 0., 1., 0.], "Expected a SYN");
+        assert!(headers[2].1.iter().all(|bit| *bit == -1.));
+    }
+
+    #[test]
+    fn test_nprint_parse_single_layer() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let nprint = Nprint::new(
+            &raw_packet,
+            vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp],
+        );
+        let headers: Vec<&[f32]> = nprint
+            .headers_with_protocols()
+            .map(|(_, values)| values)
+            .collect();
+
+        assert_eq!(nprint_rs::parse_ipv4(&raw_packet[14..]), headers[0]);
+        assert_eq!(nprint_rs::parse_tcp(&raw_packet[34..]), headers[1]);
+        assert_eq!(nprint_rs::parse_udp(&raw_packet[34..38]), headers[2]);
+        assert_eq!(
+            nprint_rs::parse_udp(&raw_packet[34..42])[..16],
+            headers[1][..16]
+        );
+
+        let mut row = [0.; 480 + 480 + 64];
+        let (ipv4, rest) = row.split_at_mut(480);
+        let (tcp, udp) = rest.split_at_mut(480);
+        assert!(nprint_rs::parse_ipv4_into(&raw_packet[14..], ipv4));
+        assert!(nprint_rs::parse_tcp_into(&raw_packet[34..], tcp));
+        assert!(!nprint_rs::parse_udp_into(&raw_packet[34..38], udp));
+        assert_eq!(row.to_vec(), nprint.print());
+
+        let mut short = [0.; 63];
+        assert!(!nprint_rs::parse_udp_into(&raw_packet[34..42], &mut short));
+        assert_eq!(
+            short, [0.; 63],
+            "Expected a slice of the wrong width untouched"
+        );
+    }
+
+    #[test]
+    fn test_nprint_anonymize_fields() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp];
+        let original = Nprint::new(&raw_packet, protocols.clone());
+        let changed = |fields: &[AnonField]| {
+            let mut nprint = Nprint::new(&raw_packet, protocols.clone());
+            nprint.anonymize_fields(fields);
+            let diff = original.diff(&nprint).unwrap();
+            diff.into_iter()
+                .map(|(index, _, after)| {
+                    assert_eq!(after, 0., "Expected a removed bit");
+                    index
+                })
+                .collect::<Vec<usize>>()
+        };
+
+        let dst = changed(&[AnonField::Ipv4Dst]);
+        assert!(!dst.is_empty());
+        assert!(dst.iter().all(|bit| (128..160).contains(bit)));
+        let ips = changed(&[
+            AnonField::Ipv4Src,
+            AnonField::Ipv4Dst,
+            AnonField::UdpSrcPort,
+        ]);
+        assert!(
+            ips.iter().all(|bit| (96..160).contains(bit)),
+            "Expected ports kept"
+        );
+        let id = changed(&[AnonField::Ipv4Id]);
+        assert!(!id.is_empty() && id.iter().all(|bit| (32..48).contains(bit)));
+        assert!(changed(&[]).is_empty());
+
+        let mut default = Nprint::new(&raw_packet, protocols.clone());
+        default.anonymize();
+        let mut all = Nprint::new(&raw_packet, protocols.clone());
+        all.anonymize_fields(&AnonField::DEFAULT);
+        assert_eq!(default.diff(&all), Some(vec![]));
+        assert_eq!(
+            original.diff(&all).unwrap().len(),
+            changed(&[
+                AnonField::Ipv4Src,
+                AnonField::Ipv4Dst,
+                AnonField::TcpSrcPort,
+                AnonField::TcpDstPort
+            ])
+            .len()
+        );
+    }
+
+    #[test]
+    fn test_nprint_tcp_flags_value() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let build = |encoding: TcpFlagsEncoding| {
+            NprintBuilder::new()
+                .protocols(vec![ProtocolType::Tcp, ProtocolType::Udp])
+                .tcp_flags(encoding)
+                .normalize_tcp_flags(true)
+                .build_from(&raw_packet)
+        };
+
+        let bits = build(TcpFlagsEncoding::Bits);
+        assert_eq!(bits.bits_per_packet(), 480 + 64);
+        assert!(!bits
+            .get_headers()
+            .contains(&"tcp_flags_value_0".to_string()));
+
+        let both = build(TcpFlagsEncoding::BitsAndValue);
+        assert_eq!(both.bits_per_packet(), 481 + 64);
+        assert_eq!(both.get_headers()[480], "tcp_flags_value_0");
+        assert_eq!(both.print()[480], 2. / 255., "Expected a normalized SYN");
+        assert_eq!(both.print()[..480], bits.print()[..480]);
+
+        let value = build(TcpFlagsEncoding::Value);
+        let headers = value.get_headers();
+        assert_eq!(value.bits_per_packet(), 481 - 9 + 64);
+        assert_eq!(headers.len(), value.bits_per_packet());
+        assert!(!headers.iter().any(|name| name.starts_with("tcp_syn")));
+        assert!(!headers.iter().any(|name| name.starts_with("tcp_ns")));
+        let index = headers
+            .iter()
+            .position(|h| h == "tcp_flags_value_0")
+            .unwrap();
+        assert_eq!(value.print()[index], 2. / 255.);
+        assert_eq!(headers[index + 1], "udp_sport_0");
+
+        let selected = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Tcp])
+            .tcp_flags(TcpFlagsEncoding::Value)
+            .select_fields(&["tcp_flags_value", "tcp_syn"])
+            .build_from(&raw_packet);
+        assert_eq!(selected.get_headers(), vec!["tcp_flags_value_0"]);
+        assert_eq!(*selected.print(), vec![2.]);
+    }
+
+    #[test]
+    fn test_nprint_llc_snap() {
+        let ipv4 = vec![
+            0x45, 0x00, 0x00, 0x1c, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10,
+            0x0c, 0x9b, 0xac, 0x10, 0x1f, 0xff, 0xe1, 0x14, 0x00, 0x35, 0x00, 0x08, 0x85, 0x00,
+        ];
+        let mut ethernet2 = vec![0x0; 12];
+        ethernet2.extend([0x08, 0x00]);
+        ethernet2.extend(&ipv4);
+        let mut snap = vec![0x0; 12];
+        snap.extend([0x00, 0x24, 0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00, 0x08, 0x00]);
+        snap.extend(&ipv4);
+
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Udp];
+        let expected = Nprint::new(&ethernet2, protocols.clone());
+        for link_layer in [LinkLayer::Ethernet, LinkLayer::Auto] {
+            let nprint = NprintBuilder::new()
+                .protocols(protocols.clone())
+                .link_layer(link_layer)
+                .build_from(&snap);
+            assert_eq!(
+                nprint.diff(&expected),
+                Some(vec![]),
+                "Expected the SNAP-encapsulated IPv4 with {:?}",
+                link_layer
+            );
+            assert_eq!(nprint.stats(), expected.stats());
+        }
+        assert_eq!(
+            FlowKey::from_packet(&snap),
+            FlowKey::from_packet(&ethernet2)
+        );
+        assert!(FlowKey::from_packet(&snap).is_some());
+    }
+
+    #[test]
+    fn test_nprint_parsed_status() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp];
+        let mut nprint = Nprint::new(&raw_packet, protocols.clone());
+        assert_eq!(nprint.parsed_ratio(), 1.);
+        nprint.add(&raw_packet[..40]);
+        nprint.add(&[0x0]);
+        nprint.add(&raw_packet);
+
+        assert_eq!(nprint.is_parsed(0, ProtocolType::Ipv4), Some(true));
+        assert_eq!(nprint.is_parsed(0, ProtocolType::Tcp), Some(true));
+        assert_eq!(nprint.is_parsed(0, ProtocolType::Udp), Some(false));
+        assert_eq!(nprint.is_parsed(1, ProtocolType::Ipv4), Some(true));
+        assert_eq!(
+            nprint.is_parsed(1, ProtocolType::Tcp),
+            Some(false),
+            "Expected the truncated segment to fall back to the fill value"
+        );
+        assert_eq!(nprint.is_parsed(2, ProtocolType::Ipv4), Some(false));
+        assert_eq!(nprint.is_parsed(4, ProtocolType::Ipv4), None);
+        assert_eq!(nprint.is_parsed(0, ProtocolType::Dns), None);
+        assert_eq!(nprint.parsed_ratio(), 0.75);
+    }
+
+    #[test]
+    fn test_nprint_from_ethernet() {
+        use pnet::packet::ethernet::EthernetPacket;
+
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let frame = EthernetPacket::new(&raw_packet).unwrap();
+        let protocols = vec![
+            ProtocolType::Ethernet,
+            ProtocolType::Ipv4,
+            ProtocolType::Tcp,
+        ];
+        let mut nprint = Nprint::from_ethernet(&frame, protocols.clone());
+        nprint.add_ethernet(&frame);
+        let mut expected = Nprint::new(&raw_packet, protocols.clone());
+        expected.add(&raw_packet);
+        assert_eq!(nprint.diff(&expected), Some(vec![]));
+        assert_eq!(nprint.stats(), expected.stats());
+        assert_eq!(nprint.flow_key(), expected.flow_key());
+
+        let mut raw_ip = NprintBuilder::new()
+            .protocols(protocols)
+            .link_layer(LinkLayer::RawIp)
+            .build_from(&raw_packet[14..]);
+        raw_ip.add_ethernet(&frame);
+        assert_eq!(
+            raw_ip.packet_data(1).unwrap(),
+            expected.packet_data(0).unwrap(),
+            "Expected the frame parsed as Ethernet whatever the link layer"
+        );
+    }
+
+    #[test]
+    fn test_nprint_scale_window() {
+        let syn = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        // Answer from the server, announcing a scale of 8
+        let mut syn_ack = syn.clone();
+        syn_ack[34..38].copy_from_slice(&[0x01, 0xbb, 0x97, 0xa4]);
+        syn_ack[47] = 0x12;
+        syn_ack[73] = 0x08;
+        let mut client_ack = syn.clone();
+        client_ack[47] = 0x10;
+        let mut server_ack = syn_ack.clone();
+        server_ack[47] = 0x10;
+        let mut nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Tcp])
+            .scale_window(true)
+            .build_from(&syn);
+        nprint.add(&syn_ack);
+        nprint.add(&client_ack);
+        nprint.add(&server_ack);
+        assert_eq!(nprint.get_headers().len(), 510, "Wrong TCP width");
+        assert_eq!(
+            nprint.get_headers()[480..],
+            (0..30)
+                .map(|index| format!("tcp_wsize_scaled_{}", index))
+                .collect::<Vec<String>>()[..],
+            "Wrong scaled window headers"
+        );
+        let windows: Vec<u64> = nprint
+            .iter_packets()
+            .map(|packet| {
+                packet[480..]
+                    .iter()
+                    .fold(0, |value, bit| (value << 1) | *bit as u64)
+            })
+            .collect();
+        assert_eq!(
+            windows,
+            vec![0x7210, 0x7210, 0x7210 << 7, 0x7210 << 8],
+            "Wrong scaled windows"
+        );
+        let raw = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Tcp])
+            .build_from(&syn);
+        assert_eq!(
+            raw.get_headers().len(),
+            480,
+            "Scaled window added by default"
+        );
+    }
+
+    #[test]
+    fn test_nprint_to_grid_string() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00, 0x53, 0x70,
+            0x6f, 0x74, 0x55, 0x64, 0x70, 0x30, 0x9e, 0x61, 0x42, 0x3d, 0x11, 0x99, 0x99, 0xee,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::Ipv4, ProtocolType::Tcp]);
+        nprint.add(&raw_packet);
+        let grid = nprint.to_grid_string();
+        let lines: Vec<&str> = grid.lines().collect();
+        assert_eq!(lines.len(), 960, "Expected one line per field");
+        let width = nprint.get_headers().iter().map(String::len).max().unwrap();
+        assert_eq!(
+            lines[0],
+            format!("{:<width$} 0 0", "ipv4_ver_0", width = width),
+            "Wrong first IPv4 line"
+        );
+        assert_eq!(
+            lines[1],
+            format!("{:<width$} 1 1", "ipv4_ver_1", width = width),
+            "Wrong second IPv4 line"
+        );
+        assert!(
+            lines[480..].iter().all(|line| line.ends_with(" . .")),
+            "Expected the absent TCP header as dots"
+        );
+        assert!(
+            lines.iter().all(|line| line.len() == width + 4),
+            "Expected aligned columns"
+        );
+    }
+
+    #[test]
+    fn test_nprint_print_trits() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x48, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x34, 0x85, 0x00, 0x53, 0x70,
+            0x6f, 0x74, 0x55, 0x64, 0x70, 0x30, 0x9e, 0x61, 0x42, 0x3d, 0x11, 0x99, 0x99, 0xee,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp];
+        let mut nprint = Nprint::new(&raw_packet, protocols.clone());
+        let single = nprint.print_trits();
+        nprint.add(&raw_packet);
+        let trits = nprint.print_trits();
+        let values = nprint.print();
+        assert_eq!(trits.len(), values.len(), "Wrong number of trits");
+        for (index, (trit, value)) in trits.iter().zip(&values).enumerate() {
+            assert_eq!(*trit, *value as i8, "Trit {} doesn't match print()", index);
+        }
+        assert!(trits.contains(&-1), "Expected absent TCP trits");
+
+        let nan = NprintBuilder::new()
+            .protocols(protocols)
+            .fill_value(FillValue::NaN)
+            .build_from(&raw_packet);
+        assert_eq!(nan.print_trits(), single, "Expected NaN fill to become -1");
+    }
+
+    #[test]
+    fn test_nprint_options_bits() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp];
+        let nprint = NprintBuilder::new()
+            .protocols(protocols.clone())
+            .tcp_options_bits(64)
+            .build_from(&raw_packet);
+        let tcp: Vec<&String> = nprint
+            .get_headers()
+            .iter()
+            .filter(|name| name.starts_with("tcp_"))
+            .collect();
+        assert_eq!(tcp.len(), 160 + 64, "Wrong TCP width");
+        assert_eq!(tcp.last().unwrap().as_str(), "tcp_opt_63");
+        assert_eq!(nprint.bits_per_packet(), 480 + 224);
+        assert!(nprint.validate().is_ok());
+        let full = Nprint::new(&raw_packet, protocols.clone());
+        assert_eq!(
+            nprint.packet_data(0).unwrap()[480..],
+            full.packet_data(0).unwrap()[480..480 + 224],
+            "Expected the first 64 option bits"
+        );
+        assert_eq!(
+            nprint.header_bytes(0, ProtocolType::Tcp),
+            None,
+            "Expected truncated options not to be reassembled"
+        );
+
+        let ipv4 = NprintBuilder::new()
+            .protocols(protocols)
+            .ipv4_options_bits(0)
+            .build_from(&raw_packet);
+        assert_eq!(ipv4.bits_per_packet(), 160 + 480, "Wrong IPv4 width");
+        assert_eq!(
+            ipv4.header_bytes(0, ProtocolType::Ipv4),
+            Some(raw_packet[14..34].to_vec()),
+            "Expected the IPv4 header without options"
+        );
+        assert_eq!(
+            NprintConfig::default().tcp_options_width(),
+            320,
+            "Wrong default options width"
+        );
+    }
+
+    #[test]
+    fn test_nprint_ip_in_ip() {
+        let inner = [
+            0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8,
+            0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b,
+            0x00, 0x00, 0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04,
+            0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x03, 0x03, 0x07,
+        ];
+        // Wraps a packet into an IPv4 header carrying protocol 4
+        let wrap = |packet: &[u8]| {
+            let mut outer = vec![
+                0x45, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x40, 0x04, 0x00, 0x00, 0x0a, 0x00,
+                0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+            ];
+            outer[2..4].copy_from_slice(&((20 + packet.len()) as u16).to_be_bytes());
+            outer.extend(packet);
+            outer
+        };
+        let frame = |packet: &[u8]| [&[0x0; 12][..], &[0x08, 0x00], packet].concat();
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp];
+        let plain = Nprint::new(&frame(&inner), protocols.clone());
+        let tunneled = Nprint::new(&frame(&wrap(&inner)), protocols.clone());
+        assert_eq!(
+            tunneled.print(),
+            plain.print(),
+            "Expected the inner IPv4 and TCP headers"
+        );
+        assert_eq!(
+            tunneled.print()[480..496],
+            [1., 0., 0., 1., 0., 1., 1., 1., 1., 0., 1., 0., 0., 1., 0., 0.],
+            "Expected the inner TCP source port"
+        );
+
+        // Nested beyond the depth guard, the transport layer isn't reached
+        let mut nested = inner.to_vec();
+        for _ in 0..6 {
+            nested = wrap(&nested);
+        }
+        let nprint = Nprint::new(&frame(&nested), protocols);
+        assert_eq!(nprint.is_parsed(0, ProtocolType::Ipv4), Some(true));
+        assert_eq!(nprint.is_parsed(0, ProtocolType::Tcp), Some(false));
+    }
+
+    #[test]
+    fn test_protocol_output_headers() {
+        for protocol in ProtocolType::ALL {
+            let headers = protocol.output_headers();
+            assert_eq!(
+                headers.len(),
+                protocol.width(),
+                "{:?} header names don't match its width",
+                protocol
+            );
+            assert_eq!(
+                Nprint::new(&[], vec![protocol]).get_headers(),
+                &headers[..],
+                "{:?} header names differ from the Nprint ones",
+                protocol
+            );
+        }
+    }
+
+    #[test]
+    fn test_headers_parse() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp];
+        let headers = Headers::parse(&raw_packet, &protocols);
+        let nprint = Nprint::new(&raw_packet, protocols);
+        assert_eq!(headers.to_vec(), nprint.print(), "Expected the Nprint row");
+        assert_eq!(headers.data(), &nprint.print()[..]);
+        assert_eq!(
+            headers.parsed(),
+            [true, true, false],
+            "Wrong parsed headers"
+        );
+        assert_eq!(
+            Headers::parse(&[], &[ProtocolType::Udp]).to_vec(),
+            vec![-1.; 64],
+            "Expected an absent header"
+        );
+    }
+
+    #[test]
+    fn test_nprint_udp_length_mismatch() {
+        // UDP header announcing 1000 bytes, over a 20 bytes datagram
+        let mut raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x30, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x03, 0xe8, 0x00, 0x00, 0x53, 0x70,
+            0x6f, 0x74, 0x55, 0x64, 0x70, 0x30, 0x9e, 0x61, 0x42, 0x3d,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Udp];
+        let nprint = Nprint::new(&raw_packet, protocols.clone());
+        assert_eq!(nprint.is_parsed(0, ProtocolType::Udp), Some(true));
+        assert_eq!(nprint.stats().truncated_udp_payload, 1);
+        assert_eq!(nprint.stats().parsed, 1, "Expected no failure");
+
+        // Shorter than its own header
+        raw_packet[38..40].copy_from_slice(&[0x00, 0x04]);
+        let nprint = Nprint::new(&raw_packet, protocols);
+        assert_eq!(nprint.is_parsed(0, ProtocolType::Udp), Some(false));
+        assert_eq!(nprint.stats().truncated_udp, 1);
+        assert_eq!(nprint.stats().truncated_udp_payload, 0);
+    }
+
+    #[test]
+    fn test_nprint_frame_meta() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let mut nprint = Nprint::new(&raw_packet, vec![ProtocolType::FrameMeta]);
+        nprint.add_with_wire_len(&raw_packet, Duration::ZERO, 1514);
+        let matrix = nprint.print_matrix(2);
+        assert_eq!(
+            matrix[0][..16],
+            [0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1., 1., 0., 1., 1., 0.],
+            "Expected a captured length of 54 bytes"
+        );
+        assert_eq!(
+            matrix[0][16..],
+            [-1.; 16],
+            "Expected an unknown wire length"
+        );
+        assert_eq!(matrix[1][..16], matrix[0][..16]);
+        assert_eq!(
+            matrix[1][16..],
+            [0., 0., 0., 0., 0., 1., 0., 1., 1., 1., 1., 0., 1., 0., 1., 0.],
+            "Expected a wire length of 1514 bytes"
+        );
+        let headers = nprint.get_headers();
+        assert_eq!(headers[0], "frame_caplen_0");
+        assert_eq!(headers[31], "frame_wirelen_15");
+    }
+
+    #[test]
+    fn test_nprint_gtp_u() {
+        let inner = [
+            0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8,
+            0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b,
+            0x00, 0x00, 0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04,
+            0x05, 0xb4, 0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00,
+            0x01, 0x03, 0x03, 0x07,
+        ];
+        let mut plain = vec![0x0; 12];
+        plain.extend([0x08, 0x00]);
+        plain.extend(inner);
+        // Ethernet, outer IPv4 carrying UDP to port 2152, a G-PDU, then the inner packet
+        let mut tunneled = vec![0x0; 12];
+        tunneled.extend([0x08, 0x00]);
+        tunneled.extend([
+            0x45, 0x00, 0x00, 0x60, 0x00, 0x01, 0x00, 0x00, 0x40, 0x11, 0x00, 0x00, 0x0a, 0x00,
+            0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+        ]);
+        tunneled.extend([0x08, 0x68, 0x08, 0x68, 0x00, 0x4c, 0x00, 0x00]);
+        tunneled.extend([0x30, 0xff, 0x00, 0x3c, 0x00, 0x00, 0x00, 0x2a]);
+        tunneled.extend(inner);
+
+        let protocols = vec![ProtocolType::Gtp, ProtocolType::Ipv4, ProtocolType::Tcp];
+        let nprint = Nprint::new(&tunneled, protocols);
+        let output = nprint.print();
+        assert_eq!(output[8..16], [1.; 8], "Expected a G-PDU");
+        assert_eq!(output[58..64], [1., 0., 1., 0., 1., 0.], "Expected TEID 42");
+        assert_eq!(
+            output[64..],
+            Nprint::new(&plain, vec![ProtocolType::Ipv4, ProtocolType::Tcp]).print(),
+            "Expected the inner IPv4 and TCP headers"
+        );
+    }
+
+    #[test]
+    fn test_nprint_schema_hash() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x1c, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x08, 0x85, 0x00,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Udp];
+        let nprint = Nprint::new(&raw_packet, protocols.clone());
+        let csv = nprint.to_csv_string();
+        assert_eq!(Nprint::schema_version(), 1);
+        assert_eq!(
+            nprint.schema_hash(),
+            Nprint::new(&[0x0], protocols.clone()).schema_hash(),
+            "Expected the hash to only depend on the columns"
+        );
+        assert!(nprint.check_csv_schema(csv.as_bytes()).is_ok());
+
+        let reordered = Nprint::new(&raw_packet, vec![ProtocolType::Udp, ProtocolType::Ipv4]);
+        assert_ne!(reordered.schema_hash(), nprint.schema_hash());
+        assert!(matches!(
+            reordered.check_csv_schema(csv.as_bytes()),
+            Err(NprintError::SchemaMismatch { expected, found })
+                if expected == reordered.schema_hash() && found == nprint.schema_hash()
+        ));
+        let narrower = NprintBuilder::new()
+            .protocols(protocols)
+            .ipv4_options_bits(0)
+            .build_from(&raw_packet);
+        assert!(narrower.check_csv_schema(csv.as_bytes()).is_err());
+        assert_eq!(
+            Nprint::new(&raw_packet, vec![]).schema_hash(),
+            0xcbf2_9ce4_8422_2325,
+            "Expected the FNV-1a offset basis without any column"
+        );
+    }
+
+    #[test]
+    fn test_nprint_from_csv() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x1c, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x08, 0x85, 0x00,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp];
+        let mut nprint = Nprint::new(&raw_packet, protocols.clone());
+        nprint.add(&[0x0]);
+        let csv = nprint.to_csv_string();
+
+        let read = Nprint::from_csv(csv.as_bytes(), protocols.clone()).unwrap();
+        assert_eq!(read.count(), 2);
+        assert_eq!(
+            read.print(),
+            nprint.print(),
+            "Expected the same values back"
+        );
+        assert_eq!(read.get_headers(), nprint.get_headers());
+        assert_eq!(read.to_csv_string(), csv, "Expected the same CSV back");
+
+        assert!(matches!(
+            Nprint::from_csv(csv.as_bytes(), vec![ProtocolType::Udp]),
+            Err(NprintError::SchemaMismatch { .. })
+        ));
+        let mut lines: Vec<String> = csv.lines().map(String::from).collect();
+        lines[2] = lines[2].replacen("-1", "x", 1);
+        let err = Nprint::from_csv(lines.join("\n").as_bytes(), protocols.clone()).unwrap_err();
+        assert!(
+            matches!(err, NprintError::MalformedCsv { line: 3, .. }),
+            "Unexpected error {}",
+            err
+        );
+        lines[2] = "0,1".to_string();
+        let err = Nprint::from_csv(lines.join("\n").as_bytes(), protocols).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "malformed CSV at line 3: expected {} values, found 2",
+                nprint.bits_per_packet()
+            )
+        );
+    }
+
+    #[test]
+    fn test_nprint_ethernet_padding() {
+        // TCP segment carrying 4 bytes, padded with 2 bytes to the 60-byte Ethernet minimum
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x2c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x20, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0x50, 0x18, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0xde, 0xad, 0xbe, 0xef,
+            0xff, 0xff,
+        ];
+        assert_eq!(raw_packet.len(), 60);
+        let nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Tcp, ProtocolType::Payload])
+            .max_payload_bytes(8)
+            .build_from(&raw_packet);
+        let payload = &nprint.print()[nprint.bits_per_packet() - 64..];
+        assert_eq!(
+            payload[..8],
+            [1., 1., 0., 1., 1., 1., 1., 0.],
+            "Expected the TCP payload"
+        );
+        assert_eq!(
+            payload[32..],
+            [-1.; 32],
+            "Expected 4 payload bytes, as announced by the IPv4 total length"
+        );
+    }
+
+    #[test]
+    fn test_nprint_include_options() {
+        // TCP SYN with 20 bytes of options
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp];
+        let with_options = Nprint::new(&raw_packet, protocols.clone());
+        assert!(NprintConfig::default().include_options);
+        assert_eq!(with_options.bits_per_packet(), 960);
+        assert!(with_options
+            .get_headers()
+            .iter()
+            .any(|name| name.starts_with("tcp_opt")));
+
+        let without_options = NprintBuilder::new()
+            .protocols(protocols)
+            .include_options(false)
+            .build_from(&raw_packet);
+        assert_eq!(without_options.bits_per_packet(), 320);
+        assert!(without_options
+            .get_headers()
+            .iter()
+            .all(|name| !name.starts_with("ipv4_opt") && !name.starts_with("tcp_opt")));
+        let output = with_options.print();
+        assert_eq!(
+            without_options.print(),
+            [&output[..160], &output[480..640]].concat(),
+            "Expected the fixed bits of both headers"
+        );
+    }
+
+    #[test]
+    fn test_nprint_print_mask() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x1c, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x08, 0x85, 0x00,
+        ];
+        let protocols = vec![ProtocolType::Tcp, ProtocolType::Udp];
+        let mut nprint = Nprint::new(&raw_packet, protocols.clone());
+        nprint.add(&[0x0]);
+        let mask = nprint.print_mask();
+        assert_eq!(mask.len(), nprint.print().len());
+        assert_eq!(mask[..480], [0.; 480], "Expected an absent TCP header");
+        assert_eq!(mask[480..544], [1.; 64], "Expected a present UDP header");
+        assert_eq!(mask[544..], [0.; 544], "Expected an absent second packet");
+
+        let nan = NprintBuilder::new()
+            .protocols(protocols.clone())
+            .fill_value(FillValue::NaN)
+            .build_from(&raw_packet);
+        assert_eq!(nan.print_mask(), mask[..544]);
+        let zero = NprintBuilder::new()
+            .protocols(protocols.clone())
+            .fill_value(FillValue::Zero)
+            .build_from(&raw_packet);
+        assert_eq!(
+            zero.print_mask(),
+            mask[..544],
+            "Expected the zero-filled TCP header to be absent"
+        );
+
+        let mut protocol_major = NprintBuilder::new()
+            .protocols(protocols)
+            .fill_value(FillValue::Zero)
+            .layout(Layout::ProtocolMajor)
+            .max_packets(3)
+            .pad_packets(true)
+            .build_from(&raw_packet);
+        protocol_major.add(&[0x0]);
+        let mask = protocol_major.print_mask();
+        assert_eq!(mask.len(), protocol_major.print().len());
+        assert_eq!(mask[..1440], [0.; 1440], "Expected no TCP header");
+        assert_eq!(
+            mask[1440..1504],
+            [1.; 64],
+            "Expected the UDP header of the first packet"
+        );
+        assert_eq!(mask[1504..], [0.; 128], "Expected no other UDP header");
+    }
+
+    #[test]
+    fn test_nprint_with_window() {
+        let packets: Vec<Vec<u8>> = (0..4u8)
+            .map(|i| {
+                vec![
+                    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45,
+                    0x00, 0x00, 0x1c, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10,
+                    0x0c, 0x9b, 0xac, 0x10, 0x1f, 0xff, 0xe1, i, 0xe1, 0x15, 0x00, 0x08, 0x85,
+                    0x00,
+                ]
+            })
+            .collect();
+        let udp = |packet: &[u8]| Nprint::new(packet, vec![ProtocolType::Udp]).print();
+        let mut nprint = Nprint::with_window(vec![ProtocolType::Udp], 3);
+        assert_eq!(nprint.count(), 0);
+        assert_eq!(nprint.print(), vec![-1.; 192], "Expected 3 padding packets");
+
+        nprint.add(&packets[0]);
+        let output = nprint.print();
+        assert_eq!(output.len(), 192, "Expected exactly 3 packets");
+        assert_eq!(output[..64], udp(&packets[0]));
+        assert_eq!(output[64..], [-1.; 128]);
+        assert!(nprint.validate().is_ok());
+
+        for packet in &packets[1..] {
+            nprint.add(packet);
+        }
+        assert_eq!(nprint.count(), 3);
+        assert_eq!(
+            nprint.print(),
+            [udp(&packets[1]), udp(&packets[2]), udp(&packets[3])].concat(),
+            "Expected the last 3 packets"
+        );
+
+        let mut protocol_major = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Udp])
+            .max_packets(2)
+            .pad_packets(true)
+            .layout(Layout::ProtocolMajor)
+            .build_from(&packets[0]);
+        assert_eq!(protocol_major.print().len(), 128);
+        assert_eq!(protocol_major.get_headers().len(), 128);
+        assert_eq!(protocol_major.get_headers()[64], "pkt1_udp_sport_0");
+        assert!(protocol_major.validate().is_ok());
+        protocol_major.add(&packets[1]);
+        assert_eq!(protocol_major.print().len(), 128);
+    }
+
+    #[test]
+    fn test_nprint_ipsec() {
+        let tcp = [
+            0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00, 0x00, 0x00, 0x50, 0x02,
+            0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let ipv4 = |protocol: u8, total_length: u8| {
+            let mut packet = vec![0x0; 12];
+            packet.extend([0x08, 0x00]);
+            packet.extend([
+                0x45,
+                0x00,
+                0x00,
+                total_length,
+                0x00,
+                0x01,
+                0x00,
+                0x00,
+                0x40,
+                protocol,
+                0x00,
+                0x00,
+                0x0a,
+                0x00,
+                0x00,
+                0x01,
+                0x0a,
+                0x00,
+                0x00,
+                0x02,
+            ]);
+            packet
+        };
+        // AH with a 12-byte integrity check value, authenticating a TCP segment
+        let ah = [
+            0x06, 0x04, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x05, 0xaa, 0xaa,
+            0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0xaa,
+        ];
+
+        let mut esp = ipv4(0x32, 0x20);
+        esp.extend([
+            0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x00, 0x05, 0xde, 0xad, 0xbe, 0xef,
+        ]);
+        let nprint = Nprint::new(&esp, vec![ProtocolType::Esp, ProtocolType::Tcp]);
+        let output = nprint.print();
+        assert_eq!(
+            output[24..32],
+            [0., 0., 0., 0., 0., 0., 1., 0.],
+            "Expected SPI 258"
+        );
+        assert_eq!(
+            output[56..64],
+            [0., 0., 0., 0., 0., 1., 0., 1.],
+            "Expected sequence 5"
+        );
+        assert_eq!(output[64..], [-1.; 480], "Expected an encrypted payload");
+        assert_eq!(nprint.get_headers()[0], "esp_spi_0");
+        assert_eq!(nprint.get_headers()[32], "esp_seq_0");
+
+        let mut authenticated = ipv4(0x33, 0x40);
+        authenticated.extend(ah);
+        authenticated.extend(tcp);
+        let mut plain = ipv4(0x06, 0x28);
+        plain.extend(tcp);
+        let protocols = vec![ProtocolType::Ah, ProtocolType::Esp, ProtocolType::Tcp];
+        let output = Nprint::new(&authenticated, protocols).print();
+        assert_eq!(
+            output[..8],
+            [0., 0., 0., 0., 0., 1., 1., 0.],
+            "Expected TCP next"
+        );
+        assert_eq!(output[96..160], [-1.; 64], "Expected no ESP header");
+        assert_eq!(
+            output[160..],
+            Nprint::new(&plain, vec![ProtocolType::Tcp]).print(),
+            "Expected the authenticated TCP header"
+        );
+
+        // IPv6 carrying AH, then UDP
+        let mut ipv6 = vec![0x0; 12];
+        ipv6.extend([0x86, 0xdd, 0x60, 0x00, 0x00, 0x00, 0x00, 0x20, 0x33, 0x40]);
+        ipv6.extend([0x0; 32]);
+        ipv6.extend([0x11]);
+        ipv6.extend(&ah[1..]);
+        ipv6.extend([0x00, 0x35, 0x00, 0x35, 0x00, 0x08, 0x00, 0x00]);
+        let output = Nprint::new(&ipv6, vec![ProtocolType::Ah, ProtocolType::Udp]).print();
+        assert_eq!(
+            output[..8],
+            [0., 0., 0., 1., 0., 0., 0., 1.],
+            "Expected UDP next"
+        );
+        assert_eq!(
+            output[96..112],
+            [0., 0., 0., 0., 0., 0., 0., 0., 0., 0., 1., 1., 0., 1., 0., 1.],
+            "Expected the UDP source port"
+        );
+    }
+
+    #[test]
+    fn test_nprint_from_hex() {
+        let packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp];
+        let expected = Nprint::new(&packet, protocols.clone()).print();
+
+        let stream = "0000000000000000000000000800\
+                      4500003cf51b400040061b24c0a82b25c6267888\
+                      97a401bb962e5e0b00000000a002721025d40000\
+                      020405b40402080ae3e214230000000001030307";
+        let dump = "0000 0000 0000 0000 0000 0000 0800 4500\n\
+                    003c f51b 4000 4006 1b24 c0a8 2b25 c626\n\
+                    7888 97a4 01bb 962e 5e0b 0000 0000 a002\n\
+                    7210 25d4 0000 0204 05b4 0402 080a e3e2\n\
+                    1423 0000 0000 0103 0307";
+        let literal = "0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, \
+                       0x45, 0x00, 0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, \
+                       0xc0, 0xa8, 0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, \
+                       0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00, 0x00, 0x00, 0xA0, 0x02, 0x72, 0x10, \
+                       0x25, 0xD4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xB4, 0x04, 0x02, 0x08, 0x0a, \
+                       0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x03, 0x07,";
+        for hex in [stream, dump, literal] {
+            let nprint = Nprint::from_hex(hex, protocols.clone()).unwrap();
+            assert_eq!(nprint.print(), expected, "Expected the bytes of {:?}", hex);
+        }
+
+        match Nprint::from_hex("4500 0g3c", protocols.clone()) {
+            Err(NprintError::MalformedHex { position, .. }) => assert_eq!(position, 6),
+            other => panic!(
+                "Expected an invalid digit, got {:?}",
+                other.map(|n| n.count())
+            ),
+        }
+        match Nprint::from_hex("4500 003", protocols) {
+            Err(NprintError::MalformedHex { position, .. }) => assert_eq!(position, 8),
+            other => panic!(
+                "Expected an odd number of digits, got {:?}",
+                other.map(|n| n.count())
+            ),
+        }
+    }
+
+    #[test]
+    fn test_nprint_port_categories() {
+        // Source port 38820 (registered), destination port 443 (well-known)
+        let packet: Vec<u8> = [
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ]
+        .to_vec();
+        let mut nprint = NprintBuilder::new()
+            .protocols(vec![ProtocolType::Tcp])
+            .port_categories(true)
+            .anonymize(true)
+            .build_from(&packet);
+
+        let headers = nprint.get_headers();
+        assert_eq!(headers.len(), 480 - 2 * 14);
+        assert_eq!(
+            headers[..4],
+            [
+                "tcp_sprt_range0",
+                "tcp_sprt_range1",
+                "tcp_dprt_range0",
+                "tcp_dprt_range1"
+            ]
+        );
+        // Kept by the anonymization, the ranges no longer identifying the hosts
+        assert_eq!(nprint.print()[..4], [0., 1., 0., 0.]);
+        nprint.anonymize_with_key(&[0x2a; 16]);
+        assert_eq!(nprint.print()[..4], [0., 1., 0., 0.]);
+        assert_eq!(
+            nprint.print()[4..],
+            Nprint::new(&packet, vec![ProtocolType::Tcp]).print()[32..],
+            "Expected the other fields untouched"
+        );
+    }
+
+    #[test]
+    fn test_nprint_payload_source() {
+        // IPv4 carrying an ICMP echo request
+        let mut packet = vec![0x0; 12];
+        packet.extend([0x08, 0x00]);
+        packet.extend([
+            0x45, 0x00, 0x00, 0x1c, 0x00, 0x01, 0x00, 0x00, 0x40, 0x01, 0x00, 0x00, 0x0a, 0x00,
+            0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+        ]);
+        let icmp = [0x08, 0x00, 0xf7, 0xfe, 0x00, 0x01, 0x00, 0x00];
+        packet.extend(icmp);
+        let build = |source: PayloadSource| {
+            NprintBuilder::new()
+                .protocols(vec![ProtocolType::Payload])
+                .max_payload_bytes(8)
+                .payload_source(source)
+                .build_from(&packet)
+                .print()
+        };
+
+        assert_eq!(
+            build(PayloadSource::TransportPayload),
+            vec![-1.; 64],
+            "Expected no transport payload"
+        );
+        let bits: Vec<f32> = icmp
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| f32::from((byte >> i) & 1)))
+            .collect();
+        assert_eq!(build(PayloadSource::IpPayload), bits);
+    }
+
+    #[test]
+    fn test_protocol_type_cache_key() {
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp];
+        let mut cache: std::collections::HashMap<Vec<ProtocolType>, Vec<String>> =
+            std::collections::HashMap::new();
+        let headers = cache
+            .entry(protocols.clone())
+            .or_insert_with(|| Nprint::new(&[], protocols.clone()).get_headers().to_vec());
+        assert_eq!(headers.len(), 960);
+        assert!(cache.contains_key(&[ProtocolType::Ipv4, ProtocolType::Tcp][..]));
+        assert!(!cache.contains_key(&[ProtocolType::Tcp, ProtocolType::Ipv4][..]));
+
+        let copied = protocols[0];
+        assert_eq!(copied, ProtocolType::Ipv4);
+    }
+
+    #[test]
+    fn test_nprint_typed_getters() {
+        let packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let mut nprint = NprintBuilder::new()
+            .protocols(vec![
+                ProtocolType::Ipv4,
+                ProtocolType::Tcp,
+                ProtocolType::Udp,
+            ])
+            .fill_value(FillValue::Zero)
+            .bit_order(BitOrder::Lsb0)
+            .build_from(&packet);
+        nprint.add(&[0x0]);
+
+        assert_eq!(nprint.ipv4_ttl(0), Some(64));
+        assert_eq!(nprint.tcp_window(0), Some(0x7210));
+        assert_eq!(nprint.tcp_flags(0), Some(0x02), "Expected a SYN");
+        // Absent headers, although filled with valid bits
+        assert_eq!(nprint.ipv4_ttl(1), None);
+        assert_eq!(nprint.tcp_flags(1), None);
+        assert_eq!(nprint.tcp_window(2), None);
+    }
+
+    #[test]
+    fn test_nprint_empty() {
+        let packets: Vec<Vec<u8>> = vec![vec![0x0; 14], vec![0x1; 14], vec![0x2; 14]];
+        let protocols = vec![ProtocolType::Ethernet, ProtocolType::Ipv4];
+
+        let mut nprint = Nprint::empty(protocols.clone());
+        assert_eq!(nprint.count(), 0);
+        assert!(nprint.print().is_empty());
+        for packet in &packets {
+            nprint.add(packet);
+        }
+
+        let mut expected = Nprint::new(&packets[0], protocols.clone());
+        for packet in &packets[1..] {
+            expected.add(packet);
+        }
+        assert_eq!(nprint.count(), 3);
+        assert_eq!(nprint.print(), expected.print());
+
+        let built = NprintBuilder::new()
+            .protocols(protocols)
+            .fill_value(FillValue::Zero)
+            .build();
+        assert_eq!(built.count(), 0);
+        assert!(built.print().is_empty());
+    }
+
+    #[test]
+    fn test_nprint_from_offset() {
+        let ip = [
+            0x45, 0x00, 0x00, 0x28, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8,
+            0x2b, 0x25, 0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b,
+            0x00, 0x00, 0x00, 0x00, 0x50, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00,
+        ];
+        let mut ethernet = vec![0x0; 12];
+        ethernet.extend([0x08, 0x00]);
+        ethernet.extend(ip);
+        // Bytes of some link layer unknown to the crate, located by another dissector
+        let mut custom = vec![0xab; 9];
+        custom.extend(ip);
+        let protocols = vec![
+            ProtocolType::Ethernet,
+            ProtocolType::Ipv4,
+            ProtocolType::Tcp,
+        ];
+
+        let mut nprint = Nprint::from_offset(&custom, 9, protocols.clone());
+        nprint.add_from_offset(&ethernet, 14);
+        let expected = Nprint::new(&ethernet, protocols.clone()).print()[112..].to_vec();
+        for packet in nprint.iter_packets() {
+            assert_eq!(packet[..112], [-1.; 112], "Expected no Ethernet header");
+            assert_eq!(packet[112..], expected);
+        }
+
+        let beyond = Nprint::from_offset(&ethernet, ethernet.len() + 1, protocols);
+        assert_eq!(beyond.count(), 1);
+        assert_eq!(beyond.is_parsed(0, ProtocolType::Ipv4), Some(false));
+    }
+
+    #[test]
+    fn test_nprint_options_indicator() {
+        // IPv4 without options, TCP with 20 bytes of options
+        let packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x3c, 0xf5, 0x1b, 0x40, 0x00, 0x40, 0x06, 0x1b, 0x24, 0xc0, 0xa8, 0x2b, 0x25,
+            0xc6, 0x26, 0x78, 0x88, 0x97, 0xa4, 0x01, 0xbb, 0x96, 0x2e, 0x5e, 0x0b, 0x00, 0x00,
+            0x00, 0x00, 0xa0, 0x02, 0x72, 0x10, 0x25, 0xd4, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+            0x04, 0x02, 0x08, 0x0a, 0xe3, 0xe2, 0x14, 0x23, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03,
+            0x03, 0x07,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp];
+        let mut nprint = NprintBuilder::new()
+            .protocols(protocols.clone())
+            .options_indicator(true)
+            .tcp_flags(TcpFlagsEncoding::BitsAndValue)
+            .build_from(&packet);
+        nprint.add(&[0x0]);
+
+        let headers = nprint.get_headers();
+        assert_eq!(headers.len(), 2 * 480 + 3);
+        assert_eq!(
+            headers[159..162],
+            ["ipv4_dst_31", "ipv4_has_opt_0", "ipv4_opt_0"]
+        );
+        assert_eq!(headers[481], "tcp_sprt_0");
+        assert_eq!(
+            headers[640..643],
+            ["tcp_urp_15", "tcp_has_opt_0", "tcp_opt_0"]
+        );
+        assert_eq!(headers[962], "tcp_flags_value_0");
+
+        let rows: Vec<Vec<f32>> = nprint.iter_packets().collect();
+        assert_eq!(rows[0][160], 0., "Expected no IPv4 options");
+        assert_eq!(rows[0][641], 1., "Expected TCP options");
+        assert_eq!(rows[0][962], 2., "Expected a SYN");
+        assert_eq!(rows[1][160], -1., "Expected an absent IPv4 header");
+        // The other bits are left untouched
+        let plain = Nprint::new(&packet, protocols.clone()).print();
+        assert_eq!(rows[0][..160], plain[..160]);
+        assert_eq!(rows[0][161..641], plain[160..640]);
+        assert_eq!(rows[0][642..962], plain[640..]);
+        assert_eq!(
+            nprint.header_bytes(0, ProtocolType::Ipv4),
+            Some(packet[14..34].to_vec())
+        );
+        assert_eq!(
+            nprint.header_bytes(0, ProtocolType::Tcp),
+            Some(packet[34..].to_vec()),
+            "Expected the options without the indicator"
+        );
+        assert!(nprint.validate().is_ok());
+
+        for (field, value) in [("ipv4_has_opt", 0.), ("tcp_has_opt", 1.)] {
+            let selected = NprintBuilder::new()
+                .protocols(protocols.clone())
+                .options_indicator(true)
+                .select_fields(&[field])
+                .build_from(&packet);
+            assert_eq!(selected.get_headers(), vec![format!("{}_0", field)]);
+            assert_eq!(selected.print(), vec![value], "Wrong {}", field);
+        }
+    }
+
+    #[test]
+    fn test_nprint_from_csv_with_config() {
+        let raw_packet = vec![
+            0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x08, 0x00, 0x45, 0x00,
+            0x00, 0x1c, 0x6f, 0xcd, 0x40, 0x00, 0x40, 0x11, 0x46, 0x1d, 0xac, 0x10, 0x0c, 0x9b,
+            0xac, 0x10, 0x1f, 0xff, 0xe1, 0x15, 0xe1, 0x15, 0x00, 0x08, 0x85, 0x00,
+        ];
+        let protocols = vec![ProtocolType::Ipv4, ProtocolType::Tcp, ProtocolType::Udp];
+        let config = NprintConfig {
+            fields: Some(vec![
+                "ipv4_ttl".to_string(),
+                "tcp_sprt".to_string(),
+                "udp_dport".to_string(),
+            ]),
+            fill_value: FillValue::NaN,
+            ..Default::default()
+        };
+        let mut nprint = Nprint::new_with_config(&raw_packet, protocols.clone(), config.clone());
+        nprint.add(&[0x0]);
+        let csv = nprint.to_csv_string();
+
+        assert!(
+            matches!(
+                Nprint::from_csv(csv.as_bytes(), protocols.clone()),
+                Err(NprintError::SchemaMismatch { .. })
+            ),
+            "Expected the default columns to differ"
+        );
+        let read = Nprint::from_csv_with_config(csv.as_bytes(), protocols, config).unwrap();
+        assert_eq!(read.count(), 2);
+        assert_eq!(read.get_headers(), nprint.get_headers());
+        assert_eq!(read.to_csv_string(), csv, "Expected the same CSV back");
+        assert_eq!(read.is_parsed(0, ProtocolType::Ipv4), Some(true));
+        assert_eq!(read.is_parsed(0, ProtocolType::Tcp), Some(false));
+        assert_eq!(read.is_parsed(0, ProtocolType::Udp), Some(true));
+        assert_eq!(read.is_parsed(1, ProtocolType::Ipv4), Some(false));
+    }
+
     // Big constant placed here, at the end for reusability and readability
     const HEADER_IP: [&str; 480] = [
         "ipv4_ver_0",
@@ -281,14 +3016,14 @@ mod nprint_tests {
         "ipv4_hl_1",
         "ipv4_hl_2",
         "ipv4_hl_3",
-        "ipv4_tos_0",
-        "ipv4_tos_1",
-        "ipv4_tos_2",
-        "ipv4_tos_3",
-        "ipv4_tos_4",
-        "ipv4_tos_5",
-        "ipv4_tos_6",
-        "ipv4_tos_7",
+        "ipv4_dscp_0",
+        "ipv4_dscp_1",
+        "ipv4_dscp_2",
+        "ipv4_dscp_3",
+        "ipv4_dscp_4",
+        "ipv4_dscp_5",
+        "ipv4_ecn_0",
+        "ipv4_ecn_1",
         "ipv4_tl_0",
         "ipv4_tl_1",
         "ipv4_tl_2",